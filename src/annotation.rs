@@ -0,0 +1,637 @@
+/**********************************************************************************
+ * Copyright © 2022 Michael Volling
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy of
+ * this software and associated documentation files (the “Software”), to deal in
+ * the Software without restriction, including without limitation the rights to
+ * use, copy, modify, merge, publish, distribute, sublicense, and/or sell copies of
+ * the Software, and to permit persons to whom the Software is furnished to do so,
+ * subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED “AS IS”, WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY, FITNESS
+ * FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE AUTHORS OR
+ * COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER
+ * IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN
+ * CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+ *********************************************************************************/
+
+use chumsky::prelude::*;
+
+use crate::ast::Annotation;
+use crate::expr::{BinaryOp, ConstExpr, ConstValue};
+use crate::name::ScopedName;
+use crate::primitive::PrimitiveType;
+use crate::scope::{with_article, Scope};
+
+/// The Target enum lists the kinds of element an annotation may be applied to
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Target {
+    Module,
+    TemplateModule,
+    Const,
+    Typedef,
+    Struct,
+    /// A member of a struct or exception
+    Member,
+    Union,
+    /// An element of a union
+    Case,
+    Enum,
+    Enumerator,
+    Bitmask,
+    BitValue,
+    Bitset,
+    Bitfield,
+    Native,
+    Exception,
+    Interface,
+    Operation,
+    /// A parameter of an operation or factory
+    Param,
+    Attribute,
+    ValueType,
+    EventType,
+    StateMember,
+    /// A factory of a value type, or a factory or finder of a home
+    Factory,
+    ValueBox,
+    Component,
+    Port,
+    Home,
+    PortType,
+    Connector,
+    Forward,
+}
+
+impl Target {
+    /// Returns a short description of the kind of element, for use in diagnostics
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Target::Module => "module",
+            Target::TemplateModule => "template module",
+            Target::Const => "constant",
+            Target::Typedef => "typedef",
+            Target::Struct => "struct",
+            Target::Member => "member",
+            Target::Union => "union",
+            Target::Case => "union element",
+            Target::Enum => "enum",
+            Target::Enumerator => "enumerator",
+            Target::Bitmask => "bitmask",
+            Target::BitValue => "bit value",
+            Target::Bitset => "bitset",
+            Target::Bitfield => "bitfield",
+            Target::Native => "native type",
+            Target::Exception => "exception",
+            Target::Interface => "interface",
+            Target::Operation => "operation",
+            Target::Param => "parameter",
+            Target::Attribute => "attribute",
+            Target::ValueType => "value type",
+            Target::EventType => "event type",
+            Target::StateMember => "state member",
+            Target::Factory => "factory",
+            Target::ValueBox => "value box",
+            Target::Component => "component",
+            Target::Port => "port",
+            Target::Home => "home",
+            Target::PortType => "porttype",
+            Target::Connector => "connector",
+            Target::Forward => "forward declaration",
+        }
+    }
+
+    /// Returns the kind of the element preceded by an article, for use in
+    /// diagnostics
+    pub fn description(&self) -> String {
+        with_article(self.kind())
+    }
+}
+
+/// The ParamType enum represents the type of a parameter of a built-in annotation
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ParamType {
+    Boolean,
+    /// An integer of the given type
+    Integer(PrimitiveType),
+    String,
+    /// One of the enumerators of an enum declared along with the annotation
+    Enum(&'static [&'static str]),
+    /// The flags of a bitmask declared along with the annotation, combined with `|`
+    Mask(&'static [&'static str]),
+    /// A constant of any type, which is usually that of the annotated element
+    Any,
+}
+
+/// The BuiltinParam type represents a parameter of a built-in annotation
+#[derive(Debug, Clone, PartialEq)]
+pub struct BuiltinParam {
+    pub name: &'static str,
+    pub ty: ParamType,
+    /// Whether the parameter must be given, as it has no default value
+    pub required: bool,
+}
+
+/// The BuiltinAnnotation type represents one of the standard annotations of IDL
+/// and XTypes, along with the elements it may be applied to
+#[derive(Debug, Clone, PartialEq)]
+pub struct BuiltinAnnotation {
+    pub name: &'static str,
+    pub params: &'static [BuiltinParam],
+    /// The kinds of element the annotation may be applied to, where an empty list
+    /// allows any element
+    pub targets: &'static [Target],
+}
+
+const fn param(name: &'static str, ty: ParamType, required: bool) -> BuiltinParam {
+    BuiltinParam { name, ty, required }
+}
+
+const fn builtin(
+    name: &'static str,
+    params: &'static [BuiltinParam],
+    targets: &'static [Target],
+) -> BuiltinAnnotation {
+    BuiltinAnnotation {
+        name,
+        params,
+        targets,
+    }
+}
+
+/// The boolean parameter of the annotations that switch a property on, such as
+/// `@key`, which defaults to TRUE
+const SWITCH: &[BuiltinParam] = &[param("value", ParamType::Boolean, false)];
+
+const ANY: &[BuiltinParam] = &[param("value", ParamType::Any, true)];
+
+const AGGREGATES: &[Target] = &[Target::Struct, Target::Union];
+const TYPES: &[Target] = &[Target::Struct, Target::Union, Target::Enum, Target::Bitmask];
+const MEMBERS: &[Target] = &[Target::Member, Target::Case];
+const VALUES: &[Target] = &[Target::Member, Target::Case, Target::Typedef];
+
+/// The built-in annotations of IDL 4.2 (8.3) and XTypes 1.3 (7.3.1.2.1)
+pub const BUILTINS: &[BuiltinAnnotation] = &[
+    // 8.3.1 group of annotations general purpose
+    builtin(
+        "id",
+        &[param(
+            "value",
+            ParamType::Integer(PrimitiveType::UnsignedLong),
+            true,
+        )],
+        MEMBERS,
+    ),
+    builtin(
+        "autoid",
+        &[param(
+            "value",
+            ParamType::Enum(&["SEQUENTIAL", "HASH"]),
+            false,
+        )],
+        &[Target::Module, Target::Struct, Target::Union],
+    ),
+    builtin("optional", SWITCH, &[Target::Member]),
+    builtin(
+        "position",
+        &[param(
+            "value",
+            ParamType::Integer(PrimitiveType::UnsignedShort),
+            true,
+        )],
+        &[Target::BitValue],
+    ),
+    builtin("value", ANY, &[Target::Enumerator]),
+    builtin(
+        "extensibility",
+        &[param(
+            "kind",
+            ParamType::Enum(&["FINAL", "APPENDABLE", "MUTABLE"]),
+            true,
+        )],
+        TYPES,
+    ),
+    builtin("final", &[], TYPES),
+    builtin("appendable", &[], TYPES),
+    builtin("mutable", &[], TYPES),
+    // 8.3.2 group of annotations data modeling
+    builtin("key", SWITCH, MEMBERS),
+    builtin("must_understand", SWITCH, MEMBERS),
+    builtin("default_literal", &[], &[Target::Enumerator]),
+    // 8.3.3 group of annotations units and ranges
+    builtin("default", ANY, VALUES),
+    builtin(
+        "range",
+        &[
+            param("min", ParamType::Any, true),
+            param("max", ParamType::Any, true),
+        ],
+        VALUES,
+    ),
+    builtin("min", ANY, VALUES),
+    builtin("max", ANY, VALUES),
+    builtin("unit", &[param("value", ParamType::String, true)], VALUES),
+    // 8.3.4 group of annotations data implementation
+    builtin(
+        "bit_bound",
+        &[param(
+            "value",
+            ParamType::Integer(PrimitiveType::UnsignedShort),
+            true,
+        )],
+        &[Target::Enum, Target::Bitmask],
+    ),
+    builtin("external", SWITCH, MEMBERS),
+    builtin("nested", SWITCH, AGGREGATES),
+    // 8.3.5 group of annotations code generation
+    builtin(
+        "verbatim",
+        &[
+            param("language", ParamType::String, false),
+            param(
+                "placement",
+                ParamType::Enum(&[
+                    "BEGIN_FILE",
+                    "BEFORE_DECLARATION",
+                    "BEGIN_DECLARATION",
+                    "END_DECLARATION",
+                    "AFTER_DECLARATION",
+                    "END_FILE",
+                ]),
+                false,
+            ),
+            param("text", ParamType::String, true),
+        ],
+        &[],
+    ),
+    // 8.3.6 group of annotations interfaces
+    builtin(
+        "service",
+        &[param("platform", ParamType::String, false)],
+        &[Target::Interface],
+    ),
+    builtin("oneway", SWITCH, &[Target::Operation]),
+    builtin("ami", SWITCH, &[Target::Interface, Target::Operation]),
+    // XTypes 7.3.1.2.1
+    builtin(
+        "hashid",
+        &[param("value", ParamType::String, false)],
+        MEMBERS,
+    ),
+    builtin(
+        "topic",
+        &[
+            param("name", ParamType::String, false),
+            param("platform", ParamType::String, false),
+        ],
+        AGGREGATES,
+    ),
+    builtin(
+        "try_construct",
+        &[param(
+            "value",
+            ParamType::Enum(&["DISCARD", "USE_DEFAULT", "TRIM"]),
+            false,
+        )],
+        VALUES,
+    ),
+    builtin(
+        "data_representation",
+        &[param(
+            "allowed_kinds",
+            ParamType::Mask(&["XCDR1", "XML", "XCDR2"]),
+            true,
+        )],
+        AGGREGATES,
+    ),
+    builtin("non_serialized", SWITCH, MEMBERS),
+    builtin("ignore_literal_names", SWITCH, &[Target::Enum]),
+    builtin("default_nested", SWITCH, &[Target::Module]),
+];
+
+impl BuiltinAnnotation {
+    /// Returns the built-in annotation called `name`, if there is one
+    ///
+    /// Example
+    ///
+    /// ```
+    /// use ox_idl::annotation::{BuiltinAnnotation, Target};
+    /// use ox_idl::name::ScopedName;
+    ///
+    /// let key = BuiltinAnnotation::lookup(&ScopedName::from("key")).unwrap();
+    /// assert!(key.applies_to(Target::Member));
+    /// assert!(!key.applies_to(Target::Enumerator));
+    /// assert!(BuiltinAnnotation::lookup(&ScopedName::from("M::key")).is_none());
+    /// ```
+    pub fn lookup(name: &ScopedName) -> Option<&'static BuiltinAnnotation> {
+        // The built-in annotations are declared at the root, so they may only be
+        // referred to by their name, optionally prefixed with `::`
+        match name.parts.as_slice() {
+            [name] => BUILTINS.iter().find(|b| b.name == name.as_str()),
+            _ => None,
+        }
+    }
+
+    /// Returns whether the annotation may be applied to the given kind of element
+    pub fn applies_to(&self, target: Target) -> bool {
+        self.targets.is_empty() || self.targets.contains(&target)
+    }
+
+    /// Matches the parameters given to an application of the annotation with the
+    /// parameters it declares, returning the expression given for each of them in
+    /// the order they are declared, or None if it is left to its default
+    ///
+    /// Example
+    ///
+    /// ```
+    /// use ox_idl::annotation::BuiltinAnnotation;
+    /// use ox_idl::ast::Annotation;
+    /// use ox_idl::name::ScopedName;
+    /// use chumsky::prelude::*;
+    ///
+    /// let range = BuiltinAnnotation::lookup(&ScopedName::from("range")).unwrap();
+    /// let a = Annotation::parser().parse("@range(max = 10, min = 0)").unwrap();
+    /// let params = range.bind(&a).unwrap();
+    /// assert_eq!(params[0].unwrap().span(), 23..24);
+    ///
+    /// let a = Annotation::parser().parse("@range(max = 10)").unwrap();
+    /// assert!(range.bind(&a).is_err());
+    /// ```
+    pub fn bind<'a>(
+        &self,
+        annotation: &'a Annotation,
+    ) -> Result<Vec<Option<&'a ConstExpr>>, Simple<char>> {
+        let error = |span, message| Err(Simple::custom(span, message));
+        let count = |n: usize| match n {
+            0 => "no parameters".to_string(),
+            1 => "exactly one parameter".to_string(),
+            n => format!("at most {} parameters", n),
+        };
+
+        let mut bound: Vec<Option<&ConstExpr>> = vec![None; self.params.len()];
+        let mut named = false;
+        for (index, param) in annotation.params.iter().enumerate() {
+            let position = match &param.name {
+                Some(name) => {
+                    named = true;
+                    match self.params.iter().position(|p| p.name == name.as_str()) {
+                        Some(position) => position,
+                        None => {
+                            return error(
+                                param.span.clone(),
+                                format!("@{} has no parameter `{}`", self.name, name),
+                            )
+                        }
+                    }
+                }
+                None if named => {
+                    return error(
+                        param.span.clone(),
+                        format!(
+                            "the parameters of @{} cannot be given by position after one is given by name",
+                            self.name
+                        ),
+                    )
+                }
+                None if index >= self.params.len() => {
+                    return error(
+                        annotation.span.clone(),
+                        format!("@{} takes {}", self.name, count(self.params.len())),
+                    )
+                }
+                None => index,
+            };
+            if bound[position].is_some() {
+                return error(
+                    param.span.clone(),
+                    format!(
+                        "`{}` of @{} is given more than once",
+                        self.params[position].name, self.name
+                    ),
+                );
+            }
+            bound[position] = Some(&param.value);
+        }
+
+        match self
+            .params
+            .iter()
+            .zip(&bound)
+            .find(|(p, b)| p.required && b.is_none())
+        {
+            Some(_) if self.params.len() == 1 => error(
+                annotation.span.clone(),
+                format!("@{} takes exactly one parameter", self.name),
+            ),
+            Some((missing, _)) => error(
+                annotation.span.clone(),
+                format!("@{} requires a value for `{}`", self.name, missing.name),
+            ),
+            None => Ok(bound),
+        }
+    }
+}
+
+impl Annotation {
+    /// Returns the built-in annotation this annotation applies, if any
+    pub fn builtin(&self) -> Option<&'static BuiltinAnnotation> {
+        BuiltinAnnotation::lookup(&self.name)
+    }
+}
+
+/// Checks that each built-in annotation applied to an element may be applied to
+/// its kind of element and is given parameters of the right type. Annotations
+/// that are not built in are left unchecked, as their definitions are not known.
+pub(crate) fn check_annotations(
+    scope: &Scope,
+    target: Target,
+    annotations: &[Annotation],
+    errors: &mut Vec<Simple<char>>,
+) {
+    for annotation in annotations {
+        let builtin = match annotation.builtin() {
+            Some(builtin) => builtin,
+            None => continue,
+        };
+        if !builtin.applies_to(target) {
+            errors.push(Simple::custom(
+                annotation.span.clone(),
+                format!(
+                    "@{} cannot be applied to {}",
+                    builtin.name,
+                    target.description()
+                ),
+            ));
+            continue;
+        }
+
+        // The parameters of @value, @position and the @bit_bound of a bitmask are
+        // already evaluated when the symbol table is built
+        if matches!(
+            (builtin.name, target),
+            ("value", Target::Enumerator)
+                | ("position", Target::BitValue)
+                | ("bit_bound", Target::Bitmask)
+        ) {
+            continue;
+        }
+
+        let params = match builtin.bind(annotation) {
+            Ok(params) => params,
+            Err(error) => {
+                errors.push(error);
+                continue;
+            }
+        };
+        for (param, value) in builtin.params.iter().zip(params) {
+            if let Some(value) = value {
+                check_param(scope, builtin, param, value, errors);
+            }
+        }
+    }
+}
+
+/// Checks that the value given to a parameter of a built-in annotation is of the
+/// type of the parameter
+fn check_param(
+    scope: &Scope,
+    builtin: &BuiltinAnnotation,
+    param: &BuiltinParam,
+    value: &ConstExpr,
+    errors: &mut Vec<Simple<char>>,
+) {
+    let result = match param.ty {
+        ParamType::Boolean => value.evaluate_as(PrimitiveType::Boolean, scope).map(drop),
+        ParamType::Integer(ty) => value.evaluate_as(ty, scope).map(drop),
+        ParamType::String => match value.evaluate(scope) {
+            Ok(ConstValue::Str(_)) => Ok(()),
+            Ok(other) => Err(Simple::custom(
+                value.span(),
+                format!("expected a string, found {} value {}", other.kind(), other),
+            )),
+            Err(error) => Err(error),
+        },
+        ParamType::Enum(names) => match value {
+            ConstExpr::Name(name, _) if is_one_of(name, names) => Ok(()),
+            _ => Err(Simple::custom(
+                value.span(),
+                format!(
+                    "`{}` of @{} must be one of {}",
+                    param.name,
+                    builtin.name,
+                    names.join(", ")
+                ),
+            )),
+        },
+        ParamType::Mask(flags) if is_mask_of(value, flags) => Ok(()),
+        ParamType::Mask(flags) => Err(Simple::custom(
+            value.span(),
+            format!(
+                "`{}` of @{} must be a combination of {}",
+                param.name,
+                builtin.name,
+                flags.join(", ")
+            ),
+        )),
+        ParamType::Any => value.evaluate(scope).map(drop),
+    };
+    if let Err(error) = result {
+        errors.push(error);
+    }
+}
+
+/// Returns whether `name` is one of the unqualified names given
+fn is_one_of(name: &ScopedName, names: &[&str]) -> bool {
+    !name.absolute && matches!(name.parts.as_slice(), [part] if names.contains(&part.as_str()))
+}
+
+/// Returns whether the expression is one or more of the given flags combined with `|`
+fn is_mask_of(value: &ConstExpr, flags: &[&str]) -> bool {
+    match value {
+        ConstExpr::Name(name, _) => is_one_of(name, flags),
+        ConstExpr::Binary(BinaryOp::Or, left, right, _) => {
+            is_mask_of(left, flags) && is_mask_of(right, flags)
+        }
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod annotation_tests {
+    use crate::annotation::{BuiltinAnnotation, Target, BUILTINS};
+    use crate::ast::Annotation;
+    use crate::name::ScopedName;
+    use crate::test_utils::message;
+    use chumsky::prelude::*;
+
+    fn bind(source: &str) -> Result<Vec<Option<std::ops::Range<usize>>>, String> {
+        let annotation = Annotation::parser().parse(source).unwrap();
+        match annotation.builtin().unwrap().bind(&annotation) {
+            Ok(params) => Ok(params.into_iter().map(|p| p.map(|e| e.span())).collect()),
+            Err(error) => Err(message(&error)),
+        }
+    }
+
+    #[test]
+    fn lookup_builtins() {
+        let lookup = |name: &str| BuiltinAnnotation::lookup(&ScopedName::from(name));
+        assert_eq!(lookup("::optional").unwrap().name, "optional");
+        assert!(lookup("Optional").is_none());
+        assert!(lookup("custom").is_none());
+        assert!(lookup("verbatim").unwrap().applies_to(Target::Forward));
+        assert!(!lookup("oneway").unwrap().applies_to(Target::Interface));
+
+        for (index, builtin) in BUILTINS.iter().enumerate() {
+            assert!(BUILTINS[..index].iter().all(|b| b.name != builtin.name));
+        }
+    }
+
+    #[test]
+    fn bind_params() {
+        assert_eq!(bind("@key"), Ok(vec![None]));
+        assert_eq!(bind("@id(5)"), Ok(vec![Some(4..5)]));
+        assert_eq!(bind("@id(value = 5)"), Ok(vec![Some(12..13)]));
+        assert_eq!(bind("@range(0, 10)"), Ok(vec![Some(7..8), Some(10..12)]));
+        assert_eq!(
+            bind("@verbatim(\"c\", text = \"x\")"),
+            Ok(vec![Some(10..13), None, Some(22..25)])
+        );
+
+        assert_eq!(bind("@final()"), Ok(vec![]));
+        assert_eq!(
+            bind("@final(TRUE)"),
+            Err("@final takes no parameters".to_string())
+        );
+        assert_eq!(
+            bind("@id"),
+            Err("@id takes exactly one parameter".to_string())
+        );
+        assert_eq!(
+            bind("@range(0, 1, 2)"),
+            Err("@range takes at most 2 parameters".to_string())
+        );
+        assert_eq!(
+            bind("@topic(platform = \"*\", \"t\")"),
+            Err(
+                "the parameters of @topic cannot be given by position after one is given by name"
+                    .to_string()
+            )
+        );
+        assert_eq!(
+            bind("@range(1, min = 0)"),
+            Err("`min` of @range is given more than once".to_string())
+        );
+        assert_eq!(
+            bind("@unit(units = \"m\")"),
+            Err("@unit has no parameter `units`".to_string())
+        );
+        assert_eq!(
+            bind("@verbatim(language = \"c\")"),
+            Err("@verbatim requires a value for `text`".to_string())
+        );
+    }
+}
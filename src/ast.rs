@@ -0,0 +1,3811 @@
+/**********************************************************************************
+ * Copyright © 2022 Michael Volling
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy of
+ * this software and associated documentation files (the “Software”), to deal in
+ * the Software without restriction, including without limitation the rights to
+ * use, copy, modify, merge, publish, distribute, sublicense, and/or sell copies of
+ * the Software, and to permit persons to whom the Software is furnished to do so,
+ * subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED “AS IS”, WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY, FITNESS
+ * FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE AUTHORS OR
+ * COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER
+ * IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN
+ * CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+ *********************************************************************************/
+
+use chumsky::prelude::*;
+
+use std::ops::Range;
+
+use crate::expr::ConstExpr;
+use crate::keyword::Keyword;
+use crate::lexer::{keyword, padding, symbol, token};
+use crate::name::{Identifier, ScopedName};
+use crate::primitive::PrimitiveType;
+
+/// The Specification type represents a complete IDL file, which is a list of
+/// definitions
+#[derive(Debug, Clone, PartialEq)]
+pub struct Specification {
+    /// The top level definitions, in the order they appear
+    pub definitions: Vec<Definition>,
+}
+
+impl Specification {
+    /// Builds a parser is able to parse a complete IDL specification as specified
+    /// in the IDL Documentation
+    ///
+    /// Example
+    ///
+    /// ```
+    /// use ox_idl::ast::{Definition, Specification};
+    /// use chumsky::prelude::*;
+    ///
+    /// let spec = Specification::parser()
+    ///     .parse("// Limits\nconst long MAX = 10;\nnative Handle;")
+    ///     .unwrap();
+    /// assert_eq!(spec.definitions.len(), 2);
+    /// assert!(matches!(spec.definitions[0], Definition::Const(_)));
+    /// assert!(matches!(spec.definitions[1], Definition::Native(_)));
+    /// ```
+    pub fn parser() -> impl Parser<char, Specification, Error = Simple<char>> {
+        // 7.4.1.3
+        // <specification> ::= <definition>+
+        padding()
+            .ignore_then(Definition::parser().repeated())
+            .then_ignore(end())
+            .map(|definitions| Specification { definitions })
+    }
+}
+
+/// The Definition enum represents the definitions that may appear at the top
+/// level of a specification or within a module
+#[derive(Debug, Clone, PartialEq)]
+pub enum Definition {
+    Module(Module),
+    TemplateModule(TemplateModule),
+    TemplateInstance(TemplateInstance),
+    Const(Const),
+    Typedef(Typedef),
+    Struct(Struct),
+    Union(Union),
+    Enum(Enum),
+    Bitmask(Bitmask),
+    Bitset(Bitset),
+    Native(Native),
+    Interface(Interface),
+    Exception(Exception),
+    ValueType(ValueType),
+    ValueBox(ValueBox),
+    Component(Component),
+    Home(Home),
+    PortType(PortType),
+    Connector(Connector),
+    Forward(Forward),
+}
+
+impl Definition {
+    /// Builds a parser is able to parse a single definition, including its
+    /// annotations and terminating semicolon, as specified in the IDL Documentation
+    ///
+    /// Example
+    ///
+    /// ```
+    /// use ox_idl::ast::Definition;
+    /// use chumsky::prelude::*;
+    ///
+    /// let d = Definition::parser().parse("native Handle;").unwrap();
+    /// assert_eq!(d.span(), 0..14);
+    /// ```
+    pub fn parser() -> impl Parser<char, Definition, Error = Simple<char>> + Clone {
+        // 7.4.1.3
+        // <definition> ::= <module_dcl> ";"
+        //                | <const_dcl> ";"
+        //                | <type_dcl> ";"
+        //
+        // 7.4.3.3
+        // <definition> ::= <except_dcl> ";" | <interface_dcl> ";"
+        //
+        // 7.4.5.3
+        // <definition> ::= <value_dcl> ";"
+        //
+        // 7.4.8.3
+        // <definition> ::= <component_dcl> ";"
+        //
+        // 7.4.9.3
+        // <definition> ::= <home_dcl> ";"
+        //
+        // 7.4.10.3
+        // <definition> ::= <event_dcl> ";"
+        //
+        // 7.4.11.3
+        // <definition> ::= <porttype_dcl> ";" | <connector_dcl> ";"
+        //
+        // 7.4.12.3
+        // <definition> ::= <template_module_dcl> ";" | <template_module_inst> ";"
+        recursive(|definition| {
+            choice((
+                Module::parser_with(definition.clone()).map(Definition::Module),
+                TemplateModule::parser_with(definition.clone()).map(Definition::TemplateModule),
+                TemplateInstance::parser().map(Definition::TemplateInstance),
+                Const::parser().map(Definition::Const),
+                Typedef::parser().map(Definition::Typedef),
+                Struct::parser().map(Definition::Struct),
+                Union::parser().map(Definition::Union),
+                Enum::parser().map(Definition::Enum),
+                Bitmask::parser().map(Definition::Bitmask),
+                Bitset::parser().map(Definition::Bitset),
+                Native::parser().map(Definition::Native),
+                Exception::parser().map(Definition::Exception),
+                Interface::parser_with(definition.clone()).map(Definition::Interface),
+                ValueType::parser_with(definition.clone()).map(Definition::ValueType),
+                ValueBox::parser().map(Definition::ValueBox),
+                Component::parser().map(Definition::Component),
+                Home::parser_with(definition).map(Definition::Home),
+                PortType::parser().map(Definition::PortType),
+                Connector::parser().map(Definition::Connector),
+                Forward::parser().map(Definition::Forward),
+            ))
+        })
+    }
+
+    /// Returns the span of the definition
+    pub fn span(&self) -> Range<usize> {
+        match self {
+            Definition::Module(d) => d.span.clone(),
+            Definition::TemplateModule(d) => d.span.clone(),
+            Definition::TemplateInstance(d) => d.span.clone(),
+            Definition::Const(d) => d.span.clone(),
+            Definition::Typedef(d) => d.span.clone(),
+            Definition::Struct(d) => d.span.clone(),
+            Definition::Union(d) => d.span.clone(),
+            Definition::Enum(d) => d.span.clone(),
+            Definition::Bitmask(d) => d.span.clone(),
+            Definition::Bitset(d) => d.span.clone(),
+            Definition::Native(d) => d.span.clone(),
+            Definition::Interface(d) => d.span.clone(),
+            Definition::Exception(d) => d.span.clone(),
+            Definition::ValueType(d) => d.span.clone(),
+            Definition::ValueBox(d) => d.span.clone(),
+            Definition::Component(d) => d.span.clone(),
+            Definition::Home(d) => d.span.clone(),
+            Definition::PortType(d) => d.span.clone(),
+            Definition::Connector(d) => d.span.clone(),
+            Definition::Forward(d) => d.span.clone(),
+        }
+    }
+}
+
+/// The Annotation type represents an annotation applied to a definition or
+/// member, such as `@key` or `@range(min = 0, max = 10)`
+#[derive(Debug, Clone, PartialEq)]
+pub struct Annotation {
+    /// The name of the annotation
+    pub name: ScopedName,
+    /// The parameters given to the annotation
+    pub params: Vec<AnnotationParam>,
+    pub span: Range<usize>,
+}
+
+/// The AnnotationParam type represents a parameter given to an annotation, which
+/// is either positional, as in `@id(5)`, or named, as in `@range(min = 0)`
+#[derive(Debug, Clone, PartialEq)]
+pub struct AnnotationParam {
+    /// The name of the parameter, or None if it is given by position
+    pub name: Option<Identifier>,
+    pub value: ConstExpr,
+    pub span: Range<usize>,
+}
+
+impl Annotation {
+    /// Builds a parser is able to parse an annotation application as specified in
+    /// the IDL Documentation
+    ///
+    /// Example
+    ///
+    /// ```
+    /// use ox_idl::ast::Annotation;
+    /// use ox_idl::name::ScopedName;
+    /// use chumsky::prelude::*;
+    ///
+    /// let a = Annotation::parser().parse("@default(5)").unwrap();
+    /// assert_eq!(a.name, ScopedName::from("default"));
+    /// assert_eq!(a.params.len(), 1);
+    /// assert_eq!(a.span, 0..11);
+    ///
+    /// let a = Annotation::parser().parse("@range(min = 0, max = 10)").unwrap();
+    /// assert_eq!(a.params[1].name.as_ref().unwrap().as_str(), "max");
+    /// assert_eq!(a.params[1].span, 16..24);
+    /// ```
+    pub fn parser() -> impl Parser<char, Annotation, Error = Simple<char>> {
+        // 7.4.15.4.2
+        // <annotation_appl> ::= "@" <scoped_name> [ "(" <annotation_appl_params> ")" ]
+        //
+        // Annotation names are not checked against the keywords, as built-in
+        // annotations such as @default share their name with a keyword.
+        let name = symbol("::")
+            .or_not()
+            .then(
+                text::ident()
+                    .separated_by(ScopedName::separator())
+                    .at_least(1),
+            )
+            .map(|(root, parts)| ScopedName {
+                absolute: root.is_some(),
+                parts,
+            });
+
+        // <annotation_appl_params> ::= <const_expr>
+        //                            | <annotation_appl_param> { "," <annotation_appl_param> }*
+        // <annotation_appl_param> ::= <identifier> "=" <const_expr>
+        //
+        // Several positional parameters are accepted as well, so that the number of
+        // parameters can be checked against the annotation.
+        let param = token(Identifier::parser())
+            .then_ignore(symbol("="))
+            .or_not()
+            .then(ConstExpr::parser())
+            .map_with_span(|(name, value), span: Range<usize>| AnnotationParam {
+                name,
+                span: span.start..value.span().end,
+                value,
+            });
+
+        let params = symbol("(")
+            .ignore_then(param.separated_by(symbol(",")))
+            .then_ignore(just(')'));
+
+        token(
+            just('@')
+                .ignore_then(name)
+                .then(padding().ignore_then(params).or_not())
+                .map_with_span(|(name, params), span| Annotation {
+                    name,
+                    params: params.unwrap_or_default(),
+                    span,
+                }),
+        )
+    }
+
+    /// Returns true if both annotations are written alike, with the same name and
+    /// parameters, regardless of where they appear in the source
+    ///
+    /// Example
+    ///
+    /// ```
+    /// use ox_idl::ast::Annotation;
+    /// use chumsky::prelude::*;
+    ///
+    /// let a = Annotation::parser().parse("@range(min = 1)").unwrap();
+    /// let b = Annotation::parser().parse("@range( min = 1 )").unwrap();
+    /// let c = Annotation::parser().parse("@range(min = 2)").unwrap();
+    /// assert!(a.same_as(&b));
+    /// assert!(!a.same_as(&c));
+    /// ```
+    pub fn same_as(&self, other: &Annotation) -> bool {
+        self.name == other.name
+            && self.params.len() == other.params.len()
+            && self
+                .params
+                .iter()
+                .zip(&other.params)
+                .all(|(a, b)| a.name == b.name && a.value.same_as(&b.value))
+    }
+}
+
+/// The Reference type represents a scoped name used to refer to another
+/// declaration, such as the base of a struct
+#[derive(Debug, Clone, PartialEq)]
+pub struct Reference {
+    pub name: ScopedName,
+    pub span: Range<usize>,
+}
+
+impl Reference {
+    /// Builds a parser is able to parse a scoped name referring to another
+    /// declaration, recording where it appears
+    pub fn parser() -> impl Parser<char, Reference, Error = Simple<char>> {
+        token(ScopedName::parser().map_with_span(|name, span| Reference { name, span }))
+    }
+}
+
+/// The TypeSpec enum represents the type given to a constant, member, typedef
+/// or parameter
+#[derive(Debug, Clone, PartialEq)]
+pub enum TypeSpec {
+    Primitive(PrimitiveType, Range<usize>),
+    /// A `string`, with an optional bound
+    String(Option<ConstExpr>, Range<usize>),
+    /// A `wstring`, with an optional bound
+    WString(Option<ConstExpr>, Range<usize>),
+    /// A `fixed`, with optional digits and scale
+    Fixed(Option<(ConstExpr, ConstExpr)>, Range<usize>),
+    /// A `sequence` of an element type, with an optional bound
+    Sequence(Box<TypeSpec>, Option<ConstExpr>, Range<usize>),
+    /// A `map` from a key type to a value type, with an optional bound
+    Map(
+        Box<TypeSpec>,
+        Box<TypeSpec>,
+        Option<ConstExpr>,
+        Range<usize>,
+    ),
+    Any(Range<usize>),
+    Object(Range<usize>),
+    ValueBase(Range<usize>),
+    /// A reference to a declared type
+    Named(ScopedName, Range<usize>),
+}
+
+impl TypeSpec {
+    /// Builds a parser is able to parse a type specification as specified in the
+    /// IDL Documentation
+    ///
+    /// Example
+    ///
+    /// ```
+    /// use ox_idl::ast::TypeSpec;
+    /// use ox_idl::name::ScopedName;
+    /// use ox_idl::primitive::PrimitiveType;
+    /// use chumsky::prelude::*;
+    ///
+    /// let parser = TypeSpec::parser();
+    ///
+    /// let t = parser.parse("unsigned short");
+    /// assert_eq!(t, Ok(TypeSpec::Primitive(PrimitiveType::UnsignedShort, 0..14)));
+    ///
+    /// let t = parser.parse("::M::Point");
+    /// assert_eq!(t, Ok(TypeSpec::Named(ScopedName::from("::M::Point"), 0..10)));
+    /// ```
+    pub fn parser() -> impl Parser<char, TypeSpec, Error = Simple<char>> {
+        // 7.4.1.3
+        // <type_spec> ::= <simple_type_spec>
+        // <simple_type_spec> ::= <base_type_spec> | <scoped_name>
+        // <template_type_spec> ::= <sequence_type> | <string_type>
+        //                        | <wide_string_type> | <fixed_pt_type>
+        // <sequence_type> ::= "sequence" "<" <type_spec> "," <positive_int_const> ">"
+        //                   | "sequence" "<" <type_spec> ">"
+        // <string_type> ::= "string" "<" <positive_int_const> ">" | "string"
+        // <wide_string_type> ::= "wstring" "<" <positive_int_const> ">" | "wstring"
+        // <fixed_pt_type> ::= "fixed" "<" <positive_int_const> "," <positive_int_const> ">"
+        // <fixed_pt_const_type> ::= "fixed"
+        //
+        // 7.4.13.3
+        // <map_type> ::= "map" "<" <type_spec> "," <type_spec> "," <positive_int_const> ">"
+        //              | "map" "<" <type_spec> "," <type_spec> ">"
+        //
+        // The bounds are checked when the specification is validated.
+        token(recursive(|type_spec| {
+            let open = padding().ignore_then(symbol("<"));
+            let bound = || {
+                open.clone()
+                    .ignore_then(ConstExpr::parser())
+                    .then_ignore(just('>'))
+            };
+
+            let sequence = Keyword::Sequence
+                .make_parser()
+                .ignore_then(open.clone())
+                .ignore_then(type_spec.clone().then_ignore(padding()))
+                .then(symbol(",").ignore_then(ConstExpr::parser()).or_not())
+                .then_ignore(just('>'))
+                .map_with_span(|(ty, bound), span| TypeSpec::Sequence(Box::new(ty), bound, span));
+            let map = Keyword::Map
+                .make_parser()
+                .ignore_then(open.clone())
+                .ignore_then(type_spec.clone().then_ignore(padding()))
+                .then_ignore(symbol(","))
+                .then(type_spec.then_ignore(padding()))
+                .then(symbol(",").ignore_then(ConstExpr::parser()).or_not())
+                .then_ignore(just('>'))
+                .map_with_span(|((key, value), bound), span| {
+                    TypeSpec::Map(Box::new(key), Box::new(value), bound, span)
+                });
+            let fixed = Keyword::Fixed
+                .make_parser()
+                .ignore_then(
+                    open.clone()
+                        .ignore_then(ConstExpr::parser())
+                        .then_ignore(symbol(","))
+                        .then(ConstExpr::parser())
+                        .then_ignore(just('>'))
+                        .or_not(),
+                )
+                .map_with_span(TypeSpec::Fixed);
+
+            choice((
+                PrimitiveType::parser().map_with_span(TypeSpec::Primitive),
+                Keyword::String
+                    .make_parser()
+                    .ignore_then(bound().or_not())
+                    .map_with_span(TypeSpec::String),
+                Keyword::WString
+                    .make_parser()
+                    .ignore_then(bound().or_not())
+                    .map_with_span(TypeSpec::WString),
+                fixed,
+                sequence,
+                map,
+                Keyword::Any
+                    .make_parser()
+                    .map_with_span(|_, span| TypeSpec::Any(span)),
+                Keyword::Object
+                    .make_parser()
+                    .map_with_span(|_, span| TypeSpec::Object(span)),
+                Keyword::ValueBase
+                    .make_parser()
+                    .map_with_span(|_, span| TypeSpec::ValueBase(span)),
+                ScopedName::parser().map_with_span(TypeSpec::Named),
+            ))
+        }))
+    }
+
+    /// Returns the span of the type specification
+    pub fn span(&self) -> Range<usize> {
+        match self {
+            TypeSpec::Primitive(_, span)
+            | TypeSpec::String(_, span)
+            | TypeSpec::WString(_, span)
+            | TypeSpec::Fixed(_, span)
+            | TypeSpec::Sequence(_, _, span)
+            | TypeSpec::Map(_, _, _, span)
+            | TypeSpec::Any(span)
+            | TypeSpec::Object(span)
+            | TypeSpec::ValueBase(span)
+            | TypeSpec::Named(_, span) => span.clone(),
+        }
+    }
+}
+
+/// The Declarator type represents a name being declared with a type, which
+/// makes it an array when it has dimensions
+#[derive(Debug, Clone, PartialEq)]
+pub struct Declarator {
+    pub name: Identifier,
+    /// The size of each dimension of an array, outermost first
+    pub dimensions: Vec<ConstExpr>,
+    pub span: Range<usize>,
+}
+
+impl Declarator {
+    /// Builds a parser is able to parse a simple or array declarator as specified
+    /// in the IDL Documentation
+    ///
+    /// Example
+    ///
+    /// ```
+    /// use ox_idl::ast::Declarator;
+    /// use chumsky::prelude::*;
+    ///
+    /// let d = Declarator::parser().parse("matrix[3][4]").unwrap();
+    /// assert_eq!(d.name.as_str(), "matrix");
+    /// assert_eq!(d.dimensions.len(), 2);
+    /// assert_eq!(d.span, 0..12);
+    /// ```
+    pub fn parser() -> impl Parser<char, Declarator, Error = Simple<char>> {
+        // 7.4.1.3
+        // <declarators> ::= <declarator> { "," <declarator> }*
+        // <declarator> ::= <simple_declarator>
+        // <simple_declarator> ::= <identifier>
+        //
+        // 7.4.2.3
+        // <declarator> ::= <simple_declarator> | <array_declarator>
+        // <array_declarator> ::= <identifier> <fixed_array_size>+
+        // <fixed_array_size> ::= "[" <positive_int_const> "]"
+        let dimension = padding()
+            .ignore_then(symbol("["))
+            .ignore_then(ConstExpr::parser())
+            .then_ignore(just(']'));
+
+        token(
+            Identifier::parser()
+                .then(dimension.repeated())
+                .map_with_span(|(name, dimensions), span| Declarator {
+                    name,
+                    dimensions,
+                    span,
+                }),
+        )
+    }
+}
+
+/// The Member type represents a member of a struct or exception, declaring
+/// one or more names with the same type
+#[derive(Debug, Clone, PartialEq)]
+pub struct Member {
+    pub annotations: Vec<Annotation>,
+    pub ty: TypeSpec,
+    pub declarators: Vec<Declarator>,
+    pub span: Range<usize>,
+}
+
+impl Member {
+    /// Builds a parser is able to parse a member declaration, including its
+    /// terminating semicolon, as specified in the IDL Documentation
+    ///
+    /// Example
+    ///
+    /// ```
+    /// use ox_idl::ast::Member;
+    /// use chumsky::prelude::*;
+    ///
+    /// let m = Member::parser().parse("long x, y, z[3];").unwrap();
+    /// assert_eq!(m.declarators.len(), 3);
+    /// ```
+    pub fn parser() -> impl Parser<char, Member, Error = Simple<char>> {
+        // 7.4.1.3
+        // <member> ::= <type_spec> <declarators> ";"
+        token(
+            Annotation::parser()
+                .repeated()
+                .then(TypeSpec::parser())
+                .then(Declarator::parser().separated_by(symbol(",")).at_least(1))
+                .then_ignore(just(';'))
+                .map_with_span(|((annotations, ty), declarators), span| Member {
+                    annotations,
+                    ty,
+                    declarators,
+                    span,
+                }),
+        )
+    }
+}
+
+/// The Module type represents a module and the definitions it contains
+#[derive(Debug, Clone, PartialEq)]
+pub struct Module {
+    pub annotations: Vec<Annotation>,
+    pub name: Identifier,
+    pub definitions: Vec<Definition>,
+    pub span: Range<usize>,
+}
+
+impl Module {
+    /// Builds a parser is able to parse a module declaration as specified in the
+    /// IDL Documentation
+    ///
+    /// Example
+    ///
+    /// ```
+    /// use ox_idl::ast::{Definition, Module};
+    /// use chumsky::prelude::*;
+    ///
+    /// let m = Module::parser()
+    ///     .parse("module a { module b { native N; }; };")
+    ///     .unwrap();
+    /// assert_eq!(m.name.as_str(), "a");
+    /// assert!(matches!(&m.definitions[0], Definition::Module(b) if b.name.as_str() == "b"));
+    /// ```
+    pub fn parser() -> impl Parser<char, Module, Error = Simple<char>> {
+        Self::parser_with(Definition::parser())
+    }
+
+    /// Builds a module parser using the given parser for the definitions within it
+    pub fn parser_with(
+        definition: impl Parser<char, Definition, Error = Simple<char>> + Clone,
+    ) -> impl Parser<char, Module, Error = Simple<char>> {
+        // 7.4.1.3
+        // <module_dcl> ::= "module" <identifier> "{" <definition>+ "}"
+        token(
+            Annotation::parser()
+                .repeated()
+                .then_ignore(keyword(Keyword::Module))
+                .then(token(Identifier::parser()))
+                .then(
+                    definition
+                        .repeated()
+                        .at_least(1)
+                        .delimited_by(symbol("{"), symbol("}")),
+                )
+                .then_ignore(just(';'))
+                .map_with_span(|((annotations, name), definitions), span| Module {
+                    annotations,
+                    name,
+                    definitions,
+                    span,
+                }),
+        )
+    }
+
+    /// Merges every module that is reopened within a list of definitions into its
+    /// first occurrence, so that each module appears once per scope
+    ///
+    /// The definitions of a reopened module are appended to those of the first
+    /// occurrence, and nested modules are merged in the same way. The merged module
+    /// keeps the span of its first occurrence, along with the annotations of every
+    /// occurrence where an annotation applied more than once alike is kept once.
+    ///
+    /// A specification keeps each reopened module where it appears, as a reopened
+    /// module may use declarations made after the module was first opened. Names are
+    /// declared in source order when a specification is validated, so the merged
+    /// definitions validate just as the specification they come from does.
+    /// [`Specification::validate_merged`] validates a specification and merges it.
+    ///
+    /// Example
+    ///
+    /// ```
+    /// use ox_idl::ast::{Definition, Module, Specification};
+    /// use chumsky::prelude::*;
+    ///
+    /// let spec = Specification::parser()
+    ///     .parse("module a { native X; }; native Y; module a { native Z; };")
+    ///     .unwrap();
+    /// assert_eq!(spec.definitions.len(), 3);
+    /// let merged = Module::merge(spec.definitions);
+    /// assert_eq!(merged.len(), 2);
+    /// let Definition::Module(a) = &merged[0] else { panic!() };
+    /// assert_eq!(a.definitions.len(), 2);
+    /// ```
+    pub fn merge(definitions: Vec<Definition>) -> Vec<Definition> {
+        // 7.4.1.4.1
+        // A module can be reopened. Reopening a module can be done in the same file or
+        // in a different file.
+        let mut merged: Vec<Definition> = Vec::new();
+        for definition in definitions {
+            let reopened = match &definition {
+                Definition::Module(module) => merged.iter_mut().find_map(|d| match d {
+                    Definition::Module(first) if first.name == module.name => Some(first),
+                    _ => None,
+                }),
+                _ => None,
+            };
+            match (reopened, definition) {
+                (Some(first), Definition::Module(module)) => {
+                    for annotation in module.annotations {
+                        if !first.annotations.iter().any(|a| a.same_as(&annotation)) {
+                            first.annotations.push(annotation);
+                        }
+                    }
+                    first.definitions.extend(module.definitions);
+                }
+                (_, definition) => merged.push(definition),
+            }
+        }
+
+        for definition in merged.iter_mut() {
+            if let Definition::Module(module) = definition {
+                module.definitions = Self::merge(std::mem::take(&mut module.definitions));
+            }
+        }
+        merged
+    }
+}
+
+/// The Const type represents the declaration of a named constant
+#[derive(Debug, Clone, PartialEq)]
+pub struct Const {
+    pub annotations: Vec<Annotation>,
+    pub ty: TypeSpec,
+    pub name: Identifier,
+    pub value: ConstExpr,
+    pub span: Range<usize>,
+}
+
+impl Const {
+    /// Builds a parser is able to parse a constant declaration as specified in the
+    /// IDL Documentation
+    ///
+    /// Example
+    ///
+    /// ```
+    /// use ox_idl::ast::Const;
+    /// use chumsky::prelude::*;
+    ///
+    /// let c = Const::parser().parse("const double PI = 3.14159;").unwrap();
+    /// assert_eq!(c.name.as_str(), "PI");
+    /// assert_eq!(c.span, 0..26);
+    /// ```
+    pub fn parser() -> impl Parser<char, Const, Error = Simple<char>> {
+        // 7.4.1.3
+        // <const_dcl> ::= "const" <const_type> <identifier> "=" <const_expr>
+        token(
+            Annotation::parser()
+                .repeated()
+                .then_ignore(keyword(Keyword::Const))
+                .then(TypeSpec::parser())
+                .then(token(Identifier::parser()))
+                .then_ignore(symbol("="))
+                .then(ConstExpr::parser())
+                .then_ignore(just(';'))
+                .map_with_span(|(((annotations, ty), name), value), span| Const {
+                    annotations,
+                    ty,
+                    name,
+                    value,
+                    span,
+                }),
+        )
+    }
+}
+
+/// The Typedef type represents the declaration of one or more aliases of a type
+#[derive(Debug, Clone, PartialEq)]
+pub struct Typedef {
+    pub annotations: Vec<Annotation>,
+    pub ty: TypeSpec,
+    pub declarators: Vec<Declarator>,
+    pub span: Range<usize>,
+}
+
+impl Typedef {
+    /// Builds a parser is able to parse a typedef declaration as specified in the
+    /// IDL Documentation
+    ///
+    /// Example
+    ///
+    /// ```
+    /// use ox_idl::ast::{Typedef, TypeSpec};
+    /// use chumsky::prelude::*;
+    ///
+    /// let t = Typedef::parser()
+    ///     .parse("typedef sequence<string<8>, 4> Names, Table[2][3];")
+    ///     .unwrap();
+    /// assert!(matches!(t.ty, TypeSpec::Sequence(_, Some(_), _)));
+    /// assert_eq!(t.declarators[1].dimensions.len(), 2);
+    /// ```
+    pub fn parser() -> impl Parser<char, Typedef, Error = Simple<char>> {
+        // 7.4.1.3
+        // <typedef_dcl> ::= "typedef" <type_declarator>
+        // <type_declarator> ::= { <simple_type_spec> | <template_type_spec> }
+        //                       <any_declarators>
+        //
+        // 7.4.2.3
+        // <any_declarators> ::= <any_declarator> { "," <any_declarator> }*
+        token(
+            Annotation::parser()
+                .repeated()
+                .then_ignore(keyword(Keyword::Typedef))
+                .then(TypeSpec::parser())
+                .then(Declarator::parser().separated_by(symbol(",")).at_least(1))
+                .then_ignore(just(';'))
+                .map_with_span(|((annotations, ty), declarators), span| Typedef {
+                    annotations,
+                    ty,
+                    declarators,
+                    span,
+                }),
+        )
+    }
+}
+
+/// The Struct type represents a structure and its members
+#[derive(Debug, Clone, PartialEq)]
+pub struct Struct {
+    pub annotations: Vec<Annotation>,
+    pub name: Identifier,
+    /// The struct this struct inherits from, if any
+    pub base: Option<Reference>,
+    pub members: Vec<Member>,
+    pub span: Range<usize>,
+}
+
+impl Struct {
+    /// Builds a parser is able to parse a struct definition as specified in the
+    /// IDL Documentation
+    ///
+    /// Example
+    ///
+    /// ```
+    /// use ox_idl::ast::Struct;
+    /// use ox_idl::name::ScopedName;
+    /// use chumsky::prelude::*;
+    ///
+    /// let s = Struct::parser()
+    ///     .parse("struct Point3 : Point { double z; };")
+    ///     .unwrap();
+    /// assert_eq!(s.name.as_str(), "Point3");
+    /// assert_eq!(s.base.unwrap().name, ScopedName::from("Point"));
+    /// assert_eq!(s.members.len(), 1);
+    /// ```
+    pub fn parser() -> impl Parser<char, Struct, Error = Simple<char>> {
+        // 7.4.1.3
+        // <struct_def> ::= "struct" <identifier> "{" <member>+ "}"
+        //
+        // 7.4.13.3
+        // <struct_def> ::= "struct" <identifier> ":" <scoped_name> "{" <member>* "}"
+        //                | "struct" <identifier> "{" "}"
+        token(
+            Annotation::parser()
+                .repeated()
+                .then_ignore(keyword(Keyword::Struct))
+                .then(token(Identifier::parser()))
+                .then(symbol(":").ignore_then(Reference::parser()).or_not())
+                .then(
+                    Member::parser()
+                        .repeated()
+                        .delimited_by(symbol("{"), symbol("}")),
+                )
+                .then_ignore(just(';'))
+                .map_with_span(|(((annotations, name), base), members), span| Struct {
+                    annotations,
+                    name,
+                    base,
+                    members,
+                    span,
+                }),
+        )
+    }
+}
+
+/// The Union type represents a discriminated union and its cases
+#[derive(Debug, Clone, PartialEq)]
+pub struct Union {
+    pub annotations: Vec<Annotation>,
+    pub name: Identifier,
+    /// The type of the discriminator given in the switch
+    pub discriminator: TypeSpec,
+    pub cases: Vec<Case>,
+    pub span: Range<usize>,
+}
+
+/// The Case type represents a single element of a union along with the labels
+/// that select it
+#[derive(Debug, Clone, PartialEq)]
+pub struct Case {
+    pub labels: Vec<CaseLabel>,
+    pub annotations: Vec<Annotation>,
+    pub ty: TypeSpec,
+    pub declarator: Declarator,
+    pub span: Range<usize>,
+}
+
+/// The CaseLabel enum represents a `case` or `default` label of a union
+#[derive(Debug, Clone, PartialEq)]
+pub enum CaseLabel {
+    Value(ConstExpr),
+    Default(Range<usize>),
+}
+
+impl Union {
+    /// Builds a parser is able to parse a union definition as specified in the
+    /// IDL Documentation
+    ///
+    /// Example
+    ///
+    /// ```
+    /// use ox_idl::ast::{CaseLabel, Union};
+    /// use chumsky::prelude::*;
+    ///
+    /// let u = Union::parser()
+    ///     .parse("union U switch (long) { case 1: case 2: long x; default: string s; };")
+    ///     .unwrap();
+    /// assert_eq!(u.cases.len(), 2);
+    /// assert_eq!(u.cases[0].labels.len(), 2);
+    /// assert!(matches!(u.cases[1].labels[0], CaseLabel::Default(_)));
+    /// ```
+    pub fn parser() -> impl Parser<char, Union, Error = Simple<char>> {
+        // 7.4.1.3
+        // <union_def> ::= "union" <identifier> "switch" "(" <switch_type_spec> ")"
+        //                 "{" <switch_body> "}"
+        // <switch_type_spec> ::= <integer_type> | <char_type> | <boolean_type>
+        //                      | <scoped_name>
+        // <switch_body> ::= <case>+
+        //
+        // The discriminator type is checked when the specification is validated.
+        token(
+            Annotation::parser()
+                .repeated()
+                .then_ignore(keyword(Keyword::Union))
+                .then(token(Identifier::parser()))
+                .then_ignore(keyword(Keyword::Switch))
+                .then(TypeSpec::parser().delimited_by(symbol("("), symbol(")")))
+                .then(
+                    Case::parser()
+                        .repeated()
+                        .at_least(1)
+                        .delimited_by(symbol("{"), symbol("}")),
+                )
+                .then_ignore(just(';'))
+                .map_with_span(
+                    |(((annotations, name), discriminator), cases), span| Union {
+                        annotations,
+                        name,
+                        discriminator,
+                        cases,
+                        span,
+                    },
+                ),
+        )
+    }
+}
+
+impl Case {
+    /// Builds a parser is able to parse a case of a union, including its labels and
+    /// terminating semicolon, as specified in the IDL Documentation
+    pub fn parser() -> impl Parser<char, Case, Error = Simple<char>> {
+        // 7.4.1.3
+        // <case> ::= <case_label>+ <element_spec> ";"
+        // <element_spec> ::= <type_spec> <declarator>
+        token(
+            CaseLabel::parser()
+                .repeated()
+                .at_least(1)
+                .then(Annotation::parser().repeated())
+                .then(TypeSpec::parser())
+                .then(Declarator::parser())
+                .then_ignore(just(';'))
+                .map_with_span(|(((labels, annotations), ty), declarator), span| Case {
+                    labels,
+                    annotations,
+                    ty,
+                    declarator,
+                    span,
+                }),
+        )
+    }
+}
+
+impl CaseLabel {
+    /// Builds a parser is able to parse a case label of a union as specified in the
+    /// IDL Documentation
+    pub fn parser() -> impl Parser<char, CaseLabel, Error = Simple<char>> {
+        // 7.4.1.3
+        // <case_label> ::= "case" <const_expr> ":"
+        //                | "default" ":"
+        let value = keyword(Keyword::Case)
+            .ignore_then(ConstExpr::parser())
+            .map(CaseLabel::Value);
+        let default = token(
+            Keyword::Default
+                .make_parser()
+                .map_with_span(|_, span| CaseLabel::Default(span)),
+        );
+
+        value.or(default).then_ignore(symbol(":"))
+    }
+
+    /// Returns the span of the label, which for a `case` label is the span of its
+    /// expression
+    pub fn span(&self) -> Range<usize> {
+        match self {
+            CaseLabel::Value(expr) => expr.span(),
+            CaseLabel::Default(span) => span.clone(),
+        }
+    }
+}
+
+/// The Enum type represents an enumeration and its enumerators
+#[derive(Debug, Clone, PartialEq)]
+pub struct Enum {
+    pub annotations: Vec<Annotation>,
+    pub name: Identifier,
+    pub enumerators: Vec<Enumerator>,
+    pub span: Range<usize>,
+}
+
+/// The Enumerator type represents a single value of an enumeration
+#[derive(Debug, Clone, PartialEq)]
+pub struct Enumerator {
+    pub annotations: Vec<Annotation>,
+    pub name: Identifier,
+    pub span: Range<usize>,
+}
+
+impl Enum {
+    /// Builds a parser is able to parse an enum definition as specified in the IDL
+    /// Documentation
+    ///
+    /// Example
+    ///
+    /// ```
+    /// use ox_idl::ast::Enum;
+    /// use chumsky::prelude::*;
+    ///
+    /// let e = Enum::parser()
+    ///     .parse("enum Color { RED, @value(5) GREEN, BLUE };")
+    ///     .unwrap();
+    /// assert_eq!(e.enumerators.len(), 3);
+    /// assert_eq!(e.enumerators[1].annotations.len(), 1);
+    /// ```
+    pub fn parser() -> impl Parser<char, Enum, Error = Simple<char>> {
+        // 7.4.1.3
+        // <enum_dcl> ::= "enum" <identifier>
+        //                "{" <enumerator> { "," <enumerator> } * "}"
+        token(
+            Annotation::parser()
+                .repeated()
+                .then_ignore(keyword(Keyword::Enum))
+                .then(token(Identifier::parser()))
+                .then(
+                    Enumerator::parser()
+                        .separated_by(symbol(","))
+                        .at_least(1)
+                        .delimited_by(symbol("{"), symbol("}")),
+                )
+                .then_ignore(just(';'))
+                .map_with_span(|((annotations, name), enumerators), span| Enum {
+                    annotations,
+                    name,
+                    enumerators,
+                    span,
+                }),
+        )
+    }
+}
+
+impl Enumerator {
+    /// Builds a parser is able to parse an enumerator as specified in the IDL
+    /// Documentation
+    pub fn parser() -> impl Parser<char, Enumerator, Error = Simple<char>> {
+        // 7.4.1.3
+        // <enumerator> ::= <identifier>
+        token(
+            Annotation::parser()
+                .repeated()
+                .then(Identifier::parser())
+                .map_with_span(|(annotations, name), span| Enumerator {
+                    annotations,
+                    name,
+                    span,
+                }),
+        )
+    }
+}
+
+/// The Bitmask type represents a bitmask and its named bits
+#[derive(Debug, Clone, PartialEq)]
+pub struct Bitmask {
+    pub annotations: Vec<Annotation>,
+    pub name: Identifier,
+    pub values: Vec<BitValue>,
+    pub span: Range<usize>,
+}
+
+/// The BitValue type represents a single named bit of a bitmask
+#[derive(Debug, Clone, PartialEq)]
+pub struct BitValue {
+    pub annotations: Vec<Annotation>,
+    pub name: Identifier,
+    pub span: Range<usize>,
+}
+
+/// The Bitset type represents a bitset and its bitfields
+#[derive(Debug, Clone, PartialEq)]
+pub struct Bitset {
+    pub annotations: Vec<Annotation>,
+    pub name: Identifier,
+    /// The bitset this bitset inherits from, if any
+    pub base: Option<Reference>,
+    pub bitfields: Vec<Bitfield>,
+    pub span: Range<usize>,
+}
+
+/// The Bitfield type represents a `bitfield` member of a bitset, which may
+/// declare several names or none at all to leave unused bits
+#[derive(Debug, Clone, PartialEq)]
+pub struct Bitfield {
+    pub annotations: Vec<Annotation>,
+    /// The number of bits in the field
+    pub width: ConstExpr,
+    /// The type the field is accessed as, if one is given
+    pub ty: Option<TypeSpec>,
+    pub names: Vec<Identifier>,
+    pub span: Range<usize>,
+}
+
+impl Bitmask {
+    /// Builds a parser is able to parse a bitmask definition as specified in the IDL
+    /// Documentation
+    ///
+    /// Example
+    ///
+    /// ```
+    /// use ox_idl::ast::Bitmask;
+    /// use chumsky::prelude::*;
+    ///
+    /// let b = Bitmask::parser()
+    ///     .parse("@bit_bound(8) bitmask Flags { READ, @position(4) WRITE };")
+    ///     .unwrap();
+    /// assert_eq!(b.values.len(), 2);
+    /// assert_eq!(b.values[1].annotations.len(), 1);
+    /// ```
+    pub fn parser() -> impl Parser<char, Bitmask, Error = Simple<char>> {
+        // 7.4.13.4.3.2
+        // <bitmask_dcl> ::= "bitmask" <identifier> "{" <bit_value> { "," <bit_value> }* "}"
+        token(
+            Annotation::parser()
+                .repeated()
+                .then_ignore(keyword(Keyword::Bitmask))
+                .then(token(Identifier::parser()))
+                .then(
+                    BitValue::parser()
+                        .separated_by(symbol(","))
+                        .at_least(1)
+                        .delimited_by(symbol("{"), symbol("}")),
+                )
+                .then_ignore(just(';'))
+                .map_with_span(|((annotations, name), values), span| Bitmask {
+                    annotations,
+                    name,
+                    values,
+                    span,
+                }),
+        )
+    }
+}
+
+impl BitValue {
+    /// Builds a parser is able to parse a named bit of a bitmask as specified in the
+    /// IDL Documentation
+    pub fn parser() -> impl Parser<char, BitValue, Error = Simple<char>> {
+        // 7.4.13.4.3.2
+        // <bit_value> ::= <identifier>
+        token(
+            Annotation::parser()
+                .repeated()
+                .then(Identifier::parser())
+                .map_with_span(|(annotations, name), span| BitValue {
+                    annotations,
+                    name,
+                    span,
+                }),
+        )
+    }
+}
+
+impl Bitset {
+    /// Builds a parser is able to parse a bitset definition as specified in the IDL
+    /// Documentation
+    ///
+    /// Example
+    ///
+    /// ```
+    /// use ox_idl::ast::Bitset;
+    /// use chumsky::prelude::*;
+    ///
+    /// let b = Bitset::parser()
+    ///     .parse("bitset Header : Base { bitfield<3> version; bitfield<2>; bitfield<1, boolean> a, b; };")
+    ///     .unwrap();
+    /// assert!(b.base.is_some());
+    /// assert_eq!(b.bitfields.len(), 3);
+    /// assert!(b.bitfields[1].names.is_empty());
+    /// assert_eq!(b.bitfields[2].names.len(), 2);
+    /// ```
+    pub fn parser() -> impl Parser<char, Bitset, Error = Simple<char>> {
+        // 7.4.13.4.3.2
+        // <bitset_dcl> ::= "bitset" <identifier> [":" <scoped_name>] "{" <bitfield>* "}"
+        token(
+            Annotation::parser()
+                .repeated()
+                .then_ignore(keyword(Keyword::Bitset))
+                .then(token(Identifier::parser()))
+                .then(symbol(":").ignore_then(Reference::parser()).or_not())
+                .then(
+                    Bitfield::parser()
+                        .repeated()
+                        .delimited_by(symbol("{"), symbol("}")),
+                )
+                .then_ignore(just(';'))
+                .map_with_span(|(((annotations, name), base), bitfields), span| Bitset {
+                    annotations,
+                    name,
+                    base,
+                    bitfields,
+                    span,
+                }),
+        )
+    }
+}
+
+impl Bitfield {
+    /// Builds a parser is able to parse a bitfield of a bitset, including its
+    /// terminating semicolon, as specified in the IDL Documentation
+    pub fn parser() -> impl Parser<char, Bitfield, Error = Simple<char>> {
+        // 7.4.13.4.3.2
+        // <bitfield> ::= <bitfield_spec> <identifier>* ";"
+        // <bitfield_spec> ::= "bitfield" "<" <positive_int_const> ">"
+        //                   | "bitfield" "<" <positive_int_const> "," <destination_type> ">"
+        // <destination_type> ::= <boolean_type> | <octet_type> | <integer_type>
+        //
+        // Several names are separated by commas. The destination type is checked
+        // when the bitset is declared.
+        token(
+            Annotation::parser()
+                .repeated()
+                .then_ignore(keyword(Keyword::Bitfield))
+                .then_ignore(symbol("<"))
+                .then(ConstExpr::parser())
+                .then(symbol(",").ignore_then(TypeSpec::parser()).or_not())
+                .then_ignore(symbol(">"))
+                .then(token(Identifier::parser()).separated_by(symbol(",")))
+                .then_ignore(just(';'))
+                .map_with_span(|(((annotations, width), ty), names), span| Bitfield {
+                    annotations,
+                    width,
+                    ty,
+                    names,
+                    span,
+                }),
+        )
+    }
+}
+
+/// The Native type represents the declaration of a native type, whose
+/// representation is left to the language mapping
+#[derive(Debug, Clone, PartialEq)]
+pub struct Native {
+    pub annotations: Vec<Annotation>,
+    pub name: Identifier,
+    pub span: Range<usize>,
+}
+
+impl Native {
+    /// Builds a parser is able to parse a native declaration as specified in the
+    /// IDL Documentation
+    ///
+    /// Example
+    ///
+    /// ```
+    /// use ox_idl::ast::Native;
+    /// use chumsky::prelude::*;
+    ///
+    /// let n = Native::parser().parse("native Handle;").unwrap();
+    /// assert_eq!(n.name.as_str(), "Handle");
+    /// ```
+    pub fn parser() -> impl Parser<char, Native, Error = Simple<char>> {
+        // 7.4.1.3
+        // <native_dcl> ::= "native" <simple_declarator>
+        token(
+            Annotation::parser()
+                .repeated()
+                .then_ignore(keyword(Keyword::Native))
+                .then(token(Identifier::parser()))
+                .then_ignore(just(';'))
+                .map_with_span(|(annotations, name), span| Native {
+                    annotations,
+                    name,
+                    span,
+                }),
+        )
+    }
+}
+
+/// The Interface type represents an interface and the operations, attributes
+/// and definitions it exports
+#[derive(Debug, Clone, PartialEq)]
+pub struct Interface {
+    pub annotations: Vec<Annotation>,
+    /// The `local` or `abstract` keyword given before `interface`, if any
+    pub modifier: Option<Keyword>,
+    pub name: Identifier,
+    pub bases: Vec<Reference>,
+    pub exports: Vec<Export>,
+    pub span: Range<usize>,
+}
+
+/// The Export enum represents the declarations that may appear within an
+/// interface
+#[derive(Debug, Clone, PartialEq)]
+pub enum Export {
+    Operation(Operation),
+    Attribute(Attribute),
+    Definition(Definition),
+}
+
+/// The Operation type represents an operation of an interface
+#[derive(Debug, Clone, PartialEq)]
+pub struct Operation {
+    pub annotations: Vec<Annotation>,
+    pub oneway: bool,
+    /// The type returned by the operation, or None if it is `void`
+    pub return_type: Option<TypeSpec>,
+    pub name: Identifier,
+    pub params: Vec<Param>,
+    pub raises: Option<Raises>,
+    pub span: Range<usize>,
+}
+
+/// The Param type represents a parameter of an operation
+#[derive(Debug, Clone, PartialEq)]
+pub struct Param {
+    pub annotations: Vec<Annotation>,
+    /// The `in`, `out` or `inout` keyword giving the direction of the parameter
+    pub direction: Keyword,
+    pub ty: TypeSpec,
+    pub name: Identifier,
+    pub span: Range<usize>,
+}
+
+/// The Attribute type represents one or more attributes of an interface
+#[derive(Debug, Clone, PartialEq)]
+pub struct Attribute {
+    pub annotations: Vec<Annotation>,
+    pub readonly: bool,
+    pub ty: TypeSpec,
+    pub names: Vec<Identifier>,
+    /// The exceptions raised when reading the attribute
+    pub get_raises: Option<Raises>,
+    /// The exceptions raised when writing the attribute
+    pub set_raises: Option<Raises>,
+    pub span: Range<usize>,
+}
+
+/// The Raises type represents a `raises`, `getraises` or `setraises` clause
+#[derive(Debug, Clone, PartialEq)]
+pub struct Raises {
+    /// The keyword that introduced the clause
+    pub keyword: Keyword,
+    pub exceptions: Vec<Reference>,
+    pub span: Range<usize>,
+}
+
+impl Interface {
+    /// Builds a parser is able to parse an interface definition as specified in the
+    /// IDL Documentation
+    ///
+    /// Example
+    ///
+    /// ```
+    /// use ox_idl::ast::{Export, Interface};
+    /// use ox_idl::keyword::Keyword;
+    /// use chumsky::prelude::*;
+    ///
+    /// let i = Interface::parser()
+    ///     .parse("local interface Account : Named { readonly attribute long balance; void deposit(in long amount); };")
+    ///     .unwrap();
+    /// assert_eq!(i.modifier, Some(Keyword::Local));
+    /// assert_eq!(i.bases.len(), 1);
+    /// assert!(matches!(i.exports[1], Export::Operation(_)));
+    /// ```
+    pub fn parser() -> impl Parser<char, Interface, Error = Simple<char>> {
+        Self::parser_with(Definition::parser())
+    }
+
+    /// Builds an interface parser using the given parser for the definitions
+    /// within it
+    pub fn parser_with(
+        definition: impl Parser<char, Definition, Error = Simple<char>> + Clone,
+    ) -> impl Parser<char, Interface, Error = Simple<char>> {
+        // 7.4.3.3
+        // <interface_def> ::= <interface_header> "{" <interface_body> "}"
+        // <interface_header> ::= <interface_kind> <identifier>
+        //                        [ <interface_inheritance_spec> ]
+        // <interface_kind> ::= "interface"
+        // <interface_inheritance_spec> ::= ":" <interface_name> { "," <interface_name> }*
+        // <interface_body> ::= <export>*
+        //
+        // 7.4.7.3
+        // <interface_kind> ::= "local" "interface"
+        //
+        // 7.4.8.3
+        // <interface_kind> ::= "abstract" "interface"
+        token(
+            Annotation::parser()
+                .repeated()
+                .then(
+                    keyword(Keyword::Local)
+                        .or(keyword(Keyword::Abstract))
+                        .or_not(),
+                )
+                .then_ignore(keyword(Keyword::Interface))
+                .then(token(Identifier::parser()))
+                .then(
+                    symbol(":")
+                        .ignore_then(Reference::parser().separated_by(symbol(",")).at_least(1))
+                        .or_not()
+                        .map(Option::unwrap_or_default),
+                )
+                .then(
+                    Export::parser_with(definition)
+                        .repeated()
+                        .delimited_by(symbol("{"), symbol("}")),
+                )
+                .then_ignore(just(';'))
+                .map_with_span(
+                    |((((annotations, modifier), name), bases), exports), span| Interface {
+                        annotations,
+                        modifier,
+                        name,
+                        bases,
+                        exports,
+                        span,
+                    },
+                ),
+        )
+    }
+}
+
+impl Export {
+    /// Builds a parser is able to parse a declaration within an interface, using
+    /// the given parser for the definitions within it
+    pub fn parser_with(
+        definition: impl Parser<char, Definition, Error = Simple<char>>,
+    ) -> impl Parser<char, Export, Error = Simple<char>> {
+        // 7.4.3.3
+        // <export> ::= <op_dcl> ";" | <attr_dcl> ";"
+        //            | <type_dcl> ";" | <const_dcl> ";" | <except_dcl> ";"
+        //
+        // Modules, interfaces, value types, components, homes, porttypes and
+        // connectors cannot be declared within an interface.
+        let definition = definition.try_map(|definition, span| match definition {
+            Definition::Module(_)
+            | Definition::TemplateModule(_)
+            | Definition::TemplateInstance(_)
+            | Definition::Interface(_) => Err(Simple::custom(
+                span,
+                "modules and interfaces cannot be declared within an interface",
+            )),
+            Definition::ValueType(_) | Definition::ValueBox(_) => Err(Simple::custom(
+                span,
+                "value types cannot be declared within an interface or value type",
+            )),
+            Definition::Component(_) | Definition::Home(_) => Err(Simple::custom(
+                span,
+                "components and homes cannot be declared within an interface or value type",
+            )),
+            Definition::PortType(_) | Definition::Connector(_) => Err(Simple::custom(
+                span,
+                "porttypes and connectors cannot be declared within an interface or value type",
+            )),
+            definition => Ok(Export::Definition(definition)),
+        });
+
+        choice((
+            Attribute::parser().map(Export::Attribute),
+            Operation::parser().map(Export::Operation),
+            definition,
+        ))
+    }
+}
+
+impl Operation {
+    /// Builds a parser is able to parse an operation, including its terminating
+    /// semicolon, as specified in the IDL Documentation
+    ///
+    /// Example
+    ///
+    /// ```
+    /// use ox_idl::ast::Operation;
+    /// use ox_idl::keyword::Keyword;
+    /// use chumsky::prelude::*;
+    ///
+    /// let o = Operation::parser()
+    ///     .parse("long transfer(in long amount, out string receipt) raises (Refused);")
+    ///     .unwrap();
+    /// assert!(o.return_type.is_some());
+    /// assert_eq!(o.params[1].direction, Keyword::Out);
+    /// assert_eq!(o.raises.unwrap().exceptions.len(), 1);
+    /// ```
+    pub fn parser() -> impl Parser<char, Operation, Error = Simple<char>> {
+        // 7.4.3.3
+        // <op_dcl> ::= <op_type_spec> <identifier> "(" [ <parameter_dcls> ] ")"
+        //              [ <raises_expr> ]
+        // <op_type_spec> ::= <type_spec> | "void"
+        // <parameter_dcls> ::= <param_dcl> { "," <param_dcl> } *
+        //
+        // 7.4.6.3
+        // <op_oneway_dcl> ::= "oneway" "void" <identifier> "(" [ <in_parameter_dcls> ] ")"
+        //
+        // Any operation may be parsed as oneway, the restrictions on oneway
+        // operations are checked when the specification is validated.
+        token(
+            Annotation::parser()
+                .repeated()
+                .then(
+                    keyword(Keyword::OneWay)
+                        .or_not()
+                        .map(|oneway| oneway.is_some()),
+                )
+                .then(
+                    keyword(Keyword::Void)
+                        .to(None)
+                        .or(TypeSpec::parser().map(Some)),
+                )
+                .then(token(Identifier::parser()))
+                .then(
+                    Param::parser()
+                        .separated_by(symbol(","))
+                        .delimited_by(symbol("("), symbol(")")),
+                )
+                .then(Raises::parser(Keyword::Raises).or_not())
+                .then_ignore(just(';'))
+                .map_with_span(
+                    |(((((annotations, oneway), return_type), name), params), raises), span| {
+                        Operation {
+                            annotations,
+                            oneway,
+                            return_type,
+                            name,
+                            params,
+                            raises,
+                            span,
+                        }
+                    },
+                ),
+        )
+    }
+}
+
+impl Param {
+    /// Builds a parser is able to parse a parameter of an operation as specified in
+    /// the IDL Documentation
+    pub fn parser() -> impl Parser<char, Param, Error = Simple<char>> {
+        // 7.4.3.3
+        // <param_dcl> ::= <param_attribute> <type_spec> <simple_declarator>
+        // <param_attribute> ::= "in" | "out" | "inout"
+        token(
+            Annotation::parser()
+                .repeated()
+                .then(choice((
+                    keyword(Keyword::In),
+                    keyword(Keyword::Out),
+                    keyword(Keyword::InOut),
+                )))
+                .then(TypeSpec::parser())
+                .then(Identifier::parser())
+                .map_with_span(|(((annotations, direction), ty), name), span| Param {
+                    annotations,
+                    direction,
+                    ty,
+                    name,
+                    span,
+                }),
+        )
+    }
+}
+
+impl Attribute {
+    /// Builds a parser is able to parse an attribute declaration, including its
+    /// terminating semicolon, as specified in the IDL Documentation
+    ///
+    /// Example
+    ///
+    /// ```
+    /// use ox_idl::ast::Attribute;
+    /// use chumsky::prelude::*;
+    ///
+    /// let a = Attribute::parser()
+    ///     .parse("attribute string name getraises (NotFound) setraises (Invalid);")
+    ///     .unwrap();
+    /// assert!(!a.readonly);
+    /// assert!(a.get_raises.is_some() && a.set_raises.is_some());
+    ///
+    /// let a = Attribute::parser().parse("readonly attribute long x, y;").unwrap();
+    /// assert_eq!(a.names.len(), 2);
+    /// ```
+    pub fn parser() -> impl Parser<char, Attribute, Error = Simple<char>> {
+        // 7.4.3.3
+        // <attr_dcl> ::= <readonly_attr_spec> | <attr_spec>
+        // <readonly_attr_spec> ::= "readonly" "attribute" <type_spec>
+        //                          <readonly_attr_declarator>
+        // <readonly_attr_declarator> ::= <simple_declarator> <raises_expr>
+        //                              | <simple_declarator> { "," <simple_declarator> }*
+        // <attr_spec> ::= "attribute" <type_spec> <attr_declarator>
+        // <attr_declarator> ::= <simple_declarator> <attr_raises_expr>
+        //                     | <simple_declarator> { "," <simple_declarator> }*
+        // <attr_raises_expr> ::= <get_excep_expr> [ <set_excep_expr> ]
+        //                      | <set_excep_expr>
+        let readonly = keyword(Keyword::ReadOnly)
+            .ignore_then(keyword(Keyword::Attribute))
+            .ignore_then(TypeSpec::parser())
+            .then(
+                token(Identifier::parser())
+                    .separated_by(symbol(","))
+                    .at_least(1),
+            )
+            .then(Raises::parser(Keyword::Raises).or_not())
+            .map(|((ty, names), get_raises)| (true, ty, names, get_raises, None));
+        let plain = keyword(Keyword::Attribute)
+            .ignore_then(TypeSpec::parser())
+            .then(
+                token(Identifier::parser())
+                    .separated_by(symbol(","))
+                    .at_least(1),
+            )
+            .then(Raises::parser(Keyword::GetRaises).or_not())
+            .then(Raises::parser(Keyword::SetRaises).or_not())
+            .map(|(((ty, names), get_raises), set_raises)| {
+                (false, ty, names, get_raises, set_raises)
+            });
+
+        token(
+            Annotation::parser()
+                .repeated()
+                .then(readonly.or(plain))
+                // Exceptions may only be given for a single attribute
+                .try_map(|(annotations, attribute), span| match attribute {
+                    (_, _, names, Some(_), _) | (_, _, names, _, Some(_)) if names.len() > 1 => {
+                        Err(Simple::custom(
+                            span,
+                            "exceptions may only be raised by an attribute declared on its own",
+                        ))
+                    }
+                    (readonly, ty, names, get_raises, set_raises) => {
+                        Ok((annotations, readonly, ty, names, get_raises, set_raises))
+                    }
+                })
+                .then_ignore(just(';'))
+                .map_with_span(
+                    |(annotations, readonly, ty, names, get_raises, set_raises), span| Attribute {
+                        annotations,
+                        readonly,
+                        ty,
+                        names,
+                        get_raises,
+                        set_raises,
+                        span,
+                    },
+                ),
+        )
+    }
+}
+
+impl Raises {
+    /// Builds a parser is able to parse a list of exceptions introduced by the
+    /// given keyword, which is one of `raises`, `getraises` or `setraises`, as
+    /// specified in the IDL Documentation
+    pub fn parser(keyword: Keyword) -> impl Parser<char, Raises, Error = Simple<char>> {
+        // 7.4.3.3
+        // <raises_expr> ::= "raises" "(" <scoped_name> { "," <scoped_name> } * ")"
+        // <get_excep_expr> ::= "getraises" <exception_list>
+        // <set_excep_expr> ::= "setraises" <exception_list>
+        // <exception_list> ::= "(" <scoped_name> { "," <scoped_name> } * ")"
+        token(
+            keyword
+                .make_parser()
+                .then_ignore(padding())
+                .then(
+                    Reference::parser()
+                        .separated_by(symbol(","))
+                        .at_least(1)
+                        .delimited_by(symbol("("), just(')')),
+                )
+                .map_with_span(|(keyword, exceptions), span| Raises {
+                    keyword,
+                    exceptions,
+                    span,
+                }),
+        )
+    }
+}
+
+/// The Exception type represents an exception and its members
+#[derive(Debug, Clone, PartialEq)]
+pub struct Exception {
+    pub annotations: Vec<Annotation>,
+    pub name: Identifier,
+    pub members: Vec<Member>,
+    pub span: Range<usize>,
+}
+
+impl Exception {
+    /// Builds a parser is able to parse an exception declaration as specified in the
+    /// IDL Documentation
+    ///
+    /// Example
+    ///
+    /// ```
+    /// use ox_idl::ast::Exception;
+    /// use chumsky::prelude::*;
+    ///
+    /// let e = Exception::parser()
+    ///     .parse("exception NotFound { string key; long code; };")
+    ///     .unwrap();
+    /// assert_eq!(e.name.as_str(), "NotFound");
+    /// assert_eq!(e.members.len(), 2);
+    /// ```
+    pub fn parser() -> impl Parser<char, Exception, Error = Simple<char>> {
+        // 7.4.3.3
+        // <except_dcl> ::= "exception" <identifier> "{" <member>* "}"
+        token(
+            Annotation::parser()
+                .repeated()
+                .then_ignore(keyword(Keyword::Exception))
+                .then(token(Identifier::parser()))
+                .then(
+                    Member::parser()
+                        .repeated()
+                        .delimited_by(symbol("{"), symbol("}")),
+                )
+                .then_ignore(just(';'))
+                .map_with_span(|((annotations, name), members), span| Exception {
+                    annotations,
+                    name,
+                    members,
+                    span,
+                }),
+        )
+    }
+}
+
+/// The ValueType type represents a value type or event type and the state
+/// members, factories, operations, attributes and definitions it contains
+#[derive(Debug, Clone, PartialEq)]
+pub struct ValueType {
+    pub annotations: Vec<Annotation>,
+    /// The `custom` or `abstract` keyword given before the kind, if any
+    pub modifier: Option<Keyword>,
+    /// The `valuetype` or `eventtype` keyword giving the kind of the value type
+    pub keyword: Keyword,
+    pub name: Identifier,
+    /// Whether the value type may be truncated to its first base
+    pub truncatable: bool,
+    /// The value types this value type inherits from
+    pub bases: Vec<Reference>,
+    /// The interfaces this value type supports
+    pub supports: Vec<Reference>,
+    pub elements: Vec<ValueElement>,
+    pub span: Range<usize>,
+}
+
+/// The ValueElement enum represents the declarations that may appear within a
+/// value type
+#[derive(Debug, Clone, PartialEq)]
+pub enum ValueElement {
+    Export(Export),
+    State(StateMember),
+    Factory(Factory),
+}
+
+/// The StateMember type represents a `public` or `private` state member of a
+/// value type, declaring one or more names with the same type
+#[derive(Debug, Clone, PartialEq)]
+pub struct StateMember {
+    pub annotations: Vec<Annotation>,
+    /// The `public` or `private` keyword giving the visibility of the member
+    pub visibility: Keyword,
+    pub ty: TypeSpec,
+    pub declarators: Vec<Declarator>,
+    pub span: Range<usize>,
+}
+
+/// The Factory type represents an initializer of a value type, or a factory or
+/// finder of a home
+#[derive(Debug, Clone, PartialEq)]
+pub struct Factory {
+    pub annotations: Vec<Annotation>,
+    /// The `factory` or `finder` keyword that introduced the declaration
+    pub keyword: Keyword,
+    pub name: Identifier,
+    pub params: Vec<Param>,
+    pub raises: Option<Raises>,
+    pub span: Range<usize>,
+}
+
+impl ValueType {
+    /// Builds a parser is able to parse a value type definition as specified in the
+    /// IDL Documentation
+    ///
+    /// Example
+    ///
+    /// ```
+    /// use ox_idl::ast::{ValueElement, ValueType};
+    /// use chumsky::prelude::*;
+    ///
+    /// let v = ValueType::parser()
+    ///     .parse("valuetype Account : truncatable Base supports Audited { private long balance; factory open(in long amount); };")
+    ///     .unwrap();
+    /// assert!(v.truncatable);
+    /// assert_eq!(v.supports.len(), 1);
+    /// assert!(matches!(v.elements[0], ValueElement::State(_)));
+    /// assert!(matches!(v.elements[1], ValueElement::Factory(_)));
+    /// ```
+    pub fn parser() -> impl Parser<char, ValueType, Error = Simple<char>> {
+        Self::parser_with(Definition::parser())
+    }
+
+    /// Builds a value type parser using the given parser for the definitions
+    /// within it
+    pub fn parser_with(
+        definition: impl Parser<char, Definition, Error = Simple<char>>,
+    ) -> impl Parser<char, ValueType, Error = Simple<char>> {
+        // 7.4.5.3
+        // <value_def> ::= <value_header> "{" <value_element>* "}"
+        // <value_header> ::= <value_kind> <identifier> [ <value_inheritance_spec> ]
+        // <value_kind> ::= "valuetype"
+        // <value_inheritance_spec> ::= [ ":" <value_name> ] [ "supports" <interface_name> ]
+        // <value_element> ::= <export> | <state_member> | <init_dcl>
+        //
+        // 7.4.7.3
+        // <value_abs_def> ::= "abstract" "valuetype" <identifier>
+        //                     [ <value_inheritance_spec> ] "{" <export>* "}"
+        // <value_kind> ::= "custom" "valuetype"
+        // <value_inheritance_spec> ::= ":" [ "truncatable" ] <value_name>
+        //                              { "," <value_name> }*
+        //                              [ "supports" <interface_name>
+        //                              { "," <interface_name> }* ]
+        //
+        //
+        // 7.4.10.3
+        // <event_def> ::= <event_header> "{" <value_element> * "}"
+        // <event_header> ::= [ "custom" ] "eventtype" <identifier>
+        //                    [ <value_inheritance_spec> ]
+        // <event_abs_def> ::= "abstract" "eventtype" <identifier>
+        //                     [ <value_inheritance_spec> ] "{" <export>* "}"
+        //
+        // Abstract value types are parsed with the same elements as other value
+        // types, their restrictions are checked when the specification is validated.
+        let inheritance = symbol(":")
+            .ignore_then(
+                keyword(Keyword::Truncatable)
+                    .or_not()
+                    .map(|truncatable| truncatable.is_some()),
+            )
+            .then(Reference::parser().separated_by(symbol(",")).at_least(1))
+            .or_not()
+            .map(Option::unwrap_or_default)
+            .then(supports());
+
+        token(
+            Annotation::parser()
+                .repeated()
+                .then(
+                    keyword(Keyword::Custom)
+                        .or(keyword(Keyword::Abstract))
+                        .or_not()
+                        .then(keyword(Keyword::ValueType).or(keyword(Keyword::EventType))),
+                )
+                .then(token(Identifier::parser()))
+                .then(inheritance)
+                .then(
+                    ValueElement::parser_with(definition)
+                        .repeated()
+                        .delimited_by(symbol("{"), symbol("}")),
+                )
+                .then_ignore(just(';'))
+                .map_with_span(
+                    |((((annotations, (modifier, keyword)), name), inheritance), elements),
+                     span| {
+                        let ((truncatable, bases), supports) = inheritance;
+                        ValueType {
+                            annotations,
+                            modifier,
+                            keyword,
+                            name,
+                            truncatable,
+                            bases,
+                            supports,
+                            elements,
+                            span,
+                        }
+                    },
+                ),
+        )
+    }
+}
+
+impl ValueElement {
+    /// Builds a parser is able to parse a declaration within a value type, using
+    /// the given parser for the definitions within it
+    pub fn parser_with(
+        definition: impl Parser<char, Definition, Error = Simple<char>>,
+    ) -> impl Parser<char, ValueElement, Error = Simple<char>> {
+        choice((
+            StateMember::parser().map(ValueElement::State),
+            Factory::parser(Keyword::Factory).map(ValueElement::Factory),
+            Export::parser_with(definition).map(ValueElement::Export),
+        ))
+    }
+}
+
+impl StateMember {
+    /// Builds a parser is able to parse a state member of a value type, including
+    /// its terminating semicolon, as specified in the IDL Documentation
+    pub fn parser() -> impl Parser<char, StateMember, Error = Simple<char>> {
+        // 7.4.5.3
+        // <state_member> ::= ( "public" | "private" ) <type_spec> <declarators> ";"
+        token(
+            Annotation::parser()
+                .repeated()
+                .then(keyword(Keyword::Public).or(keyword(Keyword::Private)))
+                .then(TypeSpec::parser())
+                .then(Declarator::parser().separated_by(symbol(",")).at_least(1))
+                .then_ignore(just(';'))
+                .map_with_span(|(((annotations, visibility), ty), declarators), span| {
+                    StateMember {
+                        annotations,
+                        visibility,
+                        ty,
+                        declarators,
+                        span,
+                    }
+                }),
+        )
+    }
+}
+
+impl Factory {
+    /// Builds a parser is able to parse a factory or finder introduced by the
+    /// given keyword, which is one of `factory` or `finder`, including its
+    /// terminating semicolon, as specified in the IDL Documentation
+    pub fn parser(keyword: Keyword) -> impl Parser<char, Factory, Error = Simple<char>> {
+        // 7.4.5.3
+        // <init_dcl> ::= "factory" <identifier> "(" [ <init_param_dcls> ] ")"
+        //                [ <raises_expr> ] ";"
+        // <init_param_dcls> ::= <init_param_dcl> { "," <init_param_dcl>}*
+        // <init_param_dcl> ::= "in" <type_spec> <simple_declarator>
+        //
+        // 7.4.9.3
+        // <factory_dcl> ::= "factory" <identifier> "(" [ <factory_param_dcls> ] ")"
+        //                   [ <raises_expr> ]
+        //
+        // 7.4.10.3
+        // <finder_dcl> ::= "finder" <identifier> "(" [ <init_param_dcls> ] ")"
+        //                  [ <raises_expr> ]
+        //
+        // Parameters may be parsed with any direction, only `in` parameters are
+        // allowed which is checked when the specification is validated.
+        token(
+            Annotation::parser()
+                .repeated()
+                .then(keyword.make_parser().then_ignore(padding()))
+                .then(token(Identifier::parser()))
+                .then(
+                    Param::parser()
+                        .separated_by(symbol(","))
+                        .delimited_by(symbol("("), symbol(")")),
+                )
+                .then(Raises::parser(Keyword::Raises).or_not())
+                .then_ignore(just(';'))
+                .map_with_span(|((((annotations, keyword), name), params), raises), span| {
+                    Factory {
+                        annotations,
+                        keyword,
+                        name,
+                        params,
+                        raises,
+                        span,
+                    }
+                }),
+        )
+    }
+}
+
+/// The ValueBox type represents a boxed value type, which wraps another type so
+/// that it may be passed as a value
+#[derive(Debug, Clone, PartialEq)]
+pub struct ValueBox {
+    pub annotations: Vec<Annotation>,
+    pub name: Identifier,
+    /// The boxed type
+    pub ty: TypeSpec,
+    pub span: Range<usize>,
+}
+
+impl ValueBox {
+    /// Builds a parser is able to parse a boxed value type as specified in the IDL
+    /// Documentation
+    ///
+    /// Example
+    ///
+    /// ```
+    /// use ox_idl::ast::{TypeSpec, ValueBox};
+    /// use chumsky::prelude::*;
+    ///
+    /// let b = ValueBox::parser().parse("valuetype Name string<32>;").unwrap();
+    /// assert_eq!(b.name.as_str(), "Name");
+    /// assert!(matches!(b.ty, TypeSpec::String(Some(_), _)));
+    /// ```
+    pub fn parser() -> impl Parser<char, ValueBox, Error = Simple<char>> {
+        // 7.4.7.3
+        // <value_box_def> ::= "valuetype" <identifier> <type_spec>
+        token(
+            Annotation::parser()
+                .repeated()
+                .then_ignore(keyword(Keyword::ValueType))
+                .then(token(Identifier::parser()))
+                .then(TypeSpec::parser())
+                .then_ignore(just(';'))
+                .map_with_span(|((annotations, name), ty), span| ValueBox {
+                    annotations,
+                    name,
+                    ty,
+                    span,
+                }),
+        )
+    }
+}
+
+/// The Component type represents a component and the ports and attributes
+/// through which it interacts with others
+#[derive(Debug, Clone, PartialEq)]
+pub struct Component {
+    pub annotations: Vec<Annotation>,
+    pub name: Identifier,
+    /// The component this component inherits from, if any
+    pub base: Option<Reference>,
+    /// The interfaces this component supports
+    pub supports: Vec<Reference>,
+    pub exports: Vec<ComponentExport>,
+    pub span: Range<usize>,
+}
+
+/// The ComponentExport enum represents the declarations that may appear within
+/// a component
+#[derive(Debug, Clone, PartialEq)]
+pub enum ComponentExport {
+    Port(Port),
+    Attribute(Attribute),
+}
+
+/// The Port type represents a port of a component, porttype or connector, such
+/// as a facet it `provides`, an event sink that `consumes` events or an
+/// extended `port` of a porttype
+#[derive(Debug, Clone, PartialEq)]
+pub struct Port {
+    pub annotations: Vec<Annotation>,
+    /// The keyword giving the kind of the port, such as `provides` or `emits`
+    pub keyword: Keyword,
+    /// Whether a `uses` port may be connected to several facets
+    pub multiple: bool,
+    /// The interface, event type or porttype of the port, which may only be
+    /// `Object` for a `provides` or `uses` port
+    pub ty: TypeSpec,
+    pub name: Identifier,
+    pub span: Range<usize>,
+}
+
+impl Component {
+    /// Builds a parser is able to parse a component definition as specified in the
+    /// IDL Documentation
+    ///
+    /// Example
+    ///
+    /// ```
+    /// use ox_idl::ast::{Component, ComponentExport};
+    /// use ox_idl::keyword::Keyword;
+    /// use chumsky::prelude::*;
+    ///
+    /// let c = Component::parser()
+    ///     .parse("component Sensor : Device supports Named { provides Reader reader; uses multiple Logger logs; attribute long rate; };")
+    ///     .unwrap();
+    /// assert!(c.base.is_some());
+    /// assert_eq!(c.exports.len(), 3);
+    /// assert!(matches!(&c.exports[1], ComponentExport::Port(p) if p.keyword == Keyword::Uses && p.multiple));
+    /// ```
+    pub fn parser() -> impl Parser<char, Component, Error = Simple<char>> {
+        // 7.4.8.3
+        // <component_def> ::= <component_header> "{" <component_body> "}"
+        // <component_header> ::= "component" <identifier> [ <component_inheritance_spec> ]
+        // <component_inheritance_spec> ::= ":" <scoped_name>
+        // <component_body> ::= <component_export>*
+        // <component_export> ::= <provides_dcl> ";" | <uses_dcl> ";" | <attr_dcl> ";"
+        //
+        // 7.4.10.3
+        // <component_header> ::= "component" <identifier> [ <component_inheritance_spec> ]
+        //                        <supported_interface_spec>
+        // <supported_interface_spec> ::= "supports" <scoped_name> { "," <scoped_name> }*
+        // <component_export> ::= <emits_dcl> ";" | <publishes_dcl> ";"
+        //                      | <consumes_dcl> ";"
+        //
+        // 7.4.11.3
+        // <component_export> ::= <port_dcl> ";"
+        token(
+            Annotation::parser()
+                .repeated()
+                .then_ignore(keyword(Keyword::Component))
+                .then(token(Identifier::parser()))
+                .then(symbol(":").ignore_then(Reference::parser()).or_not())
+                .then(supports())
+                .then(
+                    ComponentExport::parser()
+                        .repeated()
+                        .delimited_by(symbol("{"), symbol("}")),
+                )
+                .then_ignore(just(';'))
+                .map_with_span(|((((annotations, name), base), supports), exports), span| {
+                    Component {
+                        annotations,
+                        name,
+                        base,
+                        supports,
+                        exports,
+                        span,
+                    }
+                }),
+        )
+    }
+}
+
+impl ComponentExport {
+    /// Builds a parser is able to parse a declaration within a component as
+    /// specified in the IDL Documentation
+    pub fn parser() -> impl Parser<char, ComponentExport, Error = Simple<char>> {
+        Port::parser()
+            .map(ComponentExport::Port)
+            .or(Attribute::parser().map(ComponentExport::Attribute))
+    }
+}
+
+impl Port {
+    /// Builds a parser is able to parse a port of a component, including its
+    /// terminating semicolon, as specified in the IDL Documentation
+    pub fn parser() -> impl Parser<char, Port, Error = Simple<char>> {
+        // 7.4.8.3
+        // <provides_dcl> ::= "provides" <interface_type> <identifier>
+        // <interface_type> ::= <scoped_name>
+        // <uses_dcl> ::= "uses" <interface_type> <identifier>
+        //
+        // 7.4.10.3
+        // <interface_type> ::= "Object"
+        // <uses_dcl> ::= "uses" "multiple" <interface_type> <identifier>
+        // <emits_dcl> ::= "emits" <scoped_name> <identifier>
+        // <publishes_dcl> ::= "publishes" <scoped_name> <identifier>
+        // <consumes_dcl> ::= "consumes" <scoped_name> <identifier>
+        //
+        // 7.4.11.3
+        // <port_dcl> ::= { "port" | "mirrorport" } <scoped_name> <identifier>
+        //
+        // Any port may be parsed with the type `Object`, the type of each port is
+        // checked when the specification is validated.
+        let kind = choice((
+            keyword(Keyword::Provides).map(|keyword| (keyword, false)),
+            keyword(Keyword::Uses).then(
+                keyword(Keyword::Multiple)
+                    .or_not()
+                    .map(|multiple| multiple.is_some()),
+            ),
+            keyword(Keyword::Emits).map(|keyword| (keyword, false)),
+            keyword(Keyword::Publishes).map(|keyword| (keyword, false)),
+            keyword(Keyword::Consumes).map(|keyword| (keyword, false)),
+            keyword(Keyword::Port).map(|keyword| (keyword, false)),
+            keyword(Keyword::MirrorPort).map(|keyword| (keyword, false)),
+        ));
+        let ty = token(
+            Keyword::Object
+                .make_parser()
+                .map_with_span(|_, span| TypeSpec::Object(span))
+                .or(ScopedName::parser().map_with_span(TypeSpec::Named)),
+        );
+
+        token(
+            Annotation::parser()
+                .repeated()
+                .then(kind)
+                .then(ty)
+                .then(token(Identifier::parser()))
+                .then_ignore(just(';'))
+                .map_with_span(
+                    |(((annotations, (keyword, multiple)), ty), name), span| Port {
+                        annotations,
+                        keyword,
+                        multiple,
+                        ty,
+                        name,
+                        span,
+                    },
+                ),
+        )
+    }
+
+    /// Returns the port as seen from the other end of a `mirrorport`, where each
+    /// facet becomes a receptacle, each receptacle becomes a facet and each
+    /// extended port becomes a mirror port, and the other way around
+    ///
+    /// A facet cannot be `multiple`, so a `uses multiple` port becomes a plain
+    /// facet.
+    ///
+    /// Example
+    ///
+    /// ```
+    /// use ox_idl::ast::Port;
+    /// use ox_idl::keyword::Keyword;
+    /// use chumsky::prelude::*;
+    ///
+    /// let p = Port::parser().parse("uses Logger log;").unwrap();
+    /// assert_eq!(p.mirrored().keyword, Keyword::Provides);
+    /// ```
+    pub fn mirrored(&self) -> Port {
+        // 7.4.11.4
+        // A mirrorport has the same definition as a port, except that all the
+        // facets and receptacles of the porttype are reversed: provides become
+        // uses and uses become provides.
+        let keyword = match &self.keyword {
+            Keyword::Provides => Keyword::Uses,
+            Keyword::Uses => Keyword::Provides,
+            Keyword::Port => Keyword::MirrorPort,
+            Keyword::MirrorPort => Keyword::Port,
+            keyword => keyword.clone(),
+        };
+        Port {
+            keyword,
+            multiple: false,
+            ..self.clone()
+        }
+    }
+}
+
+/// The Home type represents a home, which manages the instances of a component
+#[derive(Debug, Clone, PartialEq)]
+pub struct Home {
+    pub annotations: Vec<Annotation>,
+    pub name: Identifier,
+    /// The home this home inherits from, if any
+    pub base: Option<Reference>,
+    /// The interfaces this home supports
+    pub supports: Vec<Reference>,
+    /// The component managed by the home
+    pub manages: Reference,
+    /// The value type identifying the instances of the component, if any
+    pub primary_key: Option<Reference>,
+    pub exports: Vec<HomeExport>,
+    pub span: Range<usize>,
+}
+
+/// The HomeExport enum represents the declarations that may appear within a
+/// home
+#[derive(Debug, Clone, PartialEq)]
+pub enum HomeExport {
+    Export(Export),
+    /// A `factory` or `finder` of the home
+    Factory(Factory),
+}
+
+impl Home {
+    /// Builds a parser is able to parse a home definition as specified in the IDL
+    /// Documentation
+    ///
+    /// Example
+    ///
+    /// ```
+    /// use ox_idl::ast::{Home, HomeExport};
+    /// use ox_idl::name::ScopedName;
+    /// use chumsky::prelude::*;
+    ///
+    /// let h = Home::parser()
+    ///     .parse("home SensorHome manages Sensor primarykey SensorId { factory create(in long id); finder find(in string name); };")
+    ///     .unwrap();
+    /// assert_eq!(h.manages.name, ScopedName::from("Sensor"));
+    /// assert!(h.primary_key.is_some());
+    /// assert!(matches!(h.exports[1], HomeExport::Factory(_)));
+    /// ```
+    pub fn parser() -> impl Parser<char, Home, Error = Simple<char>> {
+        Self::parser_with(Definition::parser())
+    }
+
+    /// Builds a home parser using the given parser for the definitions within it
+    pub fn parser_with(
+        definition: impl Parser<char, Definition, Error = Simple<char>>,
+    ) -> impl Parser<char, Home, Error = Simple<char>> {
+        // 7.4.9.3
+        // <home_dcl> ::= <home_header> "{" <home_body> "}"
+        // <home_header> ::= "home" <identifier> [ <home_inheritance_spec> ]
+        //                   "manages" <scoped_name>
+        // <home_inheritance_spec> ::= ":" <scoped_name>
+        // <home_body> ::= <home_export>*
+        // <home_export> ::= <export> | <factory_dcl> ";"
+        //
+        // 7.4.10.3
+        // <home_header> ::= "home" <identifier> [ <home_inheritance_spec> ]
+        //                   [ <supported_interface_spec> ]
+        //                   "manages" <scoped_name> [ <primary_key_spec> ]
+        // <primary_key_spec> ::= "primarykey" <scoped_name>
+        // <home_export> ::= <finder_dcl> ";"
+        let inheritance = symbol(":")
+            .ignore_then(Reference::parser())
+            .or_not()
+            .then(supports());
+        let manages = keyword(Keyword::Manages)
+            .ignore_then(Reference::parser())
+            .then(
+                keyword(Keyword::PrimaryKey)
+                    .ignore_then(Reference::parser())
+                    .or_not(),
+            );
+
+        token(
+            Annotation::parser()
+                .repeated()
+                .then_ignore(keyword(Keyword::Home))
+                .then(token(Identifier::parser()))
+                .then(inheritance)
+                .then(manages)
+                .then(
+                    HomeExport::parser_with(definition)
+                        .repeated()
+                        .delimited_by(symbol("{"), symbol("}")),
+                )
+                .then_ignore(just(';'))
+                .map_with_span(
+                    |((((annotations, name), inheritance), manages), exports), span| {
+                        let ((base, supports), (manages, primary_key)) = (inheritance, manages);
+                        Home {
+                            annotations,
+                            name,
+                            base,
+                            supports,
+                            manages,
+                            primary_key,
+                            exports,
+                            span,
+                        }
+                    },
+                ),
+        )
+    }
+}
+
+impl HomeExport {
+    /// Builds a parser is able to parse a declaration within a home, using the
+    /// given parser for the definitions within it
+    pub fn parser_with(
+        definition: impl Parser<char, Definition, Error = Simple<char>>,
+    ) -> impl Parser<char, HomeExport, Error = Simple<char>> {
+        choice((
+            Factory::parser(Keyword::Factory).map(HomeExport::Factory),
+            Factory::parser(Keyword::Finder).map(HomeExport::Factory),
+            Export::parser_with(definition).map(HomeExport::Export),
+        ))
+    }
+}
+
+/// The PortType type represents a porttype, which groups facets, receptacles
+/// and attributes so that they may be declared together as an extended port
+#[derive(Debug, Clone, PartialEq)]
+pub struct PortType {
+    pub annotations: Vec<Annotation>,
+    pub name: Identifier,
+    /// The ports and attributes of the porttype, which always start with a port
+    pub exports: Vec<ComponentExport>,
+    pub span: Range<usize>,
+}
+
+impl PortType {
+    /// Builds a parser is able to parse a porttype definition as specified in the
+    /// IDL Documentation
+    ///
+    /// Example
+    ///
+    /// ```
+    /// use ox_idl::ast::PortType;
+    /// use chumsky::prelude::*;
+    ///
+    /// let p = PortType::parser()
+    ///     .parse("porttype Data { provides Reader reader; uses Listener listener; attribute long depth; };")
+    ///     .unwrap();
+    /// assert_eq!(p.exports.len(), 3);
+    /// assert!(PortType::parser().parse("porttype Data { emits Sample out; };").is_err());
+    /// ```
+    pub fn parser() -> impl Parser<char, PortType, Error = Simple<char>> {
+        // 7.4.11.3
+        // <porttype_def> ::= "porttype" <identifier> "{" <port_body> "}"
+        // <port_body> ::= <port_ref> <port_export>*
+        // <port_ref> ::= <provides_dcl> ";" | <uses_dcl> ";" | <port_dcl> ";"
+        // <port_export> ::= <port_ref> | <attr_dcl> ";"
+        let body = port_ref()
+            .map(ComponentExport::Port)
+            .chain(port_export().repeated())
+            .delimited_by(symbol("{"), symbol("}"));
+
+        token(
+            Annotation::parser()
+                .repeated()
+                .then_ignore(keyword(Keyword::PortType))
+                .then(token(Identifier::parser()))
+                .then(body)
+                .then_ignore(just(';'))
+                .map_with_span(|((annotations, name), exports), span| PortType {
+                    annotations,
+                    name,
+                    exports,
+                    span,
+                }),
+        )
+    }
+}
+
+/// The Connector type represents a connector, which gathers the ports through
+/// which components interact
+#[derive(Debug, Clone, PartialEq)]
+pub struct Connector {
+    pub annotations: Vec<Annotation>,
+    pub name: Identifier,
+    /// The connector this connector inherits from, if any
+    pub base: Option<Reference>,
+    pub exports: Vec<ComponentExport>,
+    pub span: Range<usize>,
+}
+
+impl Connector {
+    /// Builds a parser is able to parse a connector definition as specified in the
+    /// IDL Documentation
+    ///
+    /// Example
+    ///
+    /// ```
+    /// use ox_idl::ast::Connector;
+    /// use chumsky::prelude::*;
+    ///
+    /// let c = Connector::parser()
+    ///     .parse("connector Pipe : Base { mirrorport Data input; port Data output; };")
+    ///     .unwrap();
+    /// assert!(c.base.is_some());
+    /// assert_eq!(c.exports.len(), 2);
+    /// assert!(Connector::parser().parse("connector Pipe {};").is_err());
+    /// ```
+    pub fn parser() -> impl Parser<char, Connector, Error = Simple<char>> {
+        // 7.4.11.3
+        // <connector_dcl> ::= <connector_header> "{" <connector_export>+ "}"
+        // <connector_header> ::= "connector" <identifier> [ <connector_inherit_spec> ]
+        // <connector_inherit_spec> ::= ":" <scoped_name>
+        // <connector_export> ::= <port_ref> | <attr_dcl> ";"
+        token(
+            Annotation::parser()
+                .repeated()
+                .then_ignore(keyword(Keyword::Connector))
+                .then(token(Identifier::parser()))
+                .then(symbol(":").ignore_then(Reference::parser()).or_not())
+                .then(
+                    port_export()
+                        .repeated()
+                        .at_least(1)
+                        .delimited_by(symbol("{"), symbol("}")),
+                )
+                .then_ignore(just(';'))
+                .map_with_span(|(((annotations, name), base), exports), span| Connector {
+                    annotations,
+                    name,
+                    base,
+                    exports,
+                    span,
+                }),
+        )
+    }
+}
+
+/// Builds a parser for a port of a porttype or connector, which may not be an
+/// event port
+fn port_ref() -> impl Parser<char, Port, Error = Simple<char>> {
+    Port::parser().try_map(|port, _| match port.keyword {
+        Keyword::Emits | Keyword::Publishes | Keyword::Consumes => Err(Simple::custom(
+            port.span,
+            format!(
+                "`{}` ports cannot be declared within a porttype or connector",
+                port.keyword
+            ),
+        )),
+        _ => Ok(port),
+    })
+}
+
+/// Builds a parser for a port or attribute of a porttype or connector
+fn port_export() -> impl Parser<char, ComponentExport, Error = Simple<char>> {
+    port_ref()
+        .map(ComponentExport::Port)
+        .or(Attribute::parser().map(ComponentExport::Attribute))
+}
+
+/// The TemplateModule type represents a template module, whose definitions are
+/// only declared once the module is instantiated with actual parameters
+#[derive(Debug, Clone, PartialEq)]
+pub struct TemplateModule {
+    pub annotations: Vec<Annotation>,
+    pub name: Identifier,
+    pub params: Vec<FormalParameter>,
+    pub definitions: Vec<TemplateDefinition>,
+    pub span: Range<usize>,
+}
+
+/// The FormalParameter type represents a parameter of a template module, such
+/// as `typename T` or `const long N`
+#[derive(Debug, Clone, PartialEq)]
+pub struct FormalParameter {
+    pub kind: FormalKind,
+    pub name: Identifier,
+    pub span: Range<usize>,
+}
+
+/// The FormalKind enum represents the kinds of parameters a template module
+/// may take
+#[derive(Debug, Clone, PartialEq)]
+pub enum FormalKind {
+    /// A type of the kind named by a keyword, such as `typename`, `struct` or
+    /// `sequence`
+    Type(Keyword),
+    /// A constant of the given type
+    Const(TypeSpec),
+    /// A sequence of the given element type, which may be an earlier parameter
+    Sequence(TypeSpec),
+}
+
+/// The TemplateDefinition enum represents the declarations that may appear
+/// within a template module
+#[derive(Debug, Clone, PartialEq)]
+pub enum TemplateDefinition {
+    Definition(Definition),
+    Alias(TemplateAlias),
+}
+
+/// The TemplateAlias type represents a reference from within a template module
+/// to another template module, instantiated with the parameters of the first
+#[derive(Debug, Clone, PartialEq)]
+pub struct TemplateAlias {
+    /// The template module that is instantiated
+    pub template: Reference,
+    /// The names of the formal parameters passed on to the template module
+    pub params: Vec<Identifier>,
+    pub name: Identifier,
+    pub span: Range<usize>,
+}
+
+/// The TemplateInstance type represents an instantiation of a template module,
+/// which declares a module holding the definitions of the template module
+#[derive(Debug, Clone, PartialEq)]
+pub struct TemplateInstance {
+    pub annotations: Vec<Annotation>,
+    /// The template module that is instantiated
+    pub template: Reference,
+    pub params: Vec<ActualParameter>,
+    pub name: Identifier,
+    pub span: Range<usize>,
+}
+
+/// The ActualParameter enum represents a parameter given to a template module
+/// when it is instantiated
+///
+/// A parameter that is only a scoped name is parsed as a type, and is taken as
+/// the name of a constant when it is given for a constant parameter.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ActualParameter {
+    Type(TypeSpec),
+    Const(ConstExpr),
+}
+
+impl TemplateModule {
+    /// Builds a parser is able to parse a template module declaration as specified
+    /// in the IDL Documentation
+    ///
+    /// Example
+    ///
+    /// ```
+    /// use ox_idl::ast::{FormalKind, TemplateModule};
+    /// use ox_idl::keyword::Keyword;
+    /// use chumsky::prelude::*;
+    ///
+    /// let t = TemplateModule::parser()
+    ///     .parse("module Buffer<typename T, const long N> { typedef T Items[N]; };")
+    ///     .unwrap();
+    /// assert_eq!(t.params.len(), 2);
+    /// assert_eq!(t.params[0].kind, FormalKind::Type(Keyword::TypeName));
+    /// assert!(matches!(t.params[1].kind, FormalKind::Const(_)));
+    /// ```
+    pub fn parser() -> impl Parser<char, TemplateModule, Error = Simple<char>> {
+        Self::parser_with(Definition::parser())
+    }
+
+    /// Builds a template module parser using the given parser for the definitions
+    /// within it
+    pub fn parser_with(
+        definition: impl Parser<char, Definition, Error = Simple<char>> + Clone,
+    ) -> impl Parser<char, TemplateModule, Error = Simple<char>> {
+        // 7.4.12.3
+        // <template_module_dcl> ::= "module" <identifier> "<" <formal_parameters> ">"
+        //                           "{" <tpl_definition>+ "}"
+        // <formal_parameters> ::= <formal_parameter> {"," <formal_parameter>}*
+        // <tpl_definition> ::= <definition> | <template_module_ref> ";"
+        let definitions = definition
+            .map(TemplateDefinition::Definition)
+            .or(TemplateAlias::parser().map(TemplateDefinition::Alias))
+            .repeated()
+            .at_least(1)
+            .delimited_by(symbol("{"), symbol("}"));
+
+        token(
+            Annotation::parser()
+                .repeated()
+                .then_ignore(keyword(Keyword::Module))
+                .then(token(Identifier::parser()))
+                .then(
+                    FormalParameter::parser()
+                        .separated_by(symbol(","))
+                        .at_least(1)
+                        .delimited_by(symbol("<"), symbol(">")),
+                )
+                .then(definitions)
+                .then_ignore(just(';'))
+                .map_with_span(|(((annotations, name), params), definitions), span| {
+                    TemplateModule {
+                        annotations,
+                        name,
+                        params,
+                        definitions,
+                        span,
+                    }
+                }),
+        )
+    }
+}
+
+impl FormalParameter {
+    /// Builds a parser is able to parse a formal parameter of a template module as
+    /// specified in the IDL Documentation
+    pub fn parser() -> impl Parser<char, FormalParameter, Error = Simple<char>> {
+        // 7.4.12.3
+        // <formal_parameter> ::= <formal_parameter_type> <identifier>
+        // <formal_parameter_type> ::= "typename" | "interface" | "valuetype"
+        //                           | "eventtype" | "struct" | "union"
+        //                           | "exception" | "enum" | "sequence"
+        //                           | "const" <const_type>
+        //                           | <sequence_type>
+        let sequence = TypeSpec::parser().try_map(|ty, span| match ty {
+            TypeSpec::Sequence(_, _, _) => Ok(FormalKind::Sequence(ty)),
+            _ => Err(Simple::custom(span, "expected a template parameter type")),
+        });
+        let kind = choice((
+            keyword(Keyword::TypeName),
+            keyword(Keyword::Interface),
+            keyword(Keyword::ValueType),
+            keyword(Keyword::EventType),
+            keyword(Keyword::Struct),
+            keyword(Keyword::Union),
+            keyword(Keyword::Exception),
+            keyword(Keyword::Enum),
+        ))
+        .map(FormalKind::Type);
+
+        token(
+            choice((
+                keyword(Keyword::Const)
+                    .ignore_then(TypeSpec::parser())
+                    .map(FormalKind::Const),
+                sequence,
+                keyword(Keyword::Sequence).map(FormalKind::Type),
+                kind,
+            ))
+            .then(Identifier::parser())
+            .map_with_span(|(kind, name), span| FormalParameter { kind, name, span }),
+        )
+    }
+}
+
+impl TemplateAlias {
+    /// Builds a parser is able to parse a reference to a template module from
+    /// within another, including its terminating semicolon, as specified in the
+    /// IDL Documentation
+    pub fn parser() -> impl Parser<char, TemplateAlias, Error = Simple<char>> {
+        // 7.4.12.3
+        // <template_module_ref> ::= "alias" <scoped_name> "<" <formal_parameter_names> ">"
+        //                           <identifier>
+        // <formal_parameter_names> ::= <identifier> { "," <identifier>}*
+        token(
+            keyword(Keyword::Alias)
+                .ignore_then(Reference::parser())
+                .then(
+                    token(Identifier::parser())
+                        .separated_by(symbol(","))
+                        .at_least(1)
+                        .delimited_by(symbol("<"), symbol(">")),
+                )
+                .then(token(Identifier::parser()))
+                .then_ignore(just(';'))
+                .map_with_span(|((template, params), name), span| TemplateAlias {
+                    template,
+                    params,
+                    name,
+                    span,
+                }),
+        )
+    }
+}
+
+impl TemplateInstance {
+    /// Builds a parser is able to parse an instantiation of a template module as
+    /// specified in the IDL Documentation
+    ///
+    /// Example
+    ///
+    /// ```
+    /// use ox_idl::ast::{ActualParameter, TemplateInstance};
+    /// use chumsky::prelude::*;
+    ///
+    /// let i = TemplateInstance::parser()
+    ///     .parse("module Buffer<string, MAX + 1> Names;")
+    ///     .unwrap();
+    /// assert_eq!(i.name.as_str(), "Names");
+    /// assert!(matches!(i.params[0], ActualParameter::Type(_)));
+    /// assert!(matches!(i.params[1], ActualParameter::Const(_)));
+    /// ```
+    pub fn parser() -> impl Parser<char, TemplateInstance, Error = Simple<char>> {
+        // 7.4.12.3
+        // <template_module_inst> ::= "module" <scoped_name> "<" <actual_parameters> ">"
+        //                            <identifier>
+        // <actual_parameters> ::= <actual_parameter> { "," <actual_parameter>}*
+        // <actual_parameter> ::= <type_spec> | <const_expr>
+        let param = ConstExpr::parser()
+            .map(|expr| match expr {
+                ConstExpr::Name(name, span) => ActualParameter::Type(TypeSpec::Named(name, span)),
+                expr => ActualParameter::Const(expr),
+            })
+            .or(TypeSpec::parser().map(ActualParameter::Type));
+
+        token(
+            Annotation::parser()
+                .repeated()
+                .then_ignore(keyword(Keyword::Module))
+                .then(Reference::parser())
+                .then(
+                    param
+                        .separated_by(symbol(","))
+                        .at_least(1)
+                        .delimited_by(symbol("<"), symbol(">")),
+                )
+                .then(token(Identifier::parser()))
+                .then_ignore(just(';'))
+                .map_with_span(|(((annotations, template), params), name), span| {
+                    TemplateInstance {
+                        annotations,
+                        template,
+                        params,
+                        name,
+                        span,
+                    }
+                }),
+        )
+    }
+}
+
+impl ActualParameter {
+    /// Returns the span of the parameter
+    pub fn span(&self) -> Range<usize> {
+        match self {
+            ActualParameter::Type(ty) => ty.span(),
+            ActualParameter::Const(expr) => expr.span(),
+        }
+    }
+}
+
+/// Builds a parser for the optional list of interfaces supported by a value type,
+/// component or home
+fn supports() -> impl Parser<char, Vec<Reference>, Error = Simple<char>> {
+    // 7.4.10.3
+    // <supported_interface_spec> ::= "supports" <scoped_name> { "," <scoped_name> }*
+    keyword(Keyword::Supports)
+        .ignore_then(Reference::parser().separated_by(symbol(",")).at_least(1))
+        .or_not()
+        .map(Option::unwrap_or_default)
+}
+
+/// The Forward type represents a forward declaration of a type that is
+/// defined later, such as `struct Node;`
+#[derive(Debug, Clone, PartialEq)]
+pub struct Forward {
+    pub annotations: Vec<Annotation>,
+    /// The keyword naming the kind of type declared, such as `struct`
+    pub keyword: Keyword,
+    /// The `local`, `abstract` or `custom` keyword given before the kind, if any
+    pub modifier: Option<Keyword>,
+    pub name: Identifier,
+    pub span: Range<usize>,
+}
+
+impl Forward {
+    /// Builds a parser is able to parse a forward declaration as specified in the
+    /// IDL Documentation
+    ///
+    /// Example
+    ///
+    /// ```
+    /// use ox_idl::ast::Forward;
+    /// use ox_idl::keyword::Keyword;
+    /// use chumsky::prelude::*;
+    ///
+    /// let f = Forward::parser().parse("struct Node;").unwrap();
+    /// assert_eq!(f.keyword, Keyword::Struct);
+    /// assert_eq!(f.name.as_str(), "Node");
+    /// ```
+    pub fn parser() -> impl Parser<char, Forward, Error = Simple<char>> {
+        // 7.4.1.3
+        // <struct_forward_dcl> ::= "struct" <identifier>
+        // <union_forward_dcl> ::= "union" <identifier>
+        //
+        // 7.4.3.3
+        // <interface_forward_dcl> ::= <interface_kind> <identifier>
+        //
+        // 7.4.5.3
+        // <value_forward_dcl> ::= <value_kind> <identifier>
+        //
+        // 7.4.7.3
+        // <value_forward_dcl> ::= "abstract" "valuetype" <identifier>
+        //
+        // 7.4.8.3
+        // <component_forward_dcl> ::= "component" <identifier>
+        //
+        // 7.4.10.3
+        // <event_forward_dcl> ::= [ "abstract" ] "eventtype" <identifier>
+        //
+        // 7.4.11.3
+        // <porttype_forward_dcl> ::= "porttype" <identifier>
+        let interface = keyword(Keyword::Local)
+            .or(keyword(Keyword::Abstract))
+            .or_not()
+            .then(keyword(Keyword::Interface));
+        let value = keyword(Keyword::Custom)
+            .or(keyword(Keyword::Abstract))
+            .or_not()
+            .then(keyword(Keyword::ValueType));
+        let event = keyword(Keyword::Abstract)
+            .or_not()
+            .then(keyword(Keyword::EventType));
+
+        token(
+            Annotation::parser()
+                .repeated()
+                .then(choice((
+                    keyword(Keyword::Struct).map(|keyword| (None, keyword)),
+                    keyword(Keyword::Union).map(|keyword| (None, keyword)),
+                    keyword(Keyword::Component).map(|keyword| (None, keyword)),
+                    keyword(Keyword::PortType).map(|keyword| (None, keyword)),
+                    interface,
+                    value,
+                    event,
+                )))
+                .then(token(Identifier::parser()))
+                .then_ignore(just(';'))
+                .map_with_span(|((annotations, (modifier, keyword)), name), span| Forward {
+                    annotations,
+                    keyword,
+                    modifier,
+                    name,
+                    span,
+                }),
+        )
+    }
+}
+
+#[cfg(test)]
+mod ast_tests {
+    use crate::ast::{
+        ActualParameter, Annotation, Attribute, Bitmask, Bitset, CaseLabel, Component,
+        ComponentExport, Connector, Const, Declarator, Definition, Enum, Exception, Export,
+        FormalKind, Forward, Home, HomeExport, Interface, Member, Module, Native, Operation,
+        PortType, Specification, Struct, TemplateDefinition, TemplateInstance, TemplateModule,
+        TypeSpec, Typedef, Union, ValueBox, ValueElement, ValueType,
+    };
+    use crate::expr::ConstExpr;
+    use crate::keyword::Keyword;
+    use crate::literal::Literal;
+    use crate::name::{Identifier, ScopedName};
+    use crate::primitive::PrimitiveType;
+    use chumsky::error::SimpleReason;
+    use chumsky::prelude::*;
+
+    #[test]
+    fn parse_annotation() {
+        let parse = |s: &str| Annotation::parser().then_ignore(end()).parse(s);
+
+        let a = parse("@key").unwrap();
+        assert_eq!(a.name, ScopedName::from("key"));
+        assert!(a.params.is_empty());
+        assert_eq!(a.span, 0..4);
+
+        let a = parse("@::M::range (1, 2 + 3) ").unwrap();
+        assert_eq!(a.name, ScopedName::from("::M::range"));
+        assert_eq!(a.params.len(), 2);
+        assert_eq!(a.span, 0..22);
+        assert_eq!(
+            a.params[0].value,
+            ConstExpr::Literal(Literal::Integer(1), 13..14)
+        );
+        assert_eq!(a.params[1].name, None);
+        assert_eq!(a.params[1].span, 16..21);
+
+        let a = parse("@verbatim(language = \"c\", text = /* body */ X )").unwrap();
+        assert_eq!(a.params.len(), 2);
+        assert_eq!(a.params[0].name, Some(Identifier::new("language").unwrap()));
+        assert_eq!(a.params[0].span, 10..24);
+        assert_eq!(a.params[1].name, Some(Identifier::new("text").unwrap()));
+        assert_eq!(
+            a.params[1].value,
+            ConstExpr::Name(ScopedName::from("X"), 44..45)
+        );
+        assert_eq!(a.params[1].span, 26..45);
+
+        // A name that is not followed by `=` is the start of a positional parameter
+        let a = parse("@value(A | B)").unwrap();
+        assert_eq!(a.params[0].name, None);
+        assert_eq!(a.params[0].span, 7..12);
+
+        assert!(parse("@").is_err());
+        assert!(parse("@range(min = )").is_err());
+        assert!(parse("@range(1").is_err());
+    }
+
+    #[test]
+    fn parse_type_spec() {
+        let parse = |s: &str| TypeSpec::parser().then_ignore(end()).parse(s);
+        assert_eq!(
+            parse("long long /* wide */ "),
+            Ok(TypeSpec::Primitive(PrimitiveType::LongLong, 0..9))
+        );
+        assert_eq!(parse("string"), Ok(TypeSpec::String(None, 0..6)));
+        assert_eq!(parse("wstring"), Ok(TypeSpec::WString(None, 0..7)));
+        assert_eq!(parse("fixed"), Ok(TypeSpec::Fixed(None, 0..5)));
+        assert_eq!(
+            parse("strings"),
+            Ok(TypeSpec::Named(ScopedName::from("strings"), 0..7))
+        );
+        assert!(parse("String").is_err());
+    }
+
+    #[test]
+    fn parse_template_types() {
+        let parse = |s: &str| TypeSpec::parser().then_ignore(end()).parse(s);
+        let long = |span| Box::new(TypeSpec::Primitive(PrimitiveType::Long, span));
+        match parse("string < 2 * N >") {
+            Ok(TypeSpec::String(Some(bound), span)) => {
+                assert_eq!(bound.span(), 9..14);
+                assert_eq!(span, 0..16);
+            }
+            t => panic!("unexpected type {:?}", t),
+        }
+        assert_eq!(
+            parse("sequence<long>"),
+            Ok(TypeSpec::Sequence(long(9..13), None, 0..14))
+        );
+        assert_eq!(
+            parse("map < long , long , 8 >"),
+            Ok(TypeSpec::Map(
+                long(6..10),
+                long(13..17),
+                Some(ConstExpr::Literal(Literal::Integer(8), 20..21)),
+                0..23
+            ))
+        );
+        match parse("sequence<sequence<wstring<4>, 2>>") {
+            Ok(TypeSpec::Sequence(inner, None, span)) => {
+                assert_eq!(span, 0..33);
+                assert!(matches!(
+                    *inner,
+                    TypeSpec::Sequence(ref element, Some(_), _)
+                        if matches!(**element, TypeSpec::WString(Some(_), _))
+                ));
+            }
+            t => panic!("unexpected type {:?}", t),
+        }
+        assert!(matches!(
+            parse("fixed<9, 2>"),
+            Ok(TypeSpec::Fixed(Some(_), _))
+        ));
+        assert_eq!(parse("any"), Ok(TypeSpec::Any(0..3)));
+
+        assert!(parse("sequence").is_err());
+        assert!(parse("sequence<>").is_err());
+        assert!(parse("string<>").is_err());
+        assert!(parse("map<long>").is_err());
+        assert!(parse("fixed<9>").is_err());
+    }
+
+    #[test]
+    fn parse_const() {
+        let c = Const::parser()
+            .parse("@verbatim(\"x\") const unsigned long SIZE = 1 << 4 ;")
+            .unwrap();
+        assert_eq!(c.annotations.len(), 1);
+        assert_eq!(
+            c.ty,
+            TypeSpec::Primitive(PrimitiveType::UnsignedLong, 21..34)
+        );
+        assert_eq!(c.name, Identifier::new("SIZE").unwrap());
+        assert_eq!(c.value.span(), 42..48);
+        assert_eq!(c.span, 0..50);
+
+        let errors = Const::parser().parse("const long Long = 1;").unwrap_err();
+        assert_eq!(errors[0].span(), 11..15);
+        assert_eq!(
+            errors[0].reason(),
+            &SimpleReason::Custom("`Long` collides with keyword `long`".to_string())
+        );
+        assert!(Const::parser().parse("const long X = 1").is_err());
+        assert!(Const::parser().parse("const long X;").is_err());
+    }
+
+    #[test]
+    fn parse_native() {
+        let n = Native::parser().parse("@a @b native  _native ;").unwrap();
+        assert_eq!(n.annotations.len(), 2);
+        assert_eq!(n.name, Identifier::new("_native").unwrap());
+        assert_eq!(n.span, 0..23);
+    }
+
+    #[test]
+    fn parse_declarator() {
+        let d = Declarator::parser().then_ignore(end()).parse("x ").unwrap();
+        assert_eq!(d.name, Identifier::new("x").unwrap());
+        assert!(d.dimensions.is_empty());
+        assert_eq!(d.span, 0..1);
+
+        let d = Declarator::parser()
+            .then_ignore(end())
+            .parse("grid [ N ] [2 * N] ")
+            .unwrap();
+        assert_eq!(
+            d.dimensions[0],
+            ConstExpr::Name(ScopedName::from("N"), 7..8)
+        );
+        assert_eq!(d.dimensions[1].span(), 12..17);
+        assert_eq!(d.span, 0..18);
+
+        assert!(Declarator::parser()
+            .then_ignore(end())
+            .parse("x[]")
+            .is_err());
+        assert!(Declarator::parser()
+            .then_ignore(end())
+            .parse("x[2")
+            .is_err());
+    }
+
+    #[test]
+    fn parse_member() {
+        let m = Member::parser()
+            .parse("@key unsigned long a, b[2] ;")
+            .unwrap();
+        assert_eq!(m.annotations.len(), 1);
+        assert_eq!(
+            m.ty,
+            TypeSpec::Primitive(PrimitiveType::UnsignedLong, 5..18)
+        );
+        assert_eq!(m.declarators.len(), 2);
+        assert_eq!(m.declarators[1].span, 22..26);
+        assert_eq!(m.span, 0..28);
+
+        assert!(Member::parser().parse("long;").is_err());
+        assert!(Member::parser().parse("long a,;").is_err());
+        assert!(Member::parser().parse("long a").is_err());
+    }
+
+    #[test]
+    fn parse_typedef() {
+        let t = Typedef::parser()
+            .parse("typedef Point Line[2], Other ;")
+            .unwrap();
+        assert_eq!(t.ty, TypeSpec::Named(ScopedName::from("Point"), 8..13));
+        assert_eq!(t.declarators.len(), 2);
+        assert_eq!(t.declarators[1].span, 23..28);
+        assert_eq!(t.span, 0..30);
+
+        assert!(Typedef::parser().parse("typedef long;").is_err());
+        assert!(Typedef::parser().parse("typedef A B").is_err());
+    }
+
+    #[test]
+    fn parse_struct() {
+        let s = Struct::parser()
+            .parse("struct Point {\n  double x, y;\n  string label;\n};")
+            .unwrap();
+        assert_eq!(s.name, Identifier::new("Point").unwrap());
+        assert_eq!(s.base, None);
+        assert_eq!(s.members.len(), 2);
+        assert_eq!(s.span, 0..48);
+
+        // Inheritance and empty structs
+        let s = Struct::parser()
+            .parse("struct Empty : ::M::Base {};")
+            .unwrap();
+        let base = s.base.unwrap();
+        assert_eq!(base.name, ScopedName::from("::M::Base"));
+        assert_eq!(base.span, 15..24);
+        assert!(s.members.is_empty());
+        assert!(Struct::parser().parse("struct Empty {};").is_ok());
+
+        assert!(Struct::parser().parse("struct S : A, B {};").is_err());
+        assert!(Struct::parser().parse("struct S { long x; }").is_err());
+        assert!(Struct::parser().parse("struct S { long x };").is_err());
+    }
+
+    #[test]
+    fn parse_union() {
+        let u = Union::parser()
+            .parse(
+                "union Shape switch (unsigned short) {\n  case 1: case TWO: double r;\n  default: @a long n[2];\n};",
+            )
+            .unwrap();
+        assert_eq!(u.name, Identifier::new("Shape").unwrap());
+        assert_eq!(
+            u.discriminator,
+            TypeSpec::Primitive(PrimitiveType::UnsignedShort, 20..34)
+        );
+        assert_eq!(u.cases.len(), 2);
+        assert_eq!(
+            u.cases[0].labels,
+            vec![
+                CaseLabel::Value(ConstExpr::Literal(Literal::Integer(1), 45..46)),
+                CaseLabel::Value(ConstExpr::Name(ScopedName::from("TWO"), 53..56)),
+            ]
+        );
+        assert_eq!(u.cases[0].span, 40..67);
+        assert_eq!(u.cases[1].labels, vec![CaseLabel::Default(70..77)]);
+        assert_eq!(u.cases[1].annotations.len(), 1);
+        assert_eq!(u.cases[1].declarator.dimensions.len(), 1);
+
+        // Each case declares exactly one element and there must be at least one case
+        assert!(Union::parser()
+            .parse("union U switch (long) { case 1: long a, b; };")
+            .is_err());
+        assert!(Union::parser().parse("union U switch (long) { };").is_err());
+        assert!(Union::parser()
+            .parse("union U switch (long) { long a; };")
+            .is_err());
+        assert!(Union::parser()
+            .parse("union U switch long { case 1: long a; };")
+            .is_err());
+
+        let f = Forward::parser().parse("union U;").unwrap();
+        assert_eq!(f.keyword, Keyword::Union);
+    }
+
+    #[test]
+    fn parse_enum() {
+        let e = Enum::parser()
+            .parse("enum Color {\n  RED,\n  @value(4) GREEN ,\n  BLUE\n};")
+            .unwrap();
+        assert_eq!(e.name, Identifier::new("Color").unwrap());
+        let names = e
+            .enumerators
+            .iter()
+            .map(|e| e.name.to_string())
+            .collect::<Vec<_>>();
+        assert_eq!(names, vec!["RED", "GREEN", "BLUE"]);
+        assert_eq!(
+            e.enumerators[1].annotations[0].name,
+            ScopedName::from("value")
+        );
+        assert_eq!(e.enumerators[1].span, 22..37);
+        assert_eq!(e.span, 0..49);
+
+        assert!(Enum::parser().parse("enum E {};").is_err());
+        assert!(Enum::parser().parse("enum E { A, };").is_err());
+        assert!(Enum::parser().parse("enum E { A B };").is_err());
+    }
+
+    #[test]
+    fn parse_bitmask() {
+        let b = Bitmask::parser()
+            .parse("@bit_bound(8) bitmask Flags { READ, @position(4) WRITE };")
+            .unwrap();
+        assert_eq!(b.annotations[0].name, ScopedName::from("bit_bound"));
+        let names = b
+            .values
+            .iter()
+            .map(|v| v.name.to_string())
+            .collect::<Vec<_>>();
+        assert_eq!(names, vec!["READ", "WRITE"]);
+        assert_eq!(b.values[1].span, 36..54);
+        assert_eq!(b.span, 0..57);
+
+        assert!(Bitmask::parser().parse("bitmask B {};").is_err());
+        assert!(Bitmask::parser().parse("bitmask B { A, };").is_err());
+    }
+
+    #[test]
+    fn parse_bitset() {
+        let b = Bitset::parser()
+            .parse("bitset B : A {\n  bitfield<3> x;\n  bitfield < 2 , octet > ;\n  bitfield<1> y, z;\n};")
+            .unwrap();
+        assert_eq!(b.base.unwrap().name, ScopedName::from("A"));
+        assert_eq!(b.bitfields.len(), 3);
+        assert!(b.bitfields[0].ty.is_none());
+        assert_eq!(b.bitfields[0].span, 17..31);
+        assert!(matches!(
+            b.bitfields[1].ty,
+            Some(TypeSpec::Primitive(PrimitiveType::Octet, _))
+        ));
+        assert!(b.bitfields[1].names.is_empty());
+        assert_eq!(b.bitfields[2].names.len(), 2);
+
+        assert!(Bitset::parser().parse("bitset B {};").is_ok());
+        assert!(Bitset::parser().parse("bitset B { bitfield x; };").is_err());
+        assert!(Bitset::parser()
+            .parse("bitset B { bitfield<1> x y; };")
+            .is_err());
+    }
+
+    #[test]
+    fn parse_forward() {
+        let f = Forward::parser().parse("struct Node ;").unwrap();
+        assert_eq!(f.keyword, Keyword::Struct);
+        assert_eq!(f.name, Identifier::new("Node").unwrap());
+        assert_eq!(f.span, 0..13);
+
+        let spec = Specification::parser()
+            .parse("struct Node; struct Node { long value; sequence_t next; };")
+            .unwrap();
+        assert!(matches!(spec.definitions[0], Definition::Forward(_)));
+        assert!(matches!(spec.definitions[1], Definition::Struct(_)));
+
+        let f = Forward::parser()
+            .parse("abstract interface Shape;")
+            .unwrap();
+        assert_eq!(f.keyword, Keyword::Interface);
+        assert_eq!(f.modifier, Some(Keyword::Abstract));
+        assert!(Forward::parser().parse("local struct S;").is_err());
+    }
+
+    #[test]
+    fn parse_interface() {
+        let i = Interface::parser()
+            .parse(
+                "interface Account : Named, ::M::Audited {\n  typedef long Amount;\n  attribute Amount limit;\n  oneway void ping();\n};",
+            )
+            .unwrap();
+        assert_eq!(i.modifier, None);
+        let bases = i
+            .bases
+            .iter()
+            .map(|b| b.name.to_string())
+            .collect::<Vec<_>>();
+        assert_eq!(bases, vec!["Named", "::M::Audited"]);
+        assert_eq!(i.exports.len(), 3);
+        assert!(matches!(
+            i.exports[0],
+            Export::Definition(Definition::Typedef(_))
+        ));
+        match &i.exports[2] {
+            Export::Operation(o) => {
+                assert!(o.oneway);
+                assert_eq!(o.return_type, None);
+                assert!(o.params.is_empty());
+                assert_eq!(o.span, 93..112);
+            }
+            e => panic!("unexpected export {:?}", e),
+        }
+        assert_eq!(i.span, 0..115);
+
+        assert!(Interface::parser().parse("interface I {};").is_ok());
+        assert!(Interface::parser().parse("interface I : {};").is_err());
+
+        let errors = Interface::parser()
+            .parse("interface I { module M { native N; }; };")
+            .unwrap_err();
+        assert!(errors.iter().any(|e| matches!(
+            e.reason(),
+            SimpleReason::Custom(message)
+                if message == "modules and interfaces cannot be declared within an interface"
+        )));
+    }
+
+    #[test]
+    fn parse_operation() {
+        let o = Operation::parser()
+            .parse("@a string<4> name (in long a, inout sequence<long> b , out T c) raises ( X , Y ) ;")
+            .unwrap();
+        assert_eq!(o.annotations.len(), 1);
+        assert!(matches!(o.return_type, Some(TypeSpec::String(Some(_), _))));
+        let directions = o
+            .params
+            .iter()
+            .map(|p| p.direction.clone())
+            .collect::<Vec<_>>();
+        assert_eq!(directions, vec![Keyword::In, Keyword::InOut, Keyword::Out]);
+        assert_eq!(o.params[1].span, 30..52);
+        let raises = o.raises.unwrap();
+        assert_eq!(raises.keyword, Keyword::Raises);
+        assert_eq!(raises.exceptions.len(), 2);
+        assert_eq!(raises.span, 64..80);
+        assert_eq!(o.span, 0..82);
+
+        assert!(Operation::parser().parse("void f(long a);").is_err());
+        assert!(Operation::parser().parse("void f(in long a,);").is_err());
+        assert!(Operation::parser().parse("void f() raises ();").is_err());
+    }
+
+    #[test]
+    fn parse_exception() {
+        let e = Exception::parser()
+            .parse("exception Failed {\n  string reason;\n};")
+            .unwrap();
+        assert_eq!(e.name, Identifier::new("Failed").unwrap());
+        assert_eq!(e.members.len(), 1);
+        assert_eq!(e.span, 0..38);
+
+        let spec = Specification::parser()
+            .parse("exception Empty {}; interface I { exception Inner {}; };")
+            .unwrap();
+        assert!(matches!(spec.definitions[0], Definition::Exception(_)));
+        assert!(Exception::parser().parse("exception E;").is_err());
+    }
+
+    #[test]
+    fn parse_value_type() {
+        let v = ValueType::parser()
+            .parse(
+                "custom valuetype Account : truncatable Base, ::M::Named supports I {\n  public long id;\n  private string<8> a, b[2];\n  factory create(in long id) raises (E);\n  void close();\n};",
+            )
+            .unwrap();
+        assert_eq!(v.modifier, Some(Keyword::Custom));
+        assert!(v.truncatable);
+        let bases = v
+            .bases
+            .iter()
+            .map(|b| b.name.to_string())
+            .collect::<Vec<_>>();
+        assert_eq!(bases, vec!["Base", "::M::Named"]);
+        assert_eq!(v.supports[0].span, 65..66);
+        assert_eq!(v.elements.len(), 4);
+        match &v.elements[1] {
+            ValueElement::State(member) => {
+                assert_eq!(member.visibility, Keyword::Private);
+                assert_eq!(member.declarators.len(), 2);
+                assert_eq!(member.span, 89..115);
+            }
+            e => panic!("unexpected element {:?}", e),
+        }
+        match &v.elements[2] {
+            ValueElement::Factory(factory) => {
+                assert_eq!(factory.name, Identifier::new("create").unwrap());
+                assert_eq!(factory.params.len(), 1);
+                assert!(factory.raises.is_some());
+            }
+            e => panic!("unexpected element {:?}", e),
+        }
+        assert!(matches!(
+            v.elements[3],
+            ValueElement::Export(Export::Operation(_))
+        ));
+
+        let v = ValueType::parser()
+            .parse("abstract valuetype Shape supports A, B {};")
+            .unwrap();
+        assert_eq!(v.modifier, Some(Keyword::Abstract));
+        assert!(!v.truncatable);
+        assert!(v.bases.is_empty());
+        assert_eq!(v.supports.len(), 2);
+
+        assert!(ValueType::parser().parse("valuetype V : {};").is_err());
+        assert!(ValueType::parser()
+            .parse("valuetype V supports {};")
+            .is_err());
+        assert!(ValueType::parser()
+            .parse("valuetype V { public x; };")
+            .is_err());
+        assert!(ValueType::parser().parse("local valuetype V {};").is_err());
+
+        let errors = ValueType::parser()
+            .parse("valuetype V { valuetype W long; };")
+            .unwrap_err();
+        assert!(errors.iter().any(|e| matches!(
+            e.reason(),
+            SimpleReason::Custom(message)
+                if message == "value types cannot be declared within an interface or value type"
+        )));
+    }
+
+    #[test]
+    fn parse_value_box() {
+        let b = ValueBox::parser()
+            .parse("valuetype Names sequence<string> ;")
+            .unwrap();
+        assert_eq!(b.name, Identifier::new("Names").unwrap());
+        assert!(matches!(b.ty, TypeSpec::Sequence(_, None, _)));
+        assert_eq!(b.span, 0..34);
+
+        let spec = Specification::parser()
+            .parse("valuetype A; abstract valuetype B; valuetype C long; valuetype D {};")
+            .unwrap();
+        let kinds = spec
+            .definitions
+            .iter()
+            .map(|d| match d {
+                Definition::Forward(f) => format!("forward {:?}", f.modifier),
+                Definition::ValueBox(_) => "box".to_string(),
+                Definition::ValueType(_) => "value".to_string(),
+                d => panic!("unexpected definition {:?}", d),
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            kinds,
+            vec!["forward None", "forward Some(Abstract)", "box", "value"]
+        );
+        assert!(ValueBox::parser().parse("valuetype V;").is_err());
+    }
+
+    #[test]
+    fn parse_event_type() {
+        let e = ValueType::parser()
+            .parse("abstract eventtype Alarm : Base supports Named { void ack(); };")
+            .unwrap();
+        assert_eq!(e.keyword, Keyword::EventType);
+        assert_eq!(e.modifier, Some(Keyword::Abstract));
+        assert_eq!(e.bases.len(), 1);
+
+        let spec = Specification::parser()
+            .parse("eventtype A; abstract eventtype B; custom eventtype C { public long x; };")
+            .unwrap();
+        let Definition::Forward(f) = &spec.definitions[1] else {
+            panic!("expected a forward declaration");
+        };
+        assert_eq!(f.keyword, Keyword::EventType);
+        assert_eq!(f.modifier, Some(Keyword::Abstract));
+        assert!(matches!(
+            &spec.definitions[2],
+            Definition::ValueType(v) if v.keyword == Keyword::EventType
+        ));
+        assert!(Specification::parser()
+            .parse("custom eventtype C;")
+            .is_err());
+        assert!(Specification::parser().parse("eventtype E long;").is_err());
+    }
+
+    #[test]
+    fn parse_component() {
+        let c = Component::parser()
+            .parse(
+                "component Sensor : Base supports A, B {\n  provides Object obj;\n  uses multiple ::M::Log logs;\n  emits Alarm alarm;\n  publishes Tick tick;\n  consumes Reset reset;\n  readonly attribute long rate;\n};",
+            )
+            .unwrap();
+        assert_eq!(c.name, Identifier::new("Sensor").unwrap());
+        assert_eq!(c.base.unwrap().name, ScopedName::from("Base"));
+        assert_eq!(c.supports.len(), 2);
+        let ports = c
+            .exports
+            .iter()
+            .filter_map(|e| match e {
+                ComponentExport::Port(p) => Some((p.keyword.clone(), p.multiple)),
+                ComponentExport::Attribute(_) => None,
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            ports,
+            vec![
+                (Keyword::Provides, false),
+                (Keyword::Uses, true),
+                (Keyword::Emits, false),
+                (Keyword::Publishes, false),
+                (Keyword::Consumes, false),
+            ]
+        );
+        match &c.exports[1] {
+            ComponentExport::Port(p) => {
+                assert_eq!(p.ty, TypeSpec::Named(ScopedName::from("::M::Log"), 79..87));
+                assert_eq!(p.span, 65..93);
+            }
+            e => panic!("unexpected export {:?}", e),
+        }
+        match &c.exports[0] {
+            ComponentExport::Port(p) => assert_eq!(p.ty, TypeSpec::Object(51..57)),
+            e => panic!("unexpected export {:?}", e),
+        }
+        assert!(matches!(c.exports[5], ComponentExport::Attribute(_)));
+
+        let f = Forward::parser().parse("component C;").unwrap();
+        assert_eq!(f.keyword, Keyword::Component);
+        assert!(Component::parser().parse("component C : A, B {};").is_err());
+        assert!(Component::parser()
+            .parse("component C { void f(); };")
+            .is_err());
+        assert!(Component::parser()
+            .parse("component C { provides multiple I i; };")
+            .is_err());
+    }
+
+    #[test]
+    fn parse_home() {
+        let h = Home::parser()
+            .parse(
+                "home Maker : Base supports I manages Sensor primarykey Key {\n  factory create(in long id) raises (E);\n  finder find(in Key key);\n  void reset();\n};",
+            )
+            .unwrap();
+        assert_eq!(h.base.unwrap().name, ScopedName::from("Base"));
+        assert_eq!(h.supports.len(), 1);
+        assert_eq!(h.manages.name, ScopedName::from("Sensor"));
+        assert_eq!(h.manages.span, 37..43);
+        assert_eq!(h.primary_key.unwrap().name, ScopedName::from("Key"));
+        let kinds = h
+            .exports
+            .iter()
+            .map(|e| match e {
+                HomeExport::Factory(f) => f.keyword.to_string(),
+                HomeExport::Export(_) => "export".to_string(),
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(kinds, vec!["factory", "finder", "export"]);
+
+        assert!(Home::parser().parse("home H {};").is_err());
+        assert!(Home::parser()
+            .parse("home H manages C primarykey {};")
+            .is_err());
+        assert!(Home::parser()
+            .parse("home H primarykey K manages C {};")
+            .is_err());
+    }
+
+    #[test]
+    fn parse_port_type() {
+        let p = PortType::parser()
+            .parse("porttype Data {\n  uses multiple Reader readers;\n  port Control control;\n  attribute long depth;\n  mirrorport Status status;\n};")
+            .unwrap();
+        assert_eq!(p.name, Identifier::new("Data").unwrap());
+        let kinds = p
+            .exports
+            .iter()
+            .map(|e| match e {
+                ComponentExport::Port(p) => p.keyword.to_string(),
+                ComponentExport::Attribute(_) => "attribute".to_string(),
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(kinds, vec!["uses", "port", "attribute", "mirrorport"]);
+        match &p.exports[0] {
+            ComponentExport::Port(port) => {
+                let mirrored = port.mirrored();
+                assert_eq!(mirrored.keyword, Keyword::Provides);
+                assert!(!mirrored.multiple);
+                assert_eq!(mirrored.mirrored().keyword, Keyword::Uses);
+            }
+            e => panic!("unexpected export {:?}", e),
+        }
+        match &p.exports[1] {
+            ComponentExport::Port(port) => {
+                assert_eq!(port.span, 50..71);
+                assert_eq!(port.mirrored().keyword, Keyword::MirrorPort);
+            }
+            e => panic!("unexpected export {:?}", e),
+        }
+
+        // A porttype must start with a port, and may not contain event ports
+        assert!(PortType::parser().parse("porttype P {};").is_err());
+        assert!(PortType::parser()
+            .parse("porttype P { attribute long a; provides I i; };")
+            .is_err());
+        let errors = PortType::parser()
+            .parse("porttype P { provides I i; consumes E e; };")
+            .unwrap_err();
+        assert!(errors.iter().any(|e| e.span() == (27..40)
+            && matches!(
+                e.reason(),
+                SimpleReason::Custom(message)
+                    if message == "`consumes` ports cannot be declared within a porttype or connector"
+            )));
+
+        let f = Forward::parser().parse("porttype P;").unwrap();
+        assert_eq!(f.keyword, Keyword::PortType);
+        assert!(Specification::parser()
+            .parse("interface I { porttype P { provides I i; }; };")
+            .is_err());
+    }
+
+    #[test]
+    fn parse_connector() {
+        let c = Connector::parser()
+            .parse("connector Pipe : ::M::Base { attribute string topic; mirrorport Data input; };")
+            .unwrap();
+        assert_eq!(c.base.unwrap().name, ScopedName::from("::M::Base"));
+        assert_eq!(c.exports.len(), 2);
+        assert!(matches!(c.exports[0], ComponentExport::Attribute(_)));
+
+        let spec = Specification::parser()
+            .parse("component C { port Data data; mirrorport Data other; }; connector D { port Data a; };")
+            .unwrap();
+        assert!(matches!(spec.definitions[0], Definition::Component(_)));
+        assert!(matches!(spec.definitions[1], Definition::Connector(_)));
+
+        assert!(Connector::parser().parse("connector C {};").is_err());
+        assert!(Connector::parser()
+            .parse("connector C : A, B { port P p; };")
+            .is_err());
+        assert!(Connector::parser()
+            .parse("connector C { emits E e; };")
+            .is_err());
+        assert!(Forward::parser().parse("connector C;").is_err());
+    }
+
+    #[test]
+    fn parse_template_module() {
+        let t = TemplateModule::parser()
+            .parse("module Tmpl<typename T, sequence S, const long N, sequence<T, N> Items, struct P> {\n  typedef T Array[N];\n  alias Other<T, N> Nested;\n};")
+            .unwrap();
+        assert_eq!(t.name, Identifier::new("Tmpl").unwrap());
+        assert_eq!(
+            t.params.iter().map(|p| p.kind.clone()).collect::<Vec<_>>(),
+            vec![
+                FormalKind::Type(Keyword::TypeName),
+                FormalKind::Type(Keyword::Sequence),
+                FormalKind::Const(TypeSpec::Primitive(PrimitiveType::Long, 42..46)),
+                FormalKind::Sequence(TypeSpec::Sequence(
+                    Box::new(TypeSpec::Named(ScopedName::from("T"), 59..60)),
+                    Some(ConstExpr::Name(ScopedName::from("N"), 62..63)),
+                    50..64
+                )),
+                FormalKind::Type(Keyword::Struct),
+            ]
+        );
+        assert_eq!(t.params[1].span, 24..34);
+        assert!(matches!(
+            t.definitions[0],
+            TemplateDefinition::Definition(Definition::Typedef(_))
+        ));
+        match &t.definitions[1] {
+            TemplateDefinition::Alias(alias) => {
+                assert_eq!(alias.template.name, ScopedName::from("Other"));
+                assert_eq!(
+                    alias.params,
+                    vec![Identifier::new("T").unwrap(), Identifier::new("N").unwrap()]
+                );
+                assert_eq!(alias.name, Identifier::new("Nested").unwrap());
+                assert_eq!(alias.span, 108..133);
+            }
+            d => panic!("unexpected definition {:?}", d),
+        }
+
+        assert!(TemplateModule::parser()
+            .parse("module T<> { native N; };")
+            .is_err());
+        assert!(TemplateModule::parser()
+            .parse("module T<typename X> {};")
+            .is_err());
+        assert!(TemplateModule::parser()
+            .parse("module T<long X> { native N; };")
+            .is_err());
+        assert!(Specification::parser()
+            .parse("module M { alias T<X> Y; };")
+            .is_err());
+        assert!(Specification::parser()
+            .parse("interface I { module T<typename X> { native N; }; };")
+            .is_err());
+    }
+
+    #[test]
+    fn parse_template_instance() {
+        let i = TemplateInstance::parser()
+            .parse("module ::M::Tmpl<long, sequence<short>, 2 * SIZE, Point, LIMIT> Inst;")
+            .unwrap();
+        assert_eq!(i.template.name, ScopedName::from("::M::Tmpl"));
+        assert_eq!(i.template.span, 7..16);
+        assert_eq!(i.name, Identifier::new("Inst").unwrap());
+        assert_eq!(
+            i.params[0],
+            ActualParameter::Type(TypeSpec::Primitive(PrimitiveType::Long, 17..21))
+        );
+        assert!(matches!(
+            i.params[1],
+            ActualParameter::Type(TypeSpec::Sequence(_, None, _))
+        ));
+        assert!(matches!(i.params[2], ActualParameter::Const(_)));
+        assert_eq!(i.params[2].span(), 40..48);
+        // A scoped name on its own is parsed as a type, whatever it names
+        assert_eq!(
+            i.params[4],
+            ActualParameter::Type(TypeSpec::Named(ScopedName::from("LIMIT"), 57..62))
+        );
+
+        let spec = Specification::parser()
+            .parse("module M { module T<typename X> { typedef X Y; }; module T<long> L; };")
+            .unwrap();
+        let Definition::Module(m) = &spec.definitions[0] else {
+            panic!("expected a module");
+        };
+        assert!(matches!(m.definitions[0], Definition::TemplateModule(_)));
+        assert!(matches!(m.definitions[1], Definition::TemplateInstance(_)));
+        assert!(TemplateInstance::parser()
+            .parse("module T<> Inst;")
+            .is_err());
+        assert!(TemplateInstance::parser().parse("module T<long>;").is_err());
+    }
+
+    #[test]
+    fn parse_attribute() {
+        let a = Attribute::parser()
+            .parse("readonly attribute long x raises (E);")
+            .unwrap();
+        assert!(a.readonly);
+        assert_eq!(a.get_raises.unwrap().keyword, Keyword::Raises);
+        assert_eq!(a.set_raises, None);
+
+        let a = Attribute::parser()
+            .parse("attribute long x setraises (E);")
+            .unwrap();
+        assert_eq!(a.get_raises, None);
+        assert_eq!(a.set_raises.unwrap().keyword, Keyword::SetRaises);
+
+        assert!(Attribute::parser()
+            .parse("readonly attribute long x setraises (E);")
+            .is_err());
+        assert!(Attribute::parser()
+            .parse("attribute long x setraises (E) getraises (F);")
+            .is_err());
+
+        let errors = Attribute::parser()
+            .parse("attribute long x, y getraises (E);")
+            .unwrap_err();
+        assert_eq!(
+            errors[0].reason(),
+            &SimpleReason::Custom(
+                "exceptions may only be raised by an attribute declared on its own".to_string()
+            )
+        );
+    }
+
+    #[test]
+    fn parse_module() {
+        let m = Module::parser()
+            .parse("@a module Outer {\n  module Inner { const long X = 1; };\n  native N;\n};")
+            .unwrap();
+        assert_eq!(m.annotations.len(), 1);
+        assert_eq!(m.name, Identifier::new("Outer").unwrap());
+        assert_eq!(m.span, 0..70);
+        assert_eq!(m.definitions.len(), 2);
+        let Definition::Module(inner) = &m.definitions[0] else {
+            panic!("expected a module");
+        };
+        assert_eq!(inner.name, Identifier::new("Inner").unwrap());
+        assert_eq!(inner.span, 20..55);
+
+        // Modules must contain at least one definition and end with a semicolon
+        assert!(Module::parser().parse("module M {};").is_err());
+        assert!(Module::parser().parse("module M { native N; }").is_err());
+        assert!(Module::parser().parse("module { native N; };").is_err());
+    }
+
+    #[test]
+    fn merge_modules() {
+        let spec = Specification::parser()
+            .parse(
+                "
+                @x module a { module b { native B1; }; native A1; };
+                module c { native C1; };
+                @x module a { native A2; module b { native B2; }; };
+                module A { native A3; };
+                ",
+            )
+            .unwrap();
+        let names = |definitions: &Vec<Definition>| {
+            definitions
+                .iter()
+                .map(|d| match d {
+                    Definition::Module(m) => m.name.to_string(),
+                    Definition::Native(n) => n.name.to_string(),
+                    _ => panic!("unexpected definition"),
+                })
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(names(&spec.definitions), vec!["a", "c", "a", "A"]);
+
+        let merged = Module::merge(spec.definitions);
+        assert_eq!(names(&merged), vec!["a", "c", "A"]);
+        let Definition::Module(a) = &merged[0] else {
+            panic!("expected a module");
+        };
+        assert_eq!(a.annotations.len(), 1);
+        assert_eq!(names(&a.definitions), vec!["b", "A1", "A2"]);
+        let Definition::Module(b) = &a.definitions[0] else {
+            panic!("expected a module");
+        };
+        assert_eq!(names(&b.definitions), vec!["B1", "B2"]);
+    }
+
+    #[test]
+    fn parse_specification() {
+        let source = "
+            /* Constants */
+            const long A = 1; // one
+            const string S = \"a\" \"b\";
+
+            native N;
+            ";
+        let spec = Specification::parser().parse(source).unwrap();
+        assert_eq!(spec.definitions.len(), 3);
+        let Definition::Const(s) = &spec.definitions[1] else {
+            panic!("expected a const");
+        };
+        let start = source.find("\"a\"").unwrap();
+        assert_eq!(
+            s.value,
+            ConstExpr::Literal(Literal::Str("ab".to_string()), start..start + 7)
+        );
+
+        assert_eq!(
+            Specification::parser().parse(" // nothing\n"),
+            Ok(Specification {
+                definitions: vec![]
+            })
+        );
+        assert!(Specification::parser().parse("native N; native").is_err());
+
+        // Malformed wide literals are reported as such, rather than as a name `L`
+        let errors = Specification::parser()
+            .parse("const wstring S = L\"a\\0b\";")
+            .unwrap_err();
+        assert_eq!(
+            errors[0].reason(),
+            &SimpleReason::Custom(
+                "a wide string literal shall not contain the wide character with value zero"
+                    .to_string()
+            )
+        );
+        assert_eq!(errors[0].span(), 21..23);
+    }
+}
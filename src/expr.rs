@@ -0,0 +1,1167 @@
+/**********************************************************************************
+ * Copyright © 2022 Michael Volling
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy of
+ * this software and associated documentation files (the “Software”), to deal in
+ * the Software without restriction, including without limitation the rights to
+ * use, copy, modify, merge, publish, distribute, sublicense, and/or sell copies of
+ * the Software, and to permit persons to whom the Software is furnished to do so,
+ * subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED “AS IS”, WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY, FITNESS
+ * FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE AUTHORS OR
+ * COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER
+ * IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN
+ * CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+ *********************************************************************************/
+
+use chumsky::prelude::*;
+
+use std::collections::HashMap;
+use std::fmt::Display;
+use std::ops::Range;
+
+use crate::fixed::Fixed;
+use crate::lexer::{symbol, token};
+use crate::literal::{FloatPrecision, Literal};
+use crate::name::{Identifier, ScopedName};
+use crate::primitive::PrimitiveType;
+
+/// The UnaryOp enum lists the unary operators of IDL constant expressions
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UnaryOp {
+    /// `-` negates a number
+    Negate,
+    /// `+` leaves a number unchanged
+    Plus,
+    /// `~` takes the bitwise complement of an integer
+    Complement,
+}
+
+impl Display for UnaryOp {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            UnaryOp::Negate => "-",
+            UnaryOp::Plus => "+",
+            UnaryOp::Complement => "~",
+        })
+    }
+}
+
+/// The BinaryOp enum lists the binary operators of IDL constant expressions
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BinaryOp {
+    /// `|` bitwise or
+    Or,
+    /// `^` bitwise exclusive or
+    Xor,
+    /// `&` bitwise and
+    And,
+    /// `<<` shift left
+    ShiftLeft,
+    /// `>>` shift right
+    ShiftRight,
+    /// `+` addition
+    Add,
+    /// `-` subtraction
+    Subtract,
+    /// `*` multiplication
+    Multiply,
+    /// `/` division
+    Divide,
+    /// `%` remainder
+    Modulo,
+}
+
+impl Display for BinaryOp {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            BinaryOp::Or => "|",
+            BinaryOp::Xor => "^",
+            BinaryOp::And => "&",
+            BinaryOp::ShiftLeft => "<<",
+            BinaryOp::ShiftRight => ">>",
+            BinaryOp::Add => "+",
+            BinaryOp::Subtract => "-",
+            BinaryOp::Multiply => "*",
+            BinaryOp::Divide => "/",
+            BinaryOp::Modulo => "%",
+        })
+    }
+}
+
+/// The ConstExpr type represents an IDL constant expression, with each node
+/// carrying the span of the source it was parsed from
+#[derive(Debug, Clone, PartialEq)]
+pub enum ConstExpr {
+    /// A Literal is a literal value
+    Literal(Literal, Range<usize>),
+    /// A Name is a reference to another constant
+    Name(ScopedName, Range<usize>),
+    /// A Unary is a unary operator applied to an expression
+    Unary(UnaryOp, Box<ConstExpr>, Range<usize>),
+    /// A Binary is a binary operator applied to two expressions
+    Binary(BinaryOp, Box<ConstExpr>, Box<ConstExpr>, Range<usize>),
+}
+
+/// The ConstScope trait resolves the names referenced by constant expressions
+/// to the values of the constants they name
+pub trait ConstScope {
+    /// Returns the value of the constant `name` refers to, or None if it does
+    /// not refer to a constant
+    fn resolve(&self, name: &ScopedName) -> Option<ConstValue>;
+}
+
+impl ConstScope for HashMap<ScopedName, ConstValue> {
+    fn resolve(&self, name: &ScopedName) -> Option<ConstValue> {
+        self.get(name).cloned()
+    }
+}
+
+/// The ConstValue type represents the value of an evaluated constant expression
+///
+/// Unlike literals, values may be negative. Integers are held wider than any
+/// IDL integer type so that results outside of a type's range can be reported.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ConstValue {
+    /// A Bool represents a boolean value
+    Bool(bool),
+    /// A Character represents a character value
+    Character(char),
+    /// A WideCharacter represents a wide character value
+    WideCharacter(char),
+    /// A FixedPoint represents a fixed point value
+    FixedPoint(Fixed),
+    /// A FloatingPoint represents a floating point value
+    FloatingPoint(f64),
+    /// A LongDouble represents a long double value outside of the range of a double,
+    /// held as the decimal text of the literal it was written as including its sign
+    LongDouble(String),
+    /// An Integer represents an integer value
+    Integer(i128),
+    /// A Str represents a string value
+    Str(String),
+    /// A WideStr represents a wstring value
+    WideStr(String),
+    /// An Enumerator represents a value of an enumeration, given by the absolute
+    /// name of the enum, the name of the enumerator and its value
+    Enumerator(ScopedName, Identifier, i32),
+}
+
+impl ConstValue {
+    /// Returns a short description of the kind of the value, for use in diagnostics
+    pub fn kind(&self) -> &'static str {
+        match self {
+            ConstValue::Bool(_) => "boolean",
+            ConstValue::Character(_) => "character",
+            ConstValue::WideCharacter(_) => "wide character",
+            ConstValue::FixedPoint(_) => "fixed point",
+            ConstValue::FloatingPoint(_) | ConstValue::LongDouble(_) => "floating point",
+            ConstValue::Integer(_) => "integer",
+            ConstValue::Str(_) => "string",
+            ConstValue::WideStr(_) => "wide string",
+            ConstValue::Enumerator(_, _, _) => "enumerator",
+        }
+    }
+
+    /// Converts the value into a value of the given type, failing with a
+    /// description of the problem if the value is of the wrong kind or is
+    /// out of the range of the type
+    ///
+    /// Example
+    ///
+    /// ```
+    /// use ox_idl::expr::ConstValue;
+    /// use ox_idl::primitive::PrimitiveType;
+    ///
+    /// let v = ConstValue::Integer(-5);
+    /// assert_eq!(v.clone().coerce(PrimitiveType::Long), Ok(ConstValue::Integer(-5)));
+    /// assert!(v.coerce(PrimitiveType::UnsignedLong).is_err());
+    /// ```
+    pub fn coerce(self, ty: PrimitiveType) -> Result<ConstValue, String> {
+        let wrong_kind = |v: &ConstValue| {
+            format!(
+                "expected a value of type {}, found {} value {}",
+                ty,
+                v.kind(),
+                v
+            )
+        };
+        let out_of_range = |v: &ConstValue| format!("value {} is out of range for type {}", v, ty);
+
+        if let Some((min, max)) = ty.integer_range() {
+            return match self {
+                ConstValue::Integer(v) if v < min || v > max => Err(out_of_range(&self)),
+                ConstValue::Integer(_) => Ok(self),
+                _ => Err(wrong_kind(&self)),
+            };
+        }
+
+        if let Some(precision) = ty.float_precision() {
+            return match self {
+                ConstValue::FloatingPoint(v)
+                    if precision == FloatPrecision::Float && v.abs() > f32::MAX as f64 =>
+                {
+                    Err(out_of_range(&self))
+                }
+                ConstValue::FloatingPoint(_) => Ok(self),
+                ConstValue::LongDouble(_) if precision == FloatPrecision::LongDouble => Ok(self),
+                ConstValue::LongDouble(_) => Err(out_of_range(&self)),
+                ConstValue::Integer(v) => Ok(ConstValue::FloatingPoint(v as f64)),
+                _ => Err(wrong_kind(&self)),
+            };
+        }
+
+        match (ty, &self) {
+            (PrimitiveType::Boolean, ConstValue::Bool(_))
+            | (PrimitiveType::Char, ConstValue::Character(_))
+            | (PrimitiveType::WChar, ConstValue::WideCharacter(_)) => Ok(self),
+            _ => Err(wrong_kind(&self)),
+        }
+    }
+}
+
+impl Display for ConstValue {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        // Values are written as they would be in IDL
+        match self {
+            ConstValue::Bool(true) => write!(f, "TRUE"),
+            ConstValue::Bool(false) => write!(f, "FALSE"),
+            ConstValue::Character(c) => write!(f, "{:?}", c),
+            ConstValue::WideCharacter(c) => write!(f, "L{:?}", c),
+            ConstValue::FixedPoint(v) => write!(f, "{}d", v),
+            ConstValue::FloatingPoint(v) => write!(f, "{:?}", v),
+            ConstValue::LongDouble(v) => write!(f, "{}", v),
+            ConstValue::Integer(v) => write!(f, "{}", v),
+            ConstValue::Str(s) => write!(f, "{:?}", s),
+            ConstValue::WideStr(s) => write!(f, "L{:?}", s),
+            ConstValue::Enumerator(_, name, _) => write!(f, "{}", name),
+        }
+    }
+}
+
+impl ConstExpr {
+    /// Builds a parser is able to parse a constant expression as specified in the
+    /// IDL Documentation
+    ///
+    /// The usual precedence of the operators is respected, from lowest to highest:
+    /// `|`, `^`, `&`, the shifts, the additive operators, the multiplicative
+    /// operators and finally the unary operators.
+    ///
+    /// Example
+    ///
+    /// ```
+    /// use ox_idl::expr::{BinaryOp, ConstExpr, UnaryOp};
+    /// use ox_idl::literal::Literal;
+    /// use chumsky::prelude::*;
+    ///
+    /// let parser = ConstExpr::parser();
+    ///
+    /// let e = parser.parse("-5");
+    /// assert_eq!(
+    ///     e,
+    ///     Ok(ConstExpr::Unary(
+    ///         UnaryOp::Negate,
+    ///         Box::new(ConstExpr::Literal(Literal::Integer(5), 1..2)),
+    ///         0..2
+    ///     ))
+    /// );
+    ///
+    /// let e = parser.parse("1 + 2 * 3").unwrap();
+    /// assert!(matches!(e, ConstExpr::Binary(BinaryOp::Add, _, _, _)));
+    /// ```
+    pub fn parser() -> impl Parser<char, ConstExpr, Error = Simple<char>> {
+        // 7.4.1.3
+        // <const_expr> ::= <or_expr>
+        // <or_expr> ::= <xor_expr> | <or_expr> "|" <xor_expr>
+        // <xor_expr> ::= <and_expr> | <xor_expr> "^" <and_expr>
+        // <and_expr> ::= <shift_expr> | <and_expr> "&" <shift_expr>
+        // <shift_expr> ::= <add_expr> | <shift_expr> ">>" <add_expr>
+        //                | <shift_expr> "<<" <add_expr>
+        // <add_expr> ::= <mult_expr> | <add_expr> "+" <mult_expr>
+        //              | <add_expr> "-" <mult_expr>
+        // <mult_expr> ::= <unary_expr> | <mult_expr> "*" <unary_expr>
+        //               | <mult_expr> "/" <unary_expr>
+        //               | <mult_expr> "%" <unary_expr>
+        // <unary_expr> ::= <unary_operator> <primary_expr> | <primary_expr>
+        // <unary_operator> ::= "-" | "+" | "~"
+        // <primary_expr> ::= <scoped_name> | <literal> | "(" <const_expr> ")"
+        recursive(|const_expr| {
+            let primary = choice((
+                token(Literal::parser().map_with_span(ConstExpr::Literal)),
+                // A name is never directly followed by a quote, which keeps the `L` of
+                // a malformed wide literal from being taken as a name and hiding the
+                // error reported by the literal
+                token(
+                    ScopedName::parser()
+                        .map_with_span(ConstExpr::Name)
+                        .then_ignore(none_of("'\"").rewind().ignored().or(end())),
+                ),
+                const_expr.delimited_by(symbol("("), symbol(")")),
+            ))
+            .boxed();
+
+            let unary_operator = choice((
+                just('-').to(UnaryOp::Negate),
+                just('+').to(UnaryOp::Plus),
+                just('~').to(UnaryOp::Complement),
+            ))
+            .map_with_span(|op, span: Range<usize>| (op, span.start));
+
+            let unary = token(unary_operator)
+                .then(primary.clone())
+                .map(|((op, start), expr)| {
+                    let span = start..expr.span().end;
+                    ConstExpr::Unary(op, Box::new(expr), span)
+                })
+                .or(primary)
+                .boxed();
+
+            let mult = Self::binary(
+                unary,
+                choice((
+                    just("*").to(BinaryOp::Multiply),
+                    just("/").to(BinaryOp::Divide),
+                    just("%").to(BinaryOp::Modulo),
+                )),
+            );
+            let add = Self::binary(
+                mult,
+                choice((
+                    just("+").to(BinaryOp::Add),
+                    just("-").to(BinaryOp::Subtract),
+                )),
+            );
+            let shift = Self::binary(
+                add,
+                choice((
+                    just("<<").to(BinaryOp::ShiftLeft),
+                    just(">>").to(BinaryOp::ShiftRight),
+                )),
+            );
+            let and = Self::binary(shift, just("&").to(BinaryOp::And));
+            let xor = Self::binary(and, just("^").to(BinaryOp::Xor));
+            Self::binary(xor, just("|").to(BinaryOp::Or))
+        })
+    }
+
+    /// Builds a parser for a left associative chain of operands separated by
+    /// binary operators
+    fn binary(
+        operand: impl Parser<char, ConstExpr, Error = Simple<char>> + Clone + 'static,
+        operator: impl Parser<char, BinaryOp, Error = Simple<char>> + Clone + 'static,
+    ) -> BoxedParser<'static, char, ConstExpr, Simple<char>> {
+        operand
+            .clone()
+            .then(token(operator).then(operand).repeated())
+            .foldl(|lhs, (op, rhs)| {
+                let span = lhs.span().start..rhs.span().end;
+                ConstExpr::Binary(op, Box::new(lhs), Box::new(rhs), span)
+            })
+            .boxed()
+    }
+
+    /// Returns the span of source the expression was parsed from
+    pub fn span(&self) -> Range<usize> {
+        match self {
+            ConstExpr::Literal(_, span)
+            | ConstExpr::Name(_, span)
+            | ConstExpr::Unary(_, _, span)
+            | ConstExpr::Binary(_, _, _, span) => span.clone(),
+        }
+    }
+
+    /// Evaluates the expression without regard to the type it will be assigned to,
+    /// resolving any names it references in `scope`
+    ///
+    /// Example
+    ///
+    /// ```
+    /// use ox_idl::expr::{ConstExpr, ConstValue};
+    /// use ox_idl::name::ScopedName;
+    /// use chumsky::prelude::*;
+    /// use std::collections::HashMap;
+    ///
+    /// let scope = HashMap::from([(ScopedName::from("N"), ConstValue::Integer(4))]);
+    ///
+    /// let e = ConstExpr::parser().parse("(N + 1) * 2").unwrap();
+    /// assert_eq!(e.evaluate(&scope), Ok(ConstValue::Integer(10)));
+    ///
+    /// let e = ConstExpr::parser().parse("-2.5").unwrap();
+    /// assert_eq!(e.evaluate(&scope), Ok(ConstValue::FloatingPoint(-2.5)));
+    /// ```
+    pub fn evaluate(&self, scope: &dyn ConstScope) -> Result<ConstValue, Simple<char>> {
+        Evaluation::new(self, None, scope).eval(self)
+    }
+
+    /// Evaluates the expression as the initializer of a constant of the given type,
+    /// resolving any names it references in `scope` and reporting values of the
+    /// wrong kind or outside of the range of the type
+    ///
+    /// Example
+    ///
+    /// ```
+    /// use ox_idl::expr::{ConstExpr, ConstValue};
+    /// use ox_idl::primitive::PrimitiveType;
+    /// use chumsky::prelude::*;
+    /// use std::collections::HashMap;
+    ///
+    /// let scope = HashMap::new();
+    ///
+    /// let e = ConstExpr::parser().parse("~0").unwrap();
+    /// assert_eq!(e.evaluate_as(PrimitiveType::Long, &scope), Ok(ConstValue::Integer(-1)));
+    /// assert_eq!(
+    ///     e.evaluate_as(PrimitiveType::UnsignedShort, &scope),
+    ///     Ok(ConstValue::Integer(65535))
+    /// );
+    ///
+    /// let e = ConstExpr::parser().parse("-5").unwrap();
+    /// assert!(e.evaluate_as(PrimitiveType::UnsignedLong, &scope).is_err());
+    /// ```
+    pub fn evaluate_as(
+        &self,
+        ty: PrimitiveType,
+        scope: &dyn ConstScope,
+    ) -> Result<ConstValue, Simple<char>> {
+        Evaluation::new(self, Some(ty), scope)
+            .eval(self)?
+            .coerce(ty)
+            .map_err(|msg| Simple::custom(self.span(), msg))
+    }
+
+    /// Returns true if both expressions are written alike, regardless of where they
+    /// appear in the source
+    pub fn same_as(&self, other: &ConstExpr) -> bool {
+        match (self, other) {
+            (ConstExpr::Literal(a, _), ConstExpr::Literal(b, _)) => a == b,
+            (ConstExpr::Name(a, _), ConstExpr::Name(b, _)) => a == b,
+            (ConstExpr::Unary(op_a, a, _), ConstExpr::Unary(op_b, b, _)) => {
+                op_a == op_b && a.same_as(b)
+            }
+            (
+                ConstExpr::Binary(op_a, lhs_a, rhs_a, _),
+                ConstExpr::Binary(op_b, lhs_b, rhs_b, _),
+            ) => op_a == op_b && lhs_a.same_as(lhs_b) && rhs_a.same_as(rhs_b),
+            _ => false,
+        }
+    }
+
+    /// Returns true if the expression contains a negation or a reference to a
+    /// negative integer constant, which makes it a signed integer expression
+    fn has_negative(&self, scope: &dyn ConstScope) -> bool {
+        match self {
+            ConstExpr::Literal(_, _) => false,
+            ConstExpr::Name(name, _) => {
+                matches!(scope.resolve(name), Some(ConstValue::Integer(v)) if v < 0)
+            }
+            ConstExpr::Unary(UnaryOp::Negate, _, _) => true,
+            ConstExpr::Unary(_, expr, _) => expr.has_negative(scope),
+            ConstExpr::Binary(_, lhs, rhs, _) => lhs.has_negative(scope) || rhs.has_negative(scope),
+        }
+    }
+}
+
+/// The state of the evaluation of a constant expression
+struct Evaluation<'a> {
+    scope: &'a dyn ConstScope,
+    target: Option<PrimitiveType>,
+    /// The integer type every integer subexpression is evaluated as
+    integer_type: PrimitiveType,
+}
+
+impl<'a> Evaluation<'a> {
+    fn new(expr: &ConstExpr, target: Option<PrimitiveType>, scope: &'a dyn ConstScope) -> Self {
+        // 7.4.1.4.4.2
+        // If the type of an integer constant is long or unsigned long, then each
+        // subexpression of the associated constant expression is treated as an
+        // unsigned long by default, or a signed long for negated literals or
+        // negative integer constants. It is an error if any subexpression values
+        // exceed the precision of the assigned type (long or unsigned long), or if a
+        // final expression value (of type unsigned long) exceeds the precision of the
+        // target type (long).
+        //
+        // If the type of an integer constant is long long or unsigned long long, then
+        // each subexpression of the associated constant expression is treated as an
+        // unsigned long long by default, or a signed long long for negated literals
+        // or negative integer constants.
+        //
+        // NOTE: Narrower integer types are evaluated as long, and expressions assigned
+        // to a signed type are always evaluated as signed so that they may go negative
+        let signed = target.is_some_and(|ty| ty.is_signed_integer()) || expr.has_negative(scope);
+        let long = target
+            .and_then(|ty| ty.integer_bits())
+            .is_some_and(|bits| bits <= 32);
+        let integer_type = match (long, signed) {
+            (true, true) => PrimitiveType::Long,
+            (true, false) => PrimitiveType::UnsignedLong,
+            (false, true) => PrimitiveType::LongLong,
+            (false, false) => PrimitiveType::UnsignedLongLong,
+        };
+
+        Evaluation {
+            scope,
+            target,
+            integer_type,
+        }
+    }
+
+    fn eval(&self, expr: &ConstExpr) -> Result<ConstValue, Simple<char>> {
+        match expr {
+            ConstExpr::Literal(literal, span) => self.eval_literal(literal, span),
+            ConstExpr::Name(name, span) => self.scope.resolve(name).ok_or_else(|| {
+                Simple::custom(span.clone(), format!("`{}` does not name a constant", name))
+            }),
+            ConstExpr::Unary(op, expr, span) => {
+                let value = self.eval(expr)?;
+                let result = match (op, &value) {
+                    (UnaryOp::Plus, ConstValue::Integer(_))
+                    | (UnaryOp::Plus, ConstValue::FloatingPoint(_))
+                    | (UnaryOp::Plus, ConstValue::FixedPoint(_)) => value.clone(),
+                    (UnaryOp::Negate, ConstValue::Integer(v)) => ConstValue::Integer(-v),
+                    (UnaryOp::Negate, ConstValue::FloatingPoint(v)) => {
+                        ConstValue::FloatingPoint(-v)
+                    }
+                    (UnaryOp::Negate, ConstValue::FixedPoint(v)) => ConstValue::FixedPoint(-*v),
+                    (UnaryOp::Plus, ConstValue::LongDouble(_)) => value.clone(),
+                    (UnaryOp::Negate, ConstValue::LongDouble(v)) => {
+                        ConstValue::LongDouble(match v.strip_prefix('-') {
+                            Some(v) => v.to_string(),
+                            None => format!("-{}", v),
+                        })
+                    }
+                    (UnaryOp::Complement, ConstValue::Integer(v)) => {
+                        ConstValue::Integer(self.complement(*v))
+                    }
+                    _ => {
+                        return Err(Simple::custom(
+                            span.clone(),
+                            format!(
+                                "operator `{}` cannot be applied to a {} value",
+                                op,
+                                value.kind()
+                            ),
+                        ))
+                    }
+                };
+                self.check_integer(result, span)
+            }
+            ConstExpr::Binary(op, lhs, rhs, span) => {
+                let lhs = self.eval(lhs)?;
+                let rhs = self.eval(rhs)?;
+                let result = self.eval_binary(*op, lhs, rhs, span)?;
+                self.check_integer(result, span)
+            }
+        }
+    }
+
+    fn eval_literal(
+        &self,
+        literal: &Literal,
+        span: &Range<usize>,
+    ) -> Result<ConstValue, Simple<char>> {
+        Ok(match literal {
+            Literal::Bool(v) => ConstValue::Bool(*v),
+            Literal::Character(c) => ConstValue::Character(*c),
+            Literal::WideCharacter(c) => ConstValue::WideCharacter(*c),
+            Literal::FixedPoint(v) => ConstValue::FixedPoint(*v),
+            // NOTE: A literal outside of the range of a double is only kept when it is
+            // assigned to a long double, without taking part in any arithmetic
+            Literal::FloatingPoint(v)
+                if !v.fits(FloatPrecision::Double)
+                    && self.target.and_then(|ty| ty.float_precision())
+                        == Some(FloatPrecision::LongDouble) =>
+            {
+                ConstValue::LongDouble(v.lexeme().to_string())
+            }
+            Literal::FloatingPoint(v) => {
+                ConstValue::FloatingPoint(v.to_f64().ok_or_else(|| {
+                    Simple::custom(
+                        span.clone(),
+                        format!(
+                            "floating point literal `{}` exceeds the range of double, \
+                         the widest precision constant expressions are evaluated in",
+                            v
+                        ),
+                    )
+                })?)
+            }
+            Literal::Integer(v) => ConstValue::Integer(*v as i128),
+            Literal::Str(s) => ConstValue::Str(s.clone()),
+            Literal::WideStr(s) => ConstValue::WideStr(s.clone()),
+        })
+    }
+
+    fn eval_binary(
+        &self,
+        op: BinaryOp,
+        lhs: ConstValue,
+        rhs: ConstValue,
+        span: &Range<usize>,
+    ) -> Result<ConstValue, Simple<char>> {
+        let error = |msg: String| Err(Simple::custom(span.clone(), msg));
+        let division_by_zero = || error("division by zero".to_string());
+
+        // 7.4.1.4.4.2
+        // Mixed type expressions (e.g., integers mixed with floats) are illegal.
+        match (lhs, rhs) {
+            (ConstValue::LongDouble(v), _) | (_, ConstValue::LongDouble(v)) => error(format!(
+                "floating point value {} exceeds the range of double, \
+                 the widest precision constant expressions are evaluated in",
+                v
+            )),
+            (ConstValue::Integer(a), ConstValue::Integer(b)) => {
+                let bits = self.integer_type.integer_bits().unwrap_or(64) as i128;
+                let result = match op {
+                    BinaryOp::Or => Some(a | b),
+                    BinaryOp::Xor => Some(a ^ b),
+                    BinaryOp::And => Some(a & b),
+                    BinaryOp::ShiftLeft | BinaryOp::ShiftRight if !(0..bits).contains(&b) => {
+                        return error(format!(
+                            "shift by {} is out of range, it must be at least 0 and less than {}",
+                            b, bits
+                        ))
+                    }
+                    BinaryOp::ShiftLeft => a.checked_mul(1 << b),
+                    BinaryOp::ShiftRight => Some(a >> b),
+                    BinaryOp::Add => a.checked_add(b),
+                    BinaryOp::Subtract => a.checked_sub(b),
+                    BinaryOp::Multiply => a.checked_mul(b),
+                    BinaryOp::Divide | BinaryOp::Modulo if b == 0 => return division_by_zero(),
+                    BinaryOp::Divide => Some(a / b),
+                    BinaryOp::Modulo => Some(a % b),
+                };
+                match result {
+                    Some(v) => Ok(ConstValue::Integer(v)),
+                    None => error(format!(
+                        "integer value exceeds the range of {}",
+                        self.integer_type
+                    )),
+                }
+            }
+            (ConstValue::FloatingPoint(a), ConstValue::FloatingPoint(b)) => {
+                let result = match op {
+                    BinaryOp::Add => a + b,
+                    BinaryOp::Subtract => a - b,
+                    BinaryOp::Multiply => a * b,
+                    BinaryOp::Divide if b == 0.0 => return division_by_zero(),
+                    BinaryOp::Divide => a / b,
+                    _ => {
+                        return error(format!(
+                            "operator `{}` cannot be applied to floating point values",
+                            op
+                        ))
+                    }
+                };
+                if result.is_finite() {
+                    Ok(ConstValue::FloatingPoint(result))
+                } else {
+                    error(format!(
+                        "floating point value exceeds the range of {}",
+                        PrimitiveType::Double
+                    ))
+                }
+            }
+            (ConstValue::FixedPoint(a), ConstValue::FixedPoint(b)) => {
+                let result = match op {
+                    BinaryOp::Add => a.checked_add(b),
+                    BinaryOp::Subtract => a.checked_sub(b),
+                    BinaryOp::Multiply => a.checked_mul(b),
+                    BinaryOp::Divide if b == Fixed::new(0, 0).unwrap() => {
+                        return division_by_zero()
+                    }
+                    BinaryOp::Divide => a.checked_div(b),
+                    _ => {
+                        return error(format!(
+                            "operator `{}` cannot be applied to fixed point values",
+                            op
+                        ))
+                    }
+                };
+                match result {
+                    Some(v) => Ok(ConstValue::FixedPoint(v)),
+                    None => error(format!(
+                        "fixed point value exceeds {} digits",
+                        crate::fixed::MAX_DIGITS
+                    )),
+                }
+            }
+            (a, b) if a.kind() != b.kind() => error(format!(
+                "operator `{}` cannot be applied to mixed {} and {} values",
+                op,
+                a.kind(),
+                b.kind()
+            )),
+            (a, _) => error(format!(
+                "operator `{}` cannot be applied to {} values",
+                op,
+                a.kind()
+            )),
+        }
+    }
+
+    /// Computes the bitwise complement of an integer following 7.4.1.4.4.2, where
+    /// the complement of a signed value is -(value + 1) and the complement of an
+    /// unsigned value is (2^bits - 1) - value
+    fn complement(&self, value: i128) -> i128 {
+        let ty = match self.target {
+            Some(ty) if ty.integer_bits().is_some() => ty,
+            _ => self.integer_type,
+        };
+        match ty.integer_bits() {
+            Some(bits) if value >= 0 && !ty.is_signed_integer() => ((1i128 << bits) - 1) - value,
+            _ => -(value + 1),
+        }
+    }
+
+    /// Checks that an intermediate integer value is within the range of the type
+    /// that integer subexpressions are evaluated as
+    fn check_integer(
+        &self,
+        value: ConstValue,
+        span: &Range<usize>,
+    ) -> Result<ConstValue, Simple<char>> {
+        match (value, self.integer_type.integer_range()) {
+            (ConstValue::Integer(v), Some((min, max))) if v < min || v > max => {
+                Err(Simple::custom(
+                    span.clone(),
+                    format!(
+                        "integer value {} exceeds the range of {}",
+                        v, self.integer_type
+                    ),
+                ))
+            }
+            (value, _) => Ok(value),
+        }
+    }
+}
+
+#[cfg(test)]
+mod expr_tests {
+    use crate::expr::{BinaryOp, ConstExpr, ConstValue, UnaryOp};
+    use crate::fixed::Fixed;
+    use crate::literal::Literal;
+    use crate::name::ScopedName;
+    use crate::primitive::PrimitiveType;
+    use crate::test_utils::message;
+    use chumsky::prelude::*;
+    use std::collections::HashMap;
+
+    fn scope() -> HashMap<ScopedName, ConstValue> {
+        HashMap::from([
+            (ScopedName::from("N"), ConstValue::Integer(4)),
+            (ScopedName::from("M::NEG"), ConstValue::Integer(-2)),
+            (ScopedName::from("PI"), ConstValue::FloatingPoint(3.5)),
+            (
+                ScopedName::from("NAME"),
+                ConstValue::Str("name".to_string()),
+            ),
+        ])
+    }
+
+    fn eval(input: &str) -> Result<ConstValue, String> {
+        ConstExpr::parser()
+            .then_ignore(end())
+            .parse(input)
+            .unwrap()
+            .evaluate(&scope())
+            .map_err(|e| message(&e))
+    }
+
+    fn eval_as(input: &str, ty: PrimitiveType) -> Result<ConstValue, String> {
+        ConstExpr::parser()
+            .then_ignore(end())
+            .parse(input)
+            .unwrap()
+            .evaluate_as(ty, &scope())
+            .map_err(|e| message(&e))
+    }
+
+    #[test]
+    fn parse_unary() {
+        assert_eq!(
+            ConstExpr::parser().parse("3"),
+            Ok(ConstExpr::Literal(Literal::Integer(3), 0..1))
+        );
+        assert_eq!(
+            ConstExpr::parser().parse("~ 3"),
+            Ok(ConstExpr::Unary(
+                UnaryOp::Complement,
+                Box::new(ConstExpr::Literal(Literal::Integer(3), 2..3)),
+                0..3
+            ))
+        );
+        assert_eq!(
+            ConstExpr::parser().parse("+TRUE"),
+            Ok(ConstExpr::Unary(
+                UnaryOp::Plus,
+                Box::new(ConstExpr::Literal(Literal::Bool(true), 1..5)),
+                0..5
+            ))
+        );
+    }
+
+    #[test]
+    fn negative_values() {
+        assert_eq!(
+            eval_as("-5", PrimitiveType::Long),
+            Ok(ConstValue::Integer(-5))
+        );
+        assert_eq!(
+            eval_as("-0x8000000000000000", PrimitiveType::LongLong),
+            Ok(ConstValue::Integer(i64::MIN as i128))
+        );
+        assert_eq!(
+            eval_as("-1.5", PrimitiveType::Double),
+            Ok(ConstValue::FloatingPoint(-1.5))
+        );
+        assert_eq!(
+            eval_as("-1.5d", PrimitiveType::Double),
+            Err("expected a value of type double, found fixed point value -1.5d".to_string())
+        );
+        assert_eq!(
+            eval("-1.05d"),
+            Ok(ConstValue::FixedPoint(Fixed::new(-105, 2).unwrap()))
+        );
+        assert_eq!(
+            eval_as("+7", PrimitiveType::Short),
+            Ok(ConstValue::Integer(7))
+        );
+    }
+
+    #[test]
+    fn range_checks() {
+        assert_eq!(
+            eval_as("-5", PrimitiveType::UnsignedLong),
+            Err("value -5 is out of range for type unsigned long".to_string())
+        );
+        assert_eq!(
+            eval_as("-128", PrimitiveType::Int8),
+            Ok(ConstValue::Integer(-128))
+        );
+        assert_eq!(
+            eval_as("-129", PrimitiveType::Int8),
+            Err("value -129 is out of range for type int8".to_string())
+        );
+        assert_eq!(
+            eval_as("256", PrimitiveType::Octet),
+            Err("value 256 is out of range for type octet".to_string())
+        );
+        assert_eq!(
+            eval_as("-32769", PrimitiveType::Short),
+            Err("value -32769 is out of range for type short".to_string())
+        );
+        assert_eq!(
+            eval_as("-18446744073709551615", PrimitiveType::LongLong),
+            Err("integer value -18446744073709551615 exceeds the range of long long".to_string())
+        );
+        assert_eq!(
+            eval_as("1e39", PrimitiveType::Float),
+            Err("value 1e39 is out of range for type float".to_string())
+        );
+        assert_eq!(
+            eval_as("-1e39", PrimitiveType::Double),
+            Ok(ConstValue::FloatingPoint(-1e39))
+        );
+        assert_eq!(
+            eval_as("1e-310", PrimitiveType::Double),
+            Ok(ConstValue::FloatingPoint(1e-310))
+        );
+        assert_eq!(
+            eval_as("2", PrimitiveType::Float),
+            Ok(ConstValue::FloatingPoint(2.0))
+        );
+        assert_eq!(
+            eval_as("1e400", PrimitiveType::LongDouble),
+            Ok(ConstValue::LongDouble("1e400".to_string()))
+        );
+        assert_eq!(
+            eval_as("-1e400", PrimitiveType::LongDouble),
+            Ok(ConstValue::LongDouble("-1e400".to_string()))
+        );
+        assert_eq!(
+            eval_as("1e400", PrimitiveType::Double),
+            Err(
+                "floating point literal `1e400` exceeds the range of double, \
+                 the widest precision constant expressions are evaluated in"
+                    .to_string()
+            )
+        );
+        assert_eq!(
+            eval_as("1e400 * 2.0", PrimitiveType::LongDouble),
+            Err("floating point value 1e400 exceeds the range of double, \
+                 the widest precision constant expressions are evaluated in"
+                .to_string())
+        );
+    }
+
+    #[test]
+    fn complement() {
+        assert_eq!(
+            eval_as("~0", PrimitiveType::Long),
+            Ok(ConstValue::Integer(-1))
+        );
+        assert_eq!(
+            eval_as("~5", PrimitiveType::Short),
+            Ok(ConstValue::Integer(-6))
+        );
+        assert_eq!(
+            eval_as("~0", PrimitiveType::UnsignedLong),
+            Ok(ConstValue::Integer(u32::MAX as i128))
+        );
+        assert_eq!(
+            eval_as("~0", PrimitiveType::UnsignedLongLong),
+            Ok(ConstValue::Integer(u64::MAX as i128))
+        );
+        assert_eq!(
+            eval_as("~0xF0", PrimitiveType::Octet),
+            Ok(ConstValue::Integer(0x0F))
+        );
+        assert_eq!(eval("~0"), Ok(ConstValue::Integer(u64::MAX as i128)));
+    }
+
+    #[test]
+    fn kind_errors() {
+        assert_eq!(
+            eval_as("-'a'", PrimitiveType::Char),
+            Err("operator `-` cannot be applied to a character value".to_string())
+        );
+        assert_eq!(
+            eval_as("~1.0", PrimitiveType::Double),
+            Err("operator `~` cannot be applied to a floating point value".to_string())
+        );
+        assert_eq!(
+            eval_as("L'a'", PrimitiveType::Char),
+            Err("expected a value of type char, found wide character value L'a'".to_string())
+        );
+        assert_eq!(
+            eval_as("TRUE", PrimitiveType::Boolean),
+            Ok(ConstValue::Bool(true))
+        );
+        assert_eq!(
+            eval_as("1", PrimitiveType::Boolean),
+            Err("expected a value of type boolean, found integer value 1".to_string())
+        );
+
+        let errors = ConstExpr::parser()
+            .parse("-\"abc\"")
+            .unwrap()
+            .evaluate(&scope())
+            .unwrap_err();
+        assert_eq!(errors.span(), 0..6);
+    }
+
+    #[test]
+    fn parse_binary() {
+        let lit = |v, span| Box::new(ConstExpr::Literal(Literal::Integer(v), span));
+
+        // Multiplication binds tighter than addition
+        assert_eq!(
+            ConstExpr::parser().parse("1 + 2*3"),
+            Ok(ConstExpr::Binary(
+                BinaryOp::Add,
+                lit(1, 0..1),
+                Box::new(ConstExpr::Binary(
+                    BinaryOp::Multiply,
+                    lit(2, 4..5),
+                    lit(3, 6..7),
+                    4..7
+                )),
+                0..7
+            ))
+        );
+
+        // Operators of the same precedence are left associative
+        assert_eq!(
+            ConstExpr::parser().parse("8 - 4 - 2"),
+            Ok(ConstExpr::Binary(
+                BinaryOp::Subtract,
+                Box::new(ConstExpr::Binary(
+                    BinaryOp::Subtract,
+                    lit(8, 0..1),
+                    lit(4, 4..5),
+                    0..5
+                )),
+                lit(2, 8..9),
+                0..9
+            ))
+        );
+
+        assert_eq!(
+            ConstExpr::parser().parse("::M::NEG"),
+            Ok(ConstExpr::Name(ScopedName::from("::M::NEG"), 0..8))
+        );
+
+        // Comments may appear between any tokens, spans exclude them
+        assert_eq!(
+            ConstExpr::parser().parse("- /* minus */ 1 // one\n+ ( 2 )"),
+            Ok(ConstExpr::Binary(
+                BinaryOp::Add,
+                Box::new(ConstExpr::Unary(UnaryOp::Negate, lit(1, 14..15), 0..15)),
+                lit(2, 27..28),
+                0..28
+            ))
+        );
+    }
+
+    #[test]
+    fn precedence() {
+        assert_eq!(
+            eval("1 | 6 ^ 3 & 2"),
+            Ok(ConstValue::Integer(1 | 6 ^ 3 & 2))
+        );
+        assert_eq!(eval("1 << 2 + 1"), Ok(ConstValue::Integer(8)));
+        assert_eq!(eval("2 + 3 * 4 % 5"), Ok(ConstValue::Integer(4)));
+        assert_eq!(eval("(2 + 3) * 4"), Ok(ConstValue::Integer(20)));
+        assert_eq!(eval("( ( 7 ) )"), Ok(ConstValue::Integer(7)));
+        assert_eq!(eval("-(2 + 3)"), Ok(ConstValue::Integer(-5)));
+        assert_eq!(eval("0x10 >> 2"), Ok(ConstValue::Integer(4)));
+        assert_eq!(eval("-16 >> 2"), Ok(ConstValue::Integer(-4)));
+        assert_eq!(eval("7 / 2"), Ok(ConstValue::Integer(3)));
+        assert_eq!(eval("-7 / 2"), Ok(ConstValue::Integer(-3)));
+    }
+
+    #[test]
+    fn references() {
+        assert_eq!(eval("N * N"), Ok(ConstValue::Integer(16)));
+        assert_eq!(eval("N + M::NEG"), Ok(ConstValue::Integer(2)));
+        assert_eq!(eval("PI * 2.0"), Ok(ConstValue::FloatingPoint(7.0)));
+        assert_eq!(
+            eval("UNKNOWN + 1"),
+            Err("`UNKNOWN` does not name a constant".to_string())
+        );
+        assert_eq!(
+            eval("NAME + 1"),
+            Err("operator `+` cannot be applied to mixed string and integer values".to_string())
+        );
+    }
+
+    #[test]
+    fn promotion() {
+        // Unsigned expressions may not go negative
+        assert_eq!(
+            eval_as("1 - 2", PrimitiveType::UnsignedLong),
+            Err("integer value -1 exceeds the range of unsigned long".to_string())
+        );
+        assert_eq!(
+            eval_as("1 - 2", PrimitiveType::Long),
+            Ok(ConstValue::Integer(-1))
+        );
+
+        // Expressions with negations or negative constants are signed
+        assert_eq!(
+            eval_as("-1 + 2", PrimitiveType::UnsignedLong),
+            Ok(ConstValue::Integer(1))
+        );
+        assert_eq!(
+            eval_as("M::NEG + 3", PrimitiveType::UnsignedLongLong),
+            Ok(ConstValue::Integer(1))
+        );
+
+        // Subexpressions are evaluated as long for types of 32 bits or less
+        assert_eq!(
+            eval_as("0xFFFFFFFF + 1 - 1", PrimitiveType::UnsignedLong),
+            Err("integer value 4294967296 exceeds the range of unsigned long".to_string())
+        );
+        assert_eq!(
+            eval_as("0xFFFFFFFF + 1 - 1", PrimitiveType::UnsignedLongLong),
+            Ok(ConstValue::Integer(0xFFFFFFFF))
+        );
+        assert_eq!(
+            eval_as("0x7FFFFFFF + 1", PrimitiveType::Short),
+            Err("integer value 2147483648 exceeds the range of long".to_string())
+        );
+        assert_eq!(
+            eval_as("0xFFFFFFFFFFFFFFFF * 2", PrimitiveType::UnsignedLongLong),
+            Err(
+                "integer value 36893488147419103230 exceeds the range of unsigned long long"
+                    .to_string()
+            )
+        );
+        assert_eq!(
+            eval_as("-0x7FFFFFFFFFFFFFFF - 2", PrimitiveType::LongLong),
+            Err("integer value -9223372036854775809 exceeds the range of long long".to_string())
+        );
+        assert_eq!(
+            eval("0xFFFFFFFFFFFFFFFF * 0xFFFFFFFFFFFFFFFF"),
+            Err("integer value exceeds the range of unsigned long long".to_string())
+        );
+    }
+
+    #[test]
+    fn errors() {
+        assert_eq!(eval("1 / 0"), Err("division by zero".to_string()));
+        assert_eq!(eval("1 % (N - 4)"), Err("division by zero".to_string()));
+        assert_eq!(eval("1.0 / 0.0"), Err("division by zero".to_string()));
+        assert_eq!(eval("1.0d / 0d"), Err("division by zero".to_string()));
+        assert_eq!(
+            eval("1 << 64"),
+            Err("shift by 64 is out of range, it must be at least 0 and less than 64".to_string())
+        );
+        assert_eq!(
+            eval_as("1 << 32", PrimitiveType::Long),
+            Err("shift by 32 is out of range, it must be at least 0 and less than 32".to_string())
+        );
+        assert_eq!(
+            eval("1 >> -1"),
+            Err("shift by -1 is out of range, it must be at least 0 and less than 64".to_string())
+        );
+        assert_eq!(
+            eval("1 << 63 << 1"),
+            Err(
+                "integer value 18446744073709551616 exceeds the range of unsigned long long"
+                    .to_string()
+            )
+        );
+        assert_eq!(
+            eval("1.5 + 1"),
+            Err(
+                "operator `+` cannot be applied to mixed floating point and integer values"
+                    .to_string()
+            )
+        );
+        assert_eq!(
+            eval("1.5 | 1.0"),
+            Err("operator `|` cannot be applied to floating point values".to_string())
+        );
+        assert_eq!(
+            eval("TRUE & FALSE"),
+            Err("operator `&` cannot be applied to boolean values".to_string())
+        );
+        assert_eq!(
+            eval("1e308 * 10.0"),
+            Err("floating point value exceeds the range of double".to_string())
+        );
+
+        // Errors point at the offending subexpression
+        let error = ConstExpr::parser()
+            .parse("2 * (1 / 0)")
+            .unwrap()
+            .evaluate(&scope())
+            .unwrap_err();
+        assert_eq!(error.span(), 5..10);
+    }
+
+    #[test]
+    fn fixed_point() {
+        assert_eq!(
+            eval("1.5d * 2d + 0.25d"),
+            Ok(ConstValue::FixedPoint(Fixed::new(325, 2).unwrap()))
+        );
+        assert_eq!(
+            eval("1d / 4d"),
+            Ok(ConstValue::FixedPoint(Fixed::new(25, 2).unwrap()))
+        );
+        assert_eq!(
+            eval("9999999999999999999999999999999d + 1d"),
+            Err("fixed point value exceeds 31 digits".to_string())
+        );
+        assert_eq!(
+            eval("1d % 2d"),
+            Err("operator `%` cannot be applied to fixed point values".to_string())
+        );
+    }
+}
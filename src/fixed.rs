@@ -0,0 +1,564 @@
+/**********************************************************************************
+ * Copyright © 2022 Michael Volling
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy of
+ * this software and associated documentation files (the “Software”), to deal in
+ * the Software without restriction, including without limitation the rights to
+ * use, copy, modify, merge, publish, distribute, sublicense, and/or sell copies of
+ * the Software, and to permit persons to whom the Software is furnished to do so,
+ * subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED “AS IS”, WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY, FITNESS
+ * FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE AUTHORS OR
+ * COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER
+ * IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN
+ * CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+ *********************************************************************************/
+
+use std::cmp::Ordering;
+use std::fmt::Display;
+use std::ops::Neg;
+
+/// The maximum number of digits an IDL fixed point value may have
+pub const MAX_DIGITS: u32 = 31;
+
+/// The Fixed type represents an IDL fixed point decimal value
+///
+/// The value is held exactly as an integer count of `10^-scale` units with
+/// at most 31 digits, which is the limit of the IDL fixed<digits,scale> type.
+/// Trailing zeros of the fraction are not significant, so `1.50` and `1.5`
+/// are the same value.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Fixed {
+    value: i128,
+    scale: u32,
+}
+
+impl Fixed {
+    /// Creates a fixed point value of `value * 10^-scale`
+    ///
+    /// Returns None if the value would need more than 31 digits
+    ///
+    /// Example
+    ///
+    /// ```
+    /// use ox_idl::fixed::Fixed;
+    ///
+    /// let f = Fixed::new(105, 2).unwrap();
+    /// assert_eq!(f.to_string(), "1.05");
+    /// assert_eq!(f.digits(), 3);
+    /// assert_eq!(f.scale(), 2);
+    /// ```
+    pub fn new(value: i128, scale: u32) -> Option<Fixed> {
+        Exact::from_i128(value, scale as usize).into_fixed(false)
+    }
+
+    /// Creates a fixed point value from the decimal digits of its integer and
+    /// fractional parts, as they are written in a fixed point literal
+    ///
+    /// Returns None if either part contains anything other than decimal digits, or
+    /// if the value would need more than 31 digits
+    ///
+    /// Example
+    ///
+    /// ```
+    /// use ox_idl::fixed::Fixed;
+    ///
+    /// assert_eq!(Fixed::from_digits("1", "05"), Fixed::new(105, 2));
+    /// assert_eq!(Fixed::from_digits("", "5"), Fixed::new(5, 1));
+    /// ```
+    pub fn from_digits(integer: &str, fraction: &str) -> Option<Fixed> {
+        let digits = integer
+            .chars()
+            .chain(fraction.chars())
+            .map(|c| c.to_digit(10).map(|d| d as u8))
+            .collect::<Option<Vec<u8>>>()?;
+        Exact {
+            negative: false,
+            digits,
+            scale: fraction.len(),
+        }
+        .into_fixed(false)
+    }
+
+    /// Returns the total number of significant digits of the value, which is the
+    /// smallest `digits` of a fixed<digits,scale> type able to hold it
+    pub fn digits(&self) -> u32 {
+        let len = match self.value.unsigned_abs() {
+            0 => 1,
+            v => v.ilog10() + 1,
+        };
+        len.max(self.scale)
+    }
+
+    /// Returns the number of digits after the decimal point
+    pub fn scale(&self) -> u32 {
+        self.scale
+    }
+
+    /// Returns true if the value can be held by a fixed<digits,scale> type
+    /// without losing any digits
+    ///
+    /// Example
+    ///
+    /// ```
+    /// use ox_idl::fixed::Fixed;
+    ///
+    /// let f = Fixed::new(12345, 2).unwrap();
+    /// assert!(f.fits(5, 2));
+    /// assert!(f.fits(6, 3));
+    /// assert!(!f.fits(5, 1));
+    /// assert!(!f.fits(4, 1));
+    /// ```
+    pub fn fits(&self, digits: u32, scale: u32) -> bool {
+        digits <= MAX_DIGITS
+            && scale <= digits
+            && self.scale <= scale
+            && self.digits() - self.scale <= digits - scale
+    }
+
+    /// Adds two values, returning None if the result does not fit in 31 digits
+    ///
+    /// Following 7.4.1.4.4.2, fractional digits beyond the 31 digit limit are
+    /// discarded without rounding.
+    pub fn checked_add(self, rhs: Fixed) -> Option<Fixed> {
+        Exact::from(self).add(Exact::from(rhs)).into_fixed(true)
+    }
+
+    /// Subtracts two values, returning None if the result does not fit in 31 digits
+    pub fn checked_sub(self, rhs: Fixed) -> Option<Fixed> {
+        Exact::from(self).add(-Exact::from(rhs)).into_fixed(true)
+    }
+
+    /// Multiplies two values, returning None if the result does not fit in 31 digits
+    ///
+    /// Following 7.4.1.4.4.2, fractional digits beyond the 31 digit limit are
+    /// discarded without rounding.
+    pub fn checked_mul(self, rhs: Fixed) -> Option<Fixed> {
+        Exact::from(self).mul(&Exact::from(rhs)).into_fixed(true)
+    }
+
+    /// Divides two values, returning None if `rhs` is zero or the result does not
+    /// fit in 31 digits
+    ///
+    /// The quotient may have an arbitrary number of fractional digits, so it is
+    /// truncated to 31 significant digits as described in 7.4.1.4.4.2.
+    ///
+    /// Example
+    ///
+    /// ```
+    /// use ox_idl::fixed::Fixed;
+    ///
+    /// let one = Fixed::new(1, 0).unwrap();
+    /// let three = Fixed::new(3, 0).unwrap();
+    /// let third = one.checked_div(three).unwrap();
+    /// assert_eq!(third.to_string(), "0.3333333333333333333333333333333");
+    ///
+    /// assert_eq!(one.checked_div(Fixed::new(0, 0).unwrap()), None);
+    /// ```
+    pub fn checked_div(self, rhs: Fixed) -> Option<Fixed> {
+        Exact::from(self).div(&Exact::from(rhs))?.into_fixed(true)
+    }
+}
+
+impl Neg for Fixed {
+    type Output = Fixed;
+
+    fn neg(self) -> Fixed {
+        Fixed {
+            value: -self.value,
+            scale: self.scale,
+        }
+    }
+}
+
+impl Ord for Fixed {
+    fn cmp(&self, other: &Self) -> Ordering {
+        Exact::from(*self).cmp(&Exact::from(*other))
+    }
+}
+
+impl PartialOrd for Fixed {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Display for Fixed {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let digits = format!(
+            "{:0width$}",
+            self.value.unsigned_abs(),
+            width = self.scale as usize + 1
+        );
+        let (integer, fraction) = digits.split_at(digits.len() - self.scale as usize);
+        if self.value < 0 {
+            f.write_str("-")?;
+        }
+        f.write_str(integer)?;
+        if !fraction.is_empty() {
+            write!(f, ".{}", fraction)?;
+        }
+        Ok(())
+    }
+}
+
+/// An exact decimal value with an unlimited number of digits, used to hold the
+/// intermediate results of fixed point arithmetic before they are truncated
+#[derive(Debug, Clone)]
+struct Exact {
+    negative: bool,
+    /// The decimal digits of the value, most significant first
+    digits: Vec<u8>,
+    scale: usize,
+}
+
+impl Exact {
+    fn from_i128(value: i128, scale: usize) -> Exact {
+        Exact {
+            negative: value < 0,
+            digits: value
+                .unsigned_abs()
+                .to_string()
+                .bytes()
+                .map(|b| b - b'0')
+                .collect(),
+            scale,
+        }
+    }
+
+    /// Converts the value into a Fixed. When `truncate` is set, fractional digits
+    /// are discarded until the value fits in 31 digits, otherwise a value that does
+    /// not fit is rejected.
+    fn into_fixed(mut self, truncate: bool) -> Option<Fixed> {
+        // Trailing zeros of the fraction are not significant
+        while self.scale > 0 && self.digits.last() == Some(&0) {
+            self.digits.pop();
+            self.scale -= 1;
+        }
+        let leading_zeros = self.digits.iter().take_while(|d| **d == 0).count();
+        self.digits.drain(..leading_zeros);
+
+        while self.digits.len().max(self.scale) > MAX_DIGITS as usize {
+            if !truncate || self.scale == 0 {
+                return None;
+            }
+            self.digits.pop();
+            self.scale -= 1;
+        }
+
+        let magnitude = self
+            .digits
+            .iter()
+            .fold(0i128, |acc, d| acc * 10 + *d as i128);
+        let value = if self.negative { -magnitude } else { magnitude };
+        Some(Fixed {
+            value,
+            scale: if value == 0 { 0 } else { self.scale as u32 },
+        })
+    }
+
+    /// Returns the digits of the value extended with trailing zeros to the given
+    /// scale, and leading zeros to the given number of integer digits
+    fn aligned(&self, integer_digits: usize, scale: usize) -> Vec<u8> {
+        let own_integer_digits = self.digits.len().saturating_sub(self.scale);
+        let leading_zeros =
+            integer_digits - own_integer_digits + self.scale.saturating_sub(self.digits.len());
+        let mut digits = vec![0; leading_zeros];
+        digits.extend(&self.digits);
+        digits.resize(digits.len() + scale - self.scale, 0);
+        digits
+    }
+
+    fn integer_digits(&self) -> usize {
+        self.digits.len().saturating_sub(self.scale)
+    }
+
+    fn cmp_magnitude(&self, other: &Exact) -> Ordering {
+        let integer_digits = self.integer_digits().max(other.integer_digits());
+        let scale = self.scale.max(other.scale);
+        self.aligned(integer_digits, scale)
+            .cmp(&other.aligned(integer_digits, scale))
+    }
+
+    fn is_zero(&self) -> bool {
+        self.digits.iter().all(|d| *d == 0)
+    }
+
+    fn add(self, other: Exact) -> Exact {
+        let integer_digits = self.integer_digits().max(other.integer_digits()) + 1;
+        let scale = self.scale.max(other.scale);
+        let (larger, smaller) = match self.cmp_magnitude(&other) {
+            Ordering::Less => (other, self),
+            _ => (self, other),
+        };
+        let a = larger.aligned(integer_digits, scale);
+        let b = smaller.aligned(integer_digits, scale);
+
+        // Subtracting the smaller magnitude from the larger can never borrow past
+        // the most significant digit
+        let subtract = larger.negative != smaller.negative;
+        let mut carry = 0i8;
+        let mut digits = vec![0; a.len()];
+        for i in (0..a.len()).rev() {
+            let mut d = if subtract {
+                a[i] as i8 - b[i] as i8 - carry
+            } else {
+                a[i] as i8 + b[i] as i8 + carry
+            };
+            carry = 0;
+            if d < 0 {
+                d += 10;
+                carry = 1;
+            } else if d > 9 {
+                d -= 10;
+                carry = 1;
+            }
+            digits[i] = d as u8;
+        }
+
+        Exact {
+            negative: larger.negative,
+            digits,
+            scale,
+        }
+    }
+
+    fn mul(&self, other: &Exact) -> Exact {
+        let mut digits = vec![0u32; self.digits.len() + other.digits.len()];
+        for (i, a) in self.digits.iter().enumerate().rev() {
+            for (j, b) in other.digits.iter().enumerate().rev() {
+                digits[i + j + 1] += *a as u32 * *b as u32;
+            }
+        }
+        for i in (1..digits.len()).rev() {
+            digits[i - 1] += digits[i] / 10;
+            digits[i] %= 10;
+        }
+
+        Exact {
+            negative: self.negative != other.negative,
+            digits: digits.into_iter().map(|d| d as u8).collect(),
+            scale: self.scale + other.scale,
+        }
+    }
+
+    fn div(&self, other: &Exact) -> Option<Exact> {
+        if other.is_zero() {
+            return None;
+        }
+
+        // The divisor comes from a Fixed so it has at most 31 digits, which keeps
+        // the running remainder (less than ten times the divisor) within a u128
+        let divisor = other
+            .digits
+            .iter()
+            .fold(0u128, |acc, d| acc * 10 + *d as u128);
+
+        let mut digits = Vec::new();
+        let mut remainder = 0u128;
+        let mut significant = 0;
+        let mut extra = 0;
+        let mut dividend = self.digits.iter();
+        loop {
+            let next = match dividend.next() {
+                Some(d) => *d,
+                None => {
+                    // Keep producing fractional digits until the quotient is exact
+                    // or has more digits than could ever be kept
+                    let scale = (self.scale + extra) as i64 - other.scale as i64;
+                    if remainder == 0 || significant > MAX_DIGITS || scale > MAX_DIGITS as i64 {
+                        break;
+                    }
+                    extra += 1;
+                    0
+                }
+            };
+            remainder = remainder * 10 + next as u128;
+            let quotient = (remainder / divisor) as u8;
+            remainder %= divisor;
+            if quotient != 0 || significant != 0 {
+                significant += 1;
+            }
+            digits.push(quotient);
+        }
+
+        // The quotient of the digits is scaled by 10^-extra, while the value is
+        // the quotient scaled by 10^(other.scale - self.scale)
+        let scale = (self.scale + extra) as i64 - other.scale as i64;
+        if scale < 0 {
+            digits.resize(digits.len() + (-scale) as usize, 0);
+        }
+
+        Some(Exact {
+            negative: self.negative != other.negative,
+            digits,
+            scale: scale.max(0) as usize,
+        })
+    }
+}
+
+impl From<Fixed> for Exact {
+    fn from(fixed: Fixed) -> Exact {
+        Exact::from_i128(fixed.value, fixed.scale as usize)
+    }
+}
+
+impl Neg for Exact {
+    type Output = Exact;
+
+    fn neg(self) -> Exact {
+        Exact {
+            negative: !self.negative,
+            ..self
+        }
+    }
+}
+
+impl Ord for Exact {
+    fn cmp(&self, other: &Self) -> Ordering {
+        match (self.is_zero(), other.is_zero()) {
+            (true, true) => return Ordering::Equal,
+            (true, false) if other.negative => return Ordering::Greater,
+            (true, false) => return Ordering::Less,
+            (false, true) if self.negative => return Ordering::Less,
+            (false, true) => return Ordering::Greater,
+            _ => {}
+        }
+        match (self.negative, other.negative) {
+            (false, true) => Ordering::Greater,
+            (true, false) => Ordering::Less,
+            (false, false) => self.cmp_magnitude(other),
+            (true, true) => other.cmp_magnitude(self),
+        }
+    }
+}
+
+impl PartialOrd for Exact {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl PartialEq for Exact {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Exact {}
+
+#[cfg(test)]
+mod fixed_tests {
+    use crate::fixed::Fixed;
+
+    fn fixed(value: i128, scale: u32) -> Fixed {
+        Fixed::new(value, scale).unwrap()
+    }
+
+    #[test]
+    fn new() {
+        assert_eq!(fixed(150, 2), fixed(15, 1));
+        assert_ne!(fixed(105, 2), fixed(15, 1));
+        assert_eq!(fixed(0, 5), fixed(0, 0));
+        assert!(Fixed::new(9_999_999_999_999_999_999_999_999_999_999, 0).is_some());
+        assert!(Fixed::new(10_000_000_000_000_000_000_000_000_000_000, 0).is_none());
+        assert!(Fixed::new(1, 31).is_some());
+        assert!(Fixed::new(1, 32).is_none());
+    }
+
+    #[test]
+    fn from_digits() {
+        assert_eq!(Fixed::from_digits("1", "05"), Some(fixed(105, 2)));
+        assert_eq!(Fixed::from_digits("001", "50"), Some(fixed(15, 1)));
+        assert_eq!(Fixed::from_digits("", "5"), Some(fixed(5, 1)));
+        assert_eq!(Fixed::from_digits("12", ""), Some(fixed(12, 0)));
+        assert_eq!(Fixed::from_digits("1a", ""), None);
+        assert_eq!(
+            Fixed::from_digits(&"9".repeat(31), ""),
+            Some(fixed(10i128.pow(31) - 1, 0))
+        );
+        assert_eq!(Fixed::from_digits(&"9".repeat(32), ""), None);
+        assert_eq!(
+            Fixed::from_digits("1", &"1".repeat(30)),
+            Some(fixed((10i128.pow(31) - 1) / 9, 30))
+        );
+        assert_eq!(Fixed::from_digits("1", &"1".repeat(31)), None);
+    }
+
+    #[test]
+    fn digits_and_scale() {
+        assert_eq!((fixed(105, 2).digits(), fixed(105, 2).scale()), (3, 2));
+        assert_eq!((fixed(5, 2).digits(), fixed(5, 2).scale()), (2, 2));
+        assert_eq!((fixed(-1200, 0).digits(), fixed(-1200, 0).scale()), (4, 0));
+        assert_eq!((fixed(0, 0).digits(), fixed(0, 0).scale()), (1, 0));
+    }
+
+    #[test]
+    fn fits() {
+        assert!(fixed(105, 2).fits(3, 2));
+        assert!(fixed(105, 2).fits(31, 2));
+        assert!(!fixed(105, 2).fits(3, 1));
+        assert!(!fixed(105, 2).fits(2, 2));
+        assert!(fixed(-99, 0).fits(2, 0));
+        assert!(!fixed(1, 0).fits(32, 0));
+        assert!(!fixed(1, 0).fits(2, 3));
+    }
+
+    #[test]
+    fn display() {
+        assert_eq!(fixed(105, 2).to_string(), "1.05");
+        assert_eq!(fixed(-105, 2).to_string(), "-1.05");
+        assert_eq!(fixed(5, 3).to_string(), "0.005");
+        assert_eq!(fixed(-5, 3).to_string(), "-0.005");
+        assert_eq!(fixed(1200, 0).to_string(), "1200");
+        assert_eq!(fixed(0, 0).to_string(), "0");
+    }
+
+    #[test]
+    fn compare() {
+        assert!(fixed(105, 2) < fixed(15, 1));
+        assert!(fixed(-105, 2) > fixed(-15, 1));
+        assert!(fixed(-1, 0) < fixed(0, 0));
+        assert!(fixed(1, 31) > fixed(0, 0));
+        assert!(fixed(10i128.pow(31) - 1, 0) > fixed(10i128.pow(31) - 1, 31));
+        assert_eq!(fixed(150, 2).cmp(&fixed(15, 1)), std::cmp::Ordering::Equal);
+    }
+
+    #[test]
+    fn arithmetic() {
+        assert_eq!(fixed(105, 2).checked_add(fixed(15, 1)), Some(fixed(255, 2)));
+        assert_eq!(fixed(105, 2).checked_sub(fixed(15, 1)), Some(fixed(-45, 2)));
+        assert_eq!(fixed(-105, 2).checked_add(fixed(105, 2)), Some(fixed(0, 0)));
+        assert_eq!(
+            fixed(15, 1).checked_mul(fixed(-15, 1)),
+            Some(fixed(-225, 2))
+        );
+        assert_eq!(fixed(1, 0).checked_div(fixed(8, 0)), Some(fixed(125, 3)));
+        assert_eq!(fixed(1, 2).checked_div(fixed(1, 4)), Some(fixed(100, 0)));
+        assert_eq!(fixed(-9, 0).checked_div(fixed(3, 1)), Some(fixed(-30, 0)));
+        assert_eq!(fixed(1, 0).checked_div(fixed(0, 0)), None);
+        assert_eq!(-fixed(15, 1), fixed(-15, 1));
+
+        // Results beyond 31 digits discard fractional digits without rounding
+        let max = fixed(10i128.pow(31) - 1, 0);
+        let tiny = fixed(1, 31);
+        assert_eq!(max.checked_add(fixed(9, 1)), Some(max));
+        assert_eq!(max.checked_sub(tiny), Some(fixed(10i128.pow(31) - 2, 0)));
+        assert_eq!(max.checked_add(fixed(1, 0)), None);
+        assert_eq!(max.checked_mul(fixed(2, 0)), None);
+        assert_eq!(
+            fixed(2, 0).checked_div(fixed(3, 0)),
+            Some(fixed((10i128.pow(31) - 1) / 9 * 6, 31))
+        );
+        assert_eq!(
+            fixed(10i128.pow(30) + 1, 30).checked_mul(fixed(10i128.pow(30) + 1, 30)),
+            Some(fixed(10i128.pow(30) + 2, 30))
+        );
+        assert_eq!(tiny.checked_mul(tiny), Some(fixed(0, 0)));
+    }
+}
@@ -22,7 +22,7 @@
 use chumsky::prelude::*;
 
 use std::fmt::Display;
-use strum::EnumIter;
+use strum::{EnumIter, IntoEnumIterator};
 
 /// The Keyword enum lists all the keywords of the
 /// IDL language and provides means of iterating
@@ -118,19 +118,71 @@ impl Keyword {
     /// Builds a parser for a keyword that accepts the IDL defined keyword
     /// and returns the value of the keyword
     ///
+    /// A word that differs from the keyword only in case is reported as colliding
+    /// with the keyword, rather than as unexpected input.
+    ///
     /// Example:
     ///
     /// ```
     /// use ox_idl::keyword::Keyword;
+    /// use chumsky::error::SimpleReason;
     /// use chumsky::prelude::*;
     ///
     /// let false_parser = Keyword::False.make_parser();
     ///
     /// let result = false_parser.parse("FALSE");
     /// assert_eq!(result, Ok(Keyword::False));
+    ///
+    /// let errors = false_parser.parse("False").unwrap_err();
+    /// assert_eq!(errors[0].span(), 0..5);
+    /// assert_eq!(
+    ///     errors[0].reason(),
+    ///     &SimpleReason::Custom("`False` collides with keyword `FALSE`".to_string())
+    /// );
+    /// ```
+    pub fn make_parser(&self) -> impl Parser<char, Keyword, Error = Simple<char>> + Clone {
+        // 7.2.4
+        // Keywords must be written exactly as shown in the above list. Identifiers
+        // that collide with keywords (see 7.2.3, Identifiers) are illegal. For
+        // example, "boolean" is a valid keyword; "Boolean" and "BOOLEAN" are illegal
+        // identifiers.
+        let keyword = self.clone();
+        text::ident().try_map(move |word: String, span| {
+            let expected = keyword.to_string();
+            if word == expected {
+                Ok(keyword.clone())
+            } else if word.eq_ignore_ascii_case(&expected) {
+                Err(Simple::custom(span, keyword.collision_message(&word)))
+            } else {
+                Err(Simple::expected_input_found(span, None, None))
+            }
+        })
+    }
+
+    /// Describes a word colliding with this keyword, for use in diagnostics
+    pub fn collision_message(&self, word: &str) -> String {
+        format!("`{}` collides with keyword `{}`", word, self)
+    }
+
+    /// Finds the keyword that a word collides with, if any
+    ///
+    /// Keywords are matched without regard to case, so both `struct` and `Struct`
+    /// collide with the `struct` keyword.
+    ///
+    /// Example:
+    ///
     /// ```
-    pub fn make_parser(&self) -> impl Parser<char, Keyword, Error = Simple<char>> {
-        text::keyword(self.to_string()).to(self.clone())
+    /// use ox_idl::keyword::Keyword;
+    ///
+    /// assert_eq!(Keyword::collision("Struct"), Some(Keyword::Struct));
+    /// assert_eq!(Keyword::collision("true"), Some(Keyword::True));
+    /// assert_eq!(Keyword::collision("Structure"), None);
+    /// ```
+    pub fn collision(word: &str) -> Option<Keyword> {
+        // 7.2.4
+        // Keywords must be written exactly as shown in the above list. Identifiers
+        // that collide with keywords (see 7.2.3, Identifiers) are illegal.
+        Keyword::iter().find(|k| k.to_string().eq_ignore_ascii_case(word))
     }
 }
 
@@ -154,6 +206,7 @@ impl Display for Keyword {
 #[cfg(test)]
 mod keyword_tests {
     use crate::keyword::Keyword;
+    use chumsky::error::SimpleReason;
     use chumsky::Parser;
     use strum::IntoEnumIterator;
 
@@ -170,6 +223,16 @@ mod keyword_tests {
         assert!(Keyword::iter().find(|k| k == &Keyword::Struct).is_some());
     }
 
+    #[test]
+    fn collision() {
+        assert_eq!(Keyword::collision("struct"), Some(Keyword::Struct));
+        assert_eq!(Keyword::collision("STRUCT"), Some(Keyword::Struct));
+        assert_eq!(Keyword::collision("valuebase"), Some(Keyword::ValueBase));
+        assert_eq!(Keyword::collision("uint64"), Some(Keyword::UInt64));
+        assert_eq!(Keyword::collision("_struct"), None);
+        assert_eq!(Keyword::collision("Name"), None);
+    }
+
     #[test]
     fn make_parser() {
         assert_eq!(
@@ -182,5 +245,16 @@ mod keyword_tests {
             Ok(Keyword::Struct)
         );
         assert!(Keyword::Struct.make_parser().parse("Struct").is_err());
+        assert!(Keyword::Struct.make_parser().parse("structure").is_err());
+        assert!(Keyword::Struct.make_parser().parse("_struct").is_err());
+
+        let errors = Keyword::Struct.make_parser().parse("Struct").unwrap_err();
+        assert_eq!(errors[0].span(), 0..6);
+        assert_eq!(
+            errors[0].reason(),
+            &SimpleReason::Custom("`Struct` collides with keyword `struct`".to_string())
+        );
+        let errors = Keyword::Struct.make_parser().parse("union").unwrap_err();
+        assert!(!matches!(errors[0].reason(), SimpleReason::Custom(_)));
     }
 }
@@ -0,0 +1,336 @@
+/**********************************************************************************
+ * Copyright © 2022 Michael Volling
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy of
+ * this software and associated documentation files (the “Software”), to deal in
+ * the Software without restriction, including without limitation the rights to
+ * use, copy, modify, merge, publish, distribute, sublicense, and/or sell copies of
+ * the Software, and to permit persons to whom the Software is furnished to do so,
+ * subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED “AS IS”, WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY, FITNESS
+ * FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE AUTHORS OR
+ * COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER
+ * IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN
+ * CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+ *********************************************************************************/
+
+use chumsky::prelude::*;
+
+use std::ops::Range;
+
+use crate::keyword::Keyword;
+
+/// The CommentKind enum lists the two styles of comments in IDL
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CommentKind {
+    /// A `//` comment, running to the end of the line
+    Line,
+    /// A `/* */` comment, which may span multiple lines
+    Block,
+}
+
+/// The Comment type represents a comment in IDL source, which is retained
+/// so that it may be used to generate documentation
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Comment {
+    /// The style of the comment
+    pub kind: CommentKind,
+    /// The text of the comment, without the comment delimiters
+    pub text: String,
+    /// The span of the comment, including the delimiters
+    pub span: Range<usize>,
+}
+
+impl Comment {
+    /// Builds a parser is able to parse a single comment as specified in the IDL
+    /// Documentation
+    ///
+    /// Example
+    ///
+    /// ```
+    /// use ox_idl::lexer::{Comment, CommentKind};
+    /// use chumsky::prelude::*;
+    ///
+    /// let c = Comment::parser().parse("/* Hello */");
+    /// assert_eq!(
+    ///     c,
+    ///     Ok(Comment {
+    ///         kind: CommentKind::Block,
+    ///         text: " Hello ".to_string(),
+    ///         span: 0..11
+    ///     })
+    /// );
+    /// ```
+    pub fn parser() -> impl Parser<char, Comment, Error = Simple<char>> + Clone {
+        // 7.2.2
+        // The characters /* start a comment, which terminates with the characters
+        // */. These comments do not nest. The characters // start a comment, which
+        // terminates at the end of the line on which they occur. The comment
+        // characters //, /*, and */ have no special meaning within a // comment and
+        // are treated as other characters. Similarly, the comment characters // and
+        // /* have no special meaning within a /* comment.
+        let line = just("//")
+            .ignore_then(take_until(text::newline().or(end())))
+            .map(|(text, _)| (CommentKind::Line, text.into_iter().collect::<String>()));
+
+        let block = just("/*")
+            .ignore_then(
+                take_until(just("*/"))
+                    .map(|(text, _)| Some(text))
+                    .or(any().repeated().then_ignore(end()).to(None)),
+            )
+            .try_map(|text, span| match text {
+                Some(text) => Ok((CommentKind::Block, text.into_iter().collect::<String>())),
+                None => Err(Simple::custom(span, "unterminated block comment")),
+            });
+
+        line.or(block)
+            .map_with_span(|(kind, text), span| Comment { kind, text, span })
+    }
+
+    /// Builds a parser that collects every comment in a complete IDL source, skipping
+    /// over string and character literals so their contents are not mistaken for
+    /// comments
+    ///
+    /// Example
+    ///
+    /// ```
+    /// use ox_idl::lexer::Comment;
+    /// use chumsky::prelude::*;
+    ///
+    /// let comments = Comment::collector()
+    ///     .parse("// A constant\nconst string S = \"// not a comment\"; /* end */")
+    ///     .unwrap();
+    /// assert_eq!(comments.len(), 2);
+    /// assert_eq!(comments[0].text, " A constant");
+    /// assert_eq!(comments[1].text, " end ");
+    /// ```
+    pub fn collector() -> impl Parser<char, Vec<Comment>, Error = Simple<char>> {
+        let quoted = |quote: char| {
+            just(quote)
+                .ignore_then(
+                    just('\\')
+                        .ignore_then(any())
+                        .or(filter(move |c: &char| *c != quote && *c != '\\'))
+                        .repeated(),
+                )
+                .then_ignore(just(quote))
+        };
+
+        choice((
+            Self::parser().map(Some),
+            quoted('"').to(None),
+            quoted('\'').to(None),
+            any().to(None),
+        ))
+        .repeated()
+        .then_ignore(end())
+        .map(|comments| comments.into_iter().flatten().collect())
+    }
+
+    /// Returns the run of comments that immediately precede `offset` in `source`,
+    /// separated from it and from each other by nothing but whitespace
+    ///
+    /// This is intended for finding the documentation comments of a declaration
+    /// from its span.
+    ///
+    /// Example
+    ///
+    /// ```
+    /// use ox_idl::lexer::Comment;
+    /// use chumsky::prelude::*;
+    ///
+    /// let source = "/* unrelated */ const long A = 1;\n// Docs for B\nconst long B = 2;";
+    /// let comments = Comment::collector().parse(source).unwrap();
+    ///
+    /// let docs = Comment::preceding(&comments, source, source.rfind("const").unwrap());
+    /// assert_eq!(docs.len(), 1);
+    /// assert_eq!(docs[0].text, " Docs for B");
+    /// ```
+    pub fn preceding<'a>(comments: &'a [Comment], source: &str, offset: usize) -> &'a [Comment] {
+        let only_whitespace = |range: Range<usize>| {
+            source
+                .chars()
+                .skip(range.start)
+                .take(range.end.saturating_sub(range.start))
+                .all(char::is_whitespace)
+        };
+
+        let before = comments.partition_point(|c| c.span.end <= offset);
+        let mut first = before;
+        let mut end = offset;
+        while first > 0 && only_whitespace(comments[first - 1].span.end..end) {
+            first -= 1;
+            end = comments[first].span.start;
+        }
+        &comments[first..before]
+    }
+}
+
+/// Builds a parser that skips any amount of whitespace and comments
+///
+/// Example
+///
+/// ```
+/// use ox_idl::lexer::padding;
+/// use chumsky::prelude::*;
+///
+/// let p = padding().ignore_then(just('x'));
+/// assert_eq!(p.parse(" // comment\n /* comment */ x"), Ok('x'));
+/// ```
+pub fn padding() -> impl Parser<char, (), Error = Simple<char>> + Clone {
+    // 7.2.1
+    // Spaces, horizontal and vertical tabs, newlines, form feeds, and comments
+    // (collective, "white space") are ignored except as they serve to separate
+    // tokens.
+    text::whitespace()
+        .ignore_then(Comment::parser().then_ignore(text::whitespace()).repeated())
+        .ignored()
+}
+
+/// Builds a parser for a token, which skips any whitespace and comments that
+/// follow it
+///
+/// Every token consumes the padding after it, so a complete specification only
+/// needs to skip the padding at its very start.
+///
+/// Example
+///
+/// ```
+/// use ox_idl::keyword::Keyword;
+/// use ox_idl::lexer::token;
+/// use ox_idl::literal::Literal;
+/// use chumsky::prelude::*;
+///
+/// let p = token(Keyword::True.make_parser()).then(token(Literal::int_parser()));
+/// assert_eq!(
+///     p.parse("TRUE /* one */ 1 // done"),
+///     Ok((Keyword::True, Literal::Integer(1)))
+/// );
+/// ```
+pub fn token<T, P>(parser: P) -> impl Parser<char, T, Error = Simple<char>>
+where
+    P: Parser<char, T, Error = Simple<char>>,
+{
+    parser.then_ignore(padding())
+}
+
+/// Builds a parser for a punctuation token such as `{` or `::`, which skips any
+/// whitespace and comments that follow it
+pub fn symbol(s: &'static str) -> impl Parser<char, &'static str, Error = Simple<char>> + Clone {
+    just(s).then_ignore(padding())
+}
+
+/// Builds a parser for a keyword token, which skips any whitespace and comments
+/// that follow it
+pub fn keyword(keyword: Keyword) -> impl Parser<char, Keyword, Error = Simple<char>> + Clone {
+    keyword.make_parser().then_ignore(padding())
+}
+
+#[cfg(test)]
+mod lexer_tests {
+    use crate::lexer::{padding, symbol, token, Comment, CommentKind};
+    use chumsky::error::SimpleReason;
+    use chumsky::prelude::*;
+
+    #[test]
+    fn parse_comment() {
+        assert_eq!(
+            Comment::parser().parse("// Hello\nWorld"),
+            Ok(Comment {
+                kind: CommentKind::Line,
+                text: " Hello".to_string(),
+                span: 0..9
+            })
+        );
+        assert_eq!(
+            Comment::parser().parse("// Hello"),
+            Ok(Comment {
+                kind: CommentKind::Line,
+                text: " Hello".to_string(),
+                span: 0..8
+            })
+        );
+        assert_eq!(
+            Comment::parser().parse("/* A\n * B // C */"),
+            Ok(Comment {
+                kind: CommentKind::Block,
+                text: " A\n * B // C ".to_string(),
+                span: 0..17
+            })
+        );
+
+        // Block comments do not nest
+        assert_eq!(
+            Comment::parser()
+                .then(just(" */"))
+                .parse("/* /* */ */")
+                .map(|(c, _)| c.text),
+            Ok(" /* ".to_string())
+        );
+
+        let errors = Comment::parser().parse("/* open").unwrap_err();
+        assert_eq!(errors[0].span(), 0..7);
+        assert_eq!(
+            errors[0].reason(),
+            &SimpleReason::Custom("unterminated block comment".to_string())
+        );
+    }
+
+    #[test]
+    fn skip_padding() {
+        assert_eq!(padding().then(end()).parse(""), Ok(((), ())));
+        assert_eq!(
+            padding()
+                .then(end())
+                .parse(" \t\n// one\n/* two */ // three\n  /**/"),
+            Ok(((), ()))
+        );
+        assert!(padding().then(end()).parse("/* open").is_err());
+    }
+
+    #[test]
+    fn tokens() {
+        let p = token(text::ident::<char, Simple<char>>())
+            .then_ignore(symbol("::"))
+            .then(token(text::ident()))
+            .then_ignore(symbol(";"))
+            .then_ignore(end());
+        assert_eq!(
+            p.parse("a/**/:: // c\n b ; // end"),
+            Ok(("a".to_string(), "b".to_string()))
+        );
+        assert!(p.parse("a : : b;").is_err());
+    }
+
+    #[test]
+    fn collect_comments() {
+        let source = "// one\nconst char C = '\\''; /* two */\nconst string S = \"\\\" // three\";";
+        let comments = Comment::collector().parse(source).unwrap();
+        assert_eq!(
+            comments.iter().map(|c| c.text.as_str()).collect::<Vec<_>>(),
+            vec![" one", " two "]
+        );
+    }
+
+    #[test]
+    fn preceding_comments() {
+        let source = "// a\n\n// b\n/* c */ X /* d */ Y";
+        let comments = Comment::collector().parse(source).unwrap();
+        let texts = |cs: &[Comment]| cs.iter().map(|c| c.text.clone()).collect::<Vec<_>>();
+
+        let x = source.find('X').unwrap();
+        assert_eq!(
+            texts(Comment::preceding(&comments, source, x)),
+            vec![" a", " b", " c "]
+        );
+        let y = source.find('Y').unwrap();
+        assert_eq!(texts(Comment::preceding(&comments, source, y)), vec![" d "]);
+        assert!(Comment::preceding(&comments, source, 0).is_empty());
+    }
+}
@@ -19,5 +19,17 @@
  * CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
  *********************************************************************************/
 
+pub mod annotation;
+pub mod ast;
+pub mod expr;
+pub mod fixed;
 pub mod keyword;
+pub mod lexer;
 pub mod literal;
+pub mod name;
+pub mod primitive;
+pub mod scope;
+pub mod template;
+#[cfg(test)]
+mod test_utils;
+pub mod validate;
@@ -21,26 +21,172 @@
 
 use chumsky::prelude::*;
 
+use std::fmt::Display;
+use std::ops::Range;
+
+use crate::fixed::Fixed;
 use crate::keyword::Keyword;
+use crate::lexer::padding;
 
 /// The Literal type represents an IDL literal value
-///
-/// There is only basic support at the moment, with the wchar
-/// and wstring types not being yet supported.
 #[derive(Debug, Clone, PartialEq)]
 pub enum Literal {
     /// A Bool represents a boolean literal
     Bool(bool),
-    /// A Bool represents a character or wide character literal
+    /// A Character represents a character literal
     Character(char),
+    /// A WideCharacter represents a wide character literal
+    WideCharacter(char),
     /// A FixedPoint represents a fixed point literal
-    FixedPoint(u64, u64),
-    /// A FloatingPoint represents a floating point (single or double precision) literal
-    FloatingPoint(f64),
+    FixedPoint(Fixed),
+    /// A FloatingPoint represents a floating point literal
+    FloatingPoint(FloatLiteral),
     /// An Integer represents a integer literal
     Integer(u64),
-    /// A Str represents a string or wstring literal
+    /// A Str represents a string literal
     Str(String),
+    /// A WideStr represents a wstring literal
+    WideStr(String),
+}
+
+/// The FloatPrecision type lists the precisions of the IDL floating point types,
+/// ordered from the narrowest to the widest
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum FloatPrecision {
+    /// The IDL float type (IEEE single precision)
+    Float,
+    /// The IDL double type (IEEE double precision)
+    Double,
+    /// The IDL long double type (IEEE double-extended precision)
+    LongDouble,
+}
+
+impl Display for FloatPrecision {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            FloatPrecision::Float => "float",
+            FloatPrecision::Double => "double",
+            FloatPrecision::LongDouble => "long double",
+        })
+    }
+}
+
+/// The FloatLiteral type represents a floating point literal
+///
+/// The literal keeps the lexeme it was written as, so it can be reproduced exactly,
+/// along with the narrowest precision that is able to represent its magnitude.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FloatLiteral {
+    lexeme: String,
+    precision: FloatPrecision,
+}
+
+impl FloatLiteral {
+    /// Creates a floating point literal from its lexeme
+    ///
+    /// Returns None if the lexeme is not a floating point literal, or if its
+    /// magnitude is outside the range of even a long double.
+    ///
+    /// Example
+    ///
+    /// ```
+    /// use ox_idl::literal::{FloatLiteral, FloatPrecision};
+    ///
+    /// let f = FloatLiteral::new("1e300").unwrap();
+    /// assert_eq!(f.precision(), FloatPrecision::Double);
+    /// assert_eq!(f.to_f64(), Some(1e300));
+    ///
+    /// assert!(FloatLiteral::new("1e5000").is_none());
+    /// ```
+    pub fn new(lexeme: &str) -> Option<FloatLiteral> {
+        let (mantissa, exponent) = match lexeme.find(['e', 'E']) {
+            Some(i) => (&lexeme[..i], lexeme[i + 1..].parse::<i64>().ok()?),
+            None => (lexeme, 0),
+        };
+        let (int_part, frac_part) = mantissa.split_once('.').unwrap_or((mantissa, ""));
+        if int_part.is_empty() && frac_part.is_empty()
+            || !int_part
+                .chars()
+                .chain(frac_part.chars())
+                .all(|c| c.is_ascii_digit())
+        {
+            return None;
+        }
+
+        let value: f64 = lexeme.parse().ok()?;
+        let digits = int_part.to_string() + frac_part;
+        let precision = match digits.find(|c| c != '0') {
+            // Zero is representable at any precision
+            None => FloatPrecision::Float,
+            // Subnormal values are still within range, only a value which overflows to
+            // infinity or underflows to zero needs a wider type
+            Some(_) if Self::in_range(value as f32) => FloatPrecision::Float,
+            Some(_) if Self::in_range(value) => FloatPrecision::Double,
+            Some(first) => {
+                // Outside of the range of a double, so compare the decimal exponent of
+                // the literal against the limits of an IEEE double-extended. An exponent
+                // too large to adjust is far outside of those limits.
+                let decimal_exponent = exponent
+                    .checked_add(int_part.len() as i64)?
+                    .checked_sub(first as i64 + 1)?;
+                let leading: f64 = format!("{}.{}", &digits[first..=first], &digits[first + 1..])
+                    .parse()
+                    .ok()?;
+                let below_max = decimal_exponent < 4932
+                    || (decimal_exponent == 4932 && leading <= 1.189_731_495_357_231_8);
+                let above_min = decimal_exponent > -4951
+                    || (decimal_exponent == -4951 && leading >= 3.645_199_531_882_474);
+                if !(below_max && above_min) {
+                    return None;
+                }
+                FloatPrecision::LongDouble
+            }
+        };
+
+        Some(FloatLiteral {
+            lexeme: lexeme.to_string(),
+            precision,
+        })
+    }
+
+    /// Returns true if a non-zero literal converted to `value` neither overflowed nor
+    /// underflowed, that is the value is either normal or subnormal
+    fn in_range<F: Into<f64>>(value: F) -> bool {
+        let value = value.into();
+        value.is_finite() && value != 0.0
+    }
+
+    /// Returns the literal as it was written
+    pub fn lexeme(&self) -> &str {
+        self.lexeme.as_str()
+    }
+
+    /// Returns the narrowest precision able to represent the literal
+    pub fn precision(&self) -> FloatPrecision {
+        self.precision
+    }
+
+    /// Returns true if the literal can be represented by a type of the given
+    /// precision without overflowing or underflowing
+    pub fn fits(&self, precision: FloatPrecision) -> bool {
+        self.precision <= precision
+    }
+
+    /// Returns the value of the literal as a double, or None if the literal is
+    /// outside of the range of a double
+    pub fn to_f64(&self) -> Option<f64> {
+        if self.fits(FloatPrecision::Double) {
+            self.lexeme.parse().ok()
+        } else {
+            None
+        }
+    }
+}
+
+impl Display for FloatLiteral {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.lexeme.as_str())
+    }
 }
 
 impl Literal {
@@ -119,7 +265,7 @@ impl Literal {
         // 7.2.6.1
         // An integer literal consisting of a sequence of digits is taken to be decimal
         // (base ten) unless it begins with 0 (digit zero).
-        text::int(10).map(|d: String| Literal::Integer(d.parse().unwrap()))
+        Self::dec_digits().try_map(Self::integer)
     }
 
     /// Builds a parser is able to parse a hexidecimal integer literal as specified
@@ -141,10 +287,7 @@ impl Literal {
         // A sequence of digits preceded by 0x (or 0X) is taken to be a hexadecimal
         // integer (base sixteen). The hexadecimal digits include a (or A) through
         // f (or F) with decimal values ten through fifteen, respectively.
-        just("0x")
-            .or(just("0X"))
-            .ignore_then(text::int(16))
-            .map(|d: String| Literal::Integer(u64::from_str_radix(d.as_str(), 16).unwrap()))
+        Self::hex_digits().try_map(Self::integer)
     }
 
     /// Builds a parser is able to parse an octal integer literal as specified in the IDL
@@ -166,9 +309,36 @@ impl Literal {
         // A sequence of digits starting with 0 is taken to be an octal integer (base eight).
         // The digits 8 and 9 are not octal digits and thus are not allowed in an octal
         // integer literal.
-        just("0").then(text::int(8)).map(|(_p, d): (&str, String)| {
-            Literal::Integer(u64::from_str_radix(d.as_str(), 8).unwrap())
-        })
+        Self::oct_digits().try_map(Self::integer)
+    }
+
+    /// Builds a parser for the digits of a decimal integer literal, along with its radix
+    fn dec_digits() -> impl Parser<char, (String, u32), Error = Simple<char>> {
+        text::int(10).map(|d| (d, 10))
+    }
+
+    /// Builds a parser for the digits of a hexadecimal integer literal, along with its radix
+    fn hex_digits() -> impl Parser<char, (String, u32), Error = Simple<char>> {
+        just("0x")
+            .or(just("0X"))
+            .ignore_then(text::int(16))
+            .map(|d| (d, 16))
+    }
+
+    /// Builds a parser for the digits of an octal integer literal, along with its radix
+    fn oct_digits() -> impl Parser<char, (String, u32), Error = Simple<char>> {
+        just("0").ignore_then(text::int(8)).map(|d| (d, 8))
+    }
+
+    /// Converts the digits of an integer literal in the given radix into an Integer,
+    /// reporting literals that do not fit in 64 bits as an error over `span`
+    fn integer(
+        (digits, radix): (String, u32),
+        span: Range<usize>,
+    ) -> Result<Literal, Simple<char>> {
+        u64::from_str_radix(digits.as_str(), radix)
+            .map(Self::Integer)
+            .map_err(|_| Simple::custom(span, "integer literal exceeds 64 bits"))
     }
 
     /// Builds a parser is able to parse any integer literal as specified in the IDL
@@ -201,26 +371,36 @@ impl Literal {
         // A sequence of digits preceded by 0x (or 0X) is taken to be a hexadecimal
         // integer (base sixteen). The hexadecimal digits include a (or A) through
         // f (or F) with decimal values ten through fifteen, respectively.
-        choice((
-            Self::hex_int_parser(),
-            Self::oct_int_parser(),
-            Self::dec_int_parser(),
-        ))
+        //
+        // The digits are matched before being converted so that a literal which
+        // overflows is reported, rather than falling back to a shorter prefix of it
+        choice((Self::hex_digits(), Self::oct_digits(), Self::dec_digits())).try_map(Self::integer)
     }
 
     /// Builds a parser is able to parse a floating point literal as specified in the IDL
     /// Documentation
     ///
+    /// Literals that exceed the range of a long double are reported as errors rather
+    /// than silently becoming infinite.
+    ///
     /// Example
     ///
     /// ```
-    /// use ox_idl::literal::Literal;
+    /// use ox_idl::literal::{FloatLiteral, FloatPrecision, Literal};
     /// use chumsky::prelude::*;
     ///
     /// let parser = Literal::float_parser();
     ///
     /// let f = parser.parse("1.3");
-    /// assert_eq!(f, Ok(Literal::FloatingPoint(1.3)));
+    /// assert_eq!(f, Ok(Literal::FloatingPoint(FloatLiteral::new("1.3").unwrap())));
+    ///
+    /// let Literal::FloatingPoint(e) = parser.parse("2.5E-3").unwrap() else { panic!() };
+    /// assert_eq!(e.to_string(), "2.5E-3");
+    /// assert_eq!(e.to_f64(), Some(0.0025));
+    ///
+    /// let Literal::FloatingPoint(big) = parser.parse("1e400").unwrap() else { panic!() };
+    /// assert_eq!(big.precision(), FloatPrecision::LongDouble);
+    /// assert_eq!(big.to_f64(), None);
     /// ```
     pub fn float_parser() -> impl Parser<char, Literal, Error = Simple<char>> {
         // 7.2.6.4
@@ -233,38 +413,67 @@ impl Literal {
         let digits = text::digits(10);
         let dot = just('.');
 
-        let decimal_only = digits
+        let exponent = one_of("eE").then(one_of("+-").or_not()).then(digits).map(
+            |((e, sign), d): ((char, Option<char>), String)| {
+                e.to_string() + sign.map(String::from).unwrap_or_default().as_str() + d.as_str()
+            },
+        );
+
+        let decimal_and_optional_fractional = digits
             .then_ignore(dot)
-            .map(|d| Self::FloatingPoint(d.parse().unwrap()));
+            .then(digits.or_not())
+            .then(exponent.clone().or_not())
+            .map(|((d, f), e): ((String, Option<String>), Option<String>)| {
+                d + "." + f.unwrap_or_default().as_str() + e.unwrap_or_default().as_str()
+            });
 
-        let fractional_only = dot
-            .ignore_then(digits)
-            .map(|f| Self::FloatingPoint(('.'.to_string() + f.as_str()).parse().unwrap()));
+        let fractional_only = dot.ignore_then(digits).then(exponent.clone().or_not()).map(
+            |(f, e): (String, Option<String>)| {
+                ".".to_string() + f.as_str() + e.unwrap_or_default().as_str()
+            },
+        );
 
-        let decimal_and_fractional =
-            digits
-                .then_ignore(just('.'))
-                .then(digits)
-                .map(|(d, f): (String, String)| {
-                    Self::FloatingPoint((d + "." + f.as_str()).parse().unwrap())
-                });
+        let decimal_and_exponent = digits
+            .then(exponent)
+            .map(|(d, e): (String, String)| d + e.as_str());
 
-        choice((decimal_and_fractional, decimal_only, fractional_only))
+        choice((
+            decimal_and_optional_fractional,
+            fractional_only,
+            decimal_and_exponent,
+        ))
+        .try_map(|lexeme: String, span| {
+            FloatLiteral::new(lexeme.as_str())
+                .map(Self::FloatingPoint)
+                .ok_or_else(|| {
+                    Simple::custom(
+                        span,
+                        format!(
+                            "floating point literal `{}` exceeds the range of long double",
+                            lexeme
+                        ),
+                    )
+                })
+        })
     }
 
     /// Builds a parser is able to parse a fixed point literal as specified in the IDL
     /// Documentation
     ///
+    /// Literals needing more than the 31 digits of the IDL fixed type are reported
+    /// as errors.
+    ///
     /// Example
     ///
     /// ```
+    /// use ox_idl::fixed::Fixed;
     /// use ox_idl::literal::Literal;
     /// use chumsky::prelude::*;
     ///
     /// let parser = Literal::fixed_parser();
     ///
     /// let f = parser.parse("1.3d");
-    /// assert_eq!(f, Ok(Literal::FixedPoint(1, 3)));
+    /// assert_eq!(f, Ok(Literal::FixedPoint(Fixed::new(13, 1).unwrap())));
     /// ```
     pub fn fixed_parser() -> impl Parser<char, Literal, Error = Simple<char>> {
         // 7.2.6.5
@@ -280,29 +489,38 @@ impl Literal {
         let decimal_only = digits
             .then_ignore(dot.repeated().at_most(1))
             .then_ignore(the_d)
-            .map(|d| Self::FixedPoint(d.parse().unwrap(), 0));
+            .map(|d| (d, String::new()));
 
         let fractional_only = dot
             .ignore_then(digits)
             .then_ignore(the_d)
-            .map(|f| Self::FixedPoint(0, f.parse().unwrap()));
+            .map(|f| (String::new(), f));
 
         let decimal_and_fractional = digits
             .then_ignore(just('.'))
             .then(digits)
-            .then_ignore(the_d)
-            .map(|(d, f): (String, String)| {
-                Self::FixedPoint(d.parse().unwrap(), f.parse().unwrap())
-            });
+            .then_ignore(the_d);
 
-        choice((decimal_and_fractional, decimal_only, fractional_only))
+        choice((decimal_and_fractional, decimal_only, fractional_only)).try_map(
+            |(d, f): (String, String), span| {
+                Fixed::from_digits(d.as_str(), f.as_str())
+                    .map(Self::FixedPoint)
+                    .ok_or_else(|| {
+                        Simple::custom(
+                            span,
+                            format!(
+                                "fixed point literal exceeds {} digits",
+                                crate::fixed::MAX_DIGITS
+                            ),
+                        )
+                    })
+            },
+        )
     }
 
     /// Builds a parser is able to parse a character literal as specified in the IDL
     /// Documentation
     ///
-    /// FIXME: This function does not yet support escape sequences
-    ///
     /// Example
     ///
     /// ```
@@ -313,6 +531,9 @@ impl Literal {
     ///
     /// let c = parser.parse("'c'");
     /// assert_eq!(c, Ok(Literal::Character('c')));
+    ///
+    /// let n = parser.parse("'\\n'");
+    /// assert_eq!(n, Ok(Literal::Character('\n')));
     /// ```
     pub fn char_parser() -> impl Parser<char, Literal, Error = Simple<char>> {
         // 7.2.6.2
@@ -325,13 +546,75 @@ impl Literal {
         // standard (see Table 7-5 on page 17). The meaning of all other characters is
         // implementation-dependent.
         //
-        // NOTE: Since ASCII and ISO 646 are the same for 8 bit characters, we should
-        // be fine to use 'is_ascii'
+        // NOTE: ISO 646 and Latin-1 share their first 128 characters, so any character
+        // whose numerical value fits in 8 bits is accepted
+        Self::character_contents(false).map(Self::Character)
+    }
+
+    /// Builds a parser is able to parse a wide character literal as specified in the IDL
+    /// Documentation
+    ///
+    /// Example
+    ///
+    /// ```
+    /// use ox_idl::literal::Literal;
+    /// use chumsky::prelude::*;
+    ///
+    /// let parser = Literal::wide_char_parser();
+    ///
+    /// let c = parser.parse("L'c'");
+    /// assert_eq!(c, Ok(Literal::WideCharacter('c')));
+    ///
+    /// let u = parser.parse("L'\\u00E9'");
+    /// assert_eq!(u, Ok(Literal::WideCharacter('é')));
+    /// ```
+    pub fn wide_char_parser() -> impl Parser<char, Literal, Error = Simple<char>> {
+        // 7.2.6.2.1
+        // Wide character literals have an L prefix, for example:
+        //    const wchar C1 = L'X';
         //
-        // TODO: Support escape sequences
-        filter::<_, _, Simple<char>>(|c: &char| c.is_ascii())
-            .delimited_by(just("'"), just("'"))
-            .map(Self::Character)
+        // Attempts to assign a wide character literal to a non-wide character
+        // constant, or to assign a non-wide character literal to a wide character
+        // constant, shall be treated as an error.
+        just('L')
+            .ignore_then(Self::character_contents(true))
+            .map(Self::WideCharacter)
+    }
+
+    /// Builds a parser for the quoted contents of a character literal, which must be
+    /// exactly one character or escape sequence. Narrow characters are limited to 8 bits.
+    fn character_contents(wide: bool) -> impl Parser<char, char, Error = Simple<char>> {
+        Self::escape_sequence(wide)
+            .or(Self::source_character(wide, '\''))
+            .repeated()
+            .delimited_by(just('\''), just('\''))
+            .try_map(move |chars, span| match chars.as_slice() {
+                [c] => Ok(*c),
+                [] => Err(Simple::custom(span, "character literal is empty")),
+                _ if wide => Err(Simple::custom(
+                    span,
+                    "wide character literal contains more than one character",
+                )),
+                _ => Err(Simple::custom(
+                    span,
+                    "character literal contains more than one character",
+                )),
+            })
+    }
+
+    /// Builds a parser for a single unescaped character of a character or string literal
+    /// delimited by `quote`. Characters of narrow literals must fit in 8 bits.
+    fn source_character(wide: bool, quote: char) -> impl Parser<char, char, Error = Simple<char>> {
+        filter(move |c: &char| *c != quote && *c != '\\').try_map(move |c, span| {
+            if !wide && c as u32 > 0xFF {
+                Err(Simple::custom(
+                    span,
+                    format!("`{}` is out of range for a char", c),
+                ))
+            } else {
+                Ok(c)
+            }
+        })
     }
 
     /// Builds a parser is able to parse a string literal as specified in the IDL
@@ -372,20 +655,152 @@ impl Literal {
         // ‘\xA’ and ‘B’ after concatenation (and not the single hexadecimal character
         // ‘\xAB’).
         //
-        // TODO: Support escape sequences
+        // NOTE: Characters of non-wide strings are limited to the 8-bit Latin-1 range
+        Self::concatenated_strings(false).map(Self::Str)
+    }
+
+    /// Builds a parser is able to parse a wide string literal as specified in the IDL
+    /// Documentation
+    ///
+    /// Example
+    ///
+    /// ```
+    /// use ox_idl::literal::Literal;
+    /// use chumsky::prelude::*;
+    ///
+    /// let parser = Literal::wide_string_parser();
+    ///
+    /// let s = parser.parse("L\"Hello\" L\"World\"");
+    /// assert_eq!(s, Ok(Literal::WideStr("HelloWorld".to_string())));
+    /// ```
+    pub fn wide_string_parser() -> impl Parser<char, Literal, Error = Simple<char>> {
+        // 7.2.6.3
+        // Wide string literals have in addition an L prefix, for example:
+        //    const wstring S2 = L"Hello";
         //
-        // FIXME: Right now we are parsing the utf-8 format. Ideally we would use the
-        // Latin-1 character set
-        let single_string = filter::<_, _, Simple<char>>(|c: &char| *c != '"')
+        // A wide string literal shall not contain the wide character with value zero.
+        Self::concatenated_strings(true).map(Self::WideStr)
+    }
+
+    /// Builds a parser for one or more adjacent string literals, which are concatenated
+    /// into a single string. When `wide` is set each string requires the L prefix.
+    fn concatenated_strings(wide: bool) -> impl Parser<char, String, Error = Simple<char>> {
+        // Wide strings require exactly one L prefix, narrow strings must not have one
+        let prefix = just('L').repeated().exactly(if wide { 1 } else { 0 });
+
+        let single_string = Self::escape_sequence(wide)
+            .or(Self::source_character(wide, '"'))
+            .try_map(move |c, span| match c {
+                '\0' if wide => Err(Simple::custom(
+                    span,
+                    "a wide string literal shall not contain the wide character with value zero",
+                )),
+                '\0' => Err(Simple::custom(
+                    span,
+                    "a string literal shall not contain the character '\\0'",
+                )),
+                _ => Ok(c),
+            })
             .repeated()
-            .delimited_by(just('"'), just('"'))
+            .delimited_by(prefix.then(just('"')), just('"'))
             .collect::<String>();
 
-        // Now support implicit concatination
+        // Now support implicit concatination, adjacent strings may be separated by
+        // any whitespace or comments
         single_string
-            .then_ignore(text::whitespace())
+            .separated_by(padding())
+            .at_least(1)
+            .map(|vs| vs.concat())
+    }
+
+    /// Builds a parser for a single escape sequence (including the leading backslash)
+    /// as listed in Table 7-9 of the IDL documentation, returning the escaped character
+    ///
+    /// Malformed or out-of-range escapes are reported as custom errors spanning the
+    /// whole escape sequence. The `wide` flag selects whether the escape is part of a
+    /// wide character or wide string literal, which is the only place the `\u` escape
+    /// is allowed and where octal escapes may exceed the range of a char.
+    fn escape_sequence(wide: bool) -> impl Parser<char, char, Error = Simple<char>> {
+        // 7.2.6.2.2
+        // The escape \ooo consists of the backslash followed by one, two, or three
+        // octal digits that are taken to specify the value of the desired character.
+        // The escape \xhh consists of the backslash followed by x followed by one or
+        // two hexadecimal digits that are taken to specify the value of the desired
+        // character. The escape \uhhhh consists of a backslash followed by the
+        // character ‘u’, followed by one, two, three or four hexadecimal digits. This
+        // represents a unicode character literal. A sequence of octal or hexadecimal
+        // digits is terminated by the first character that is not an octal digit or a
+        // hexadecimal digit, respectively.
+        //
+        // The \u escape is valid only with wchar and wstring types.
+        let simple = choice((
+            just('n').to('\n'),
+            just('t').to('\t'),
+            just('v').to('\x0B'),
+            just('b').to('\x08'),
+            just('r').to('\r'),
+            just('f').to('\x0C'),
+            just('a').to('\x07'),
+            just('\\').to('\\'),
+            just('?').to('?'),
+            just('\'').to('\''),
+            just('"').to('"'),
+        ))
+        .map(Ok);
+
+        let octal = filter(|c: &char| c.is_digit(8))
             .repeated()
-            .map(|vs| Self::Str(vs.concat()))
+            .at_least(1)
+            .at_most(3)
+            .collect::<String>()
+            .map(move |digits| {
+                // At most three octal digits can never overflow a u32
+                let value = u32::from_str_radix(digits.as_str(), 8).unwrap();
+                if !wide && value > 0xFF {
+                    Err(format!(
+                        "octal escape sequence `\\{}` is out of range for a char",
+                        digits
+                    ))
+                } else {
+                    // Values up to 0o777 are always valid unicode scalar values
+                    Ok(char::from_u32(value).unwrap())
+                }
+            });
+
+        let hex_digits = |max| {
+            filter(|c: &char| c.is_ascii_hexdigit())
+                .repeated()
+                .at_most(max)
+                .collect::<String>()
+        };
+
+        let hex = just('x').ignore_then(hex_digits(2)).map(|digits| {
+            if digits.is_empty() {
+                Err("`\\x` escape sequence has no hexadecimal digits".to_string())
+            } else {
+                // At most two hexadecimal digits always fit within a char
+                Ok(char::from_u32(u32::from_str_radix(digits.as_str(), 16).unwrap()).unwrap())
+            }
+        });
+
+        let unicode = just('u').ignore_then(hex_digits(4)).map(move |digits| {
+            if !wide {
+                Err("`\\u` escape sequences are only valid in wide character \
+                     and wide string literals"
+                    .to_string())
+            } else if digits.is_empty() {
+                Err("`\\u` escape sequence has no hexadecimal digits".to_string())
+            } else {
+                char::from_u32(u32::from_str_radix(digits.as_str(), 16).unwrap())
+                    .ok_or_else(|| format!("`\\u{}` is not a valid unicode character", digits))
+            }
+        });
+
+        let unknown = any().map(|c| Err(format!("unknown escape sequence `\\{}`", c)));
+
+        just('\\')
+            .ignore_then(choice((simple, octal, hex, unicode, unknown)))
+            .try_map(|escaped, span| escaped.map_err(|msg| Simple::custom(span, msg)))
     }
 
     /// Builds a parser is able to parse any literal as specified in the IDL
@@ -413,14 +828,19 @@ impl Literal {
             Self::int_parser(),
             Self::char_parser(),
             Self::string_parser(),
+            Self::wide_char_parser(),
+            Self::wide_string_parser(),
         ))
     }
 }
 
 #[cfg(test)]
 mod literal_tests {
-    use crate::literal::Literal;
-    use chumsky::Parser;
+    use crate::ast::Specification;
+    use crate::fixed::Fixed;
+    use crate::literal::{FloatLiteral, FloatPrecision, Literal};
+    use chumsky::error::SimpleReason;
+    use chumsky::prelude::*;
 
     #[test]
     fn parse_true() {
@@ -495,6 +915,35 @@ mod literal_tests {
         );
     }
 
+    #[test]
+    fn parse_int_overflow() {
+        assert_eq!(
+            Literal::int_parser().parse("18446744073709551615"),
+            Ok(Literal::Integer(u64::MAX))
+        );
+        assert_eq!(
+            Literal::int_parser().parse("0xFFFFFFFFFFFFFFFF"),
+            Ok(Literal::Integer(u64::MAX))
+        );
+        assert_eq!(
+            Literal::int_parser().parse("01777777777777777777777"),
+            Ok(Literal::Integer(u64::MAX))
+        );
+
+        let expect_overflow = |input: &str| {
+            let errors = Literal::int_parser().parse(input).unwrap_err();
+            assert_eq!(errors[0].span(), 0..input.len());
+            assert_eq!(
+                errors[0].reason(),
+                &SimpleReason::Custom("integer literal exceeds 64 bits".to_string())
+            );
+        };
+        expect_overflow("18446744073709551616");
+        expect_overflow("123456789012345678901234567890");
+        expect_overflow("0x10000000000000000");
+        expect_overflow("02000000000000000000000");
+    }
+
     #[test]
     fn parse_int() {
         // Decimal
@@ -538,27 +987,78 @@ mod literal_tests {
 
     #[test]
     fn parse_float() {
-        assert_eq!(
-            Literal::float_parser().parse("1.1"),
-            Ok(Literal::FloatingPoint(1.1))
-        );
+        let float = |s: &str| Ok(Literal::FloatingPoint(FloatLiteral::new(s).unwrap()));
+
+        assert_eq!(Literal::float_parser().parse("1.1"), float("1.1"));
         assert_eq!(
             Literal::float_parser().parse("19234.12534"),
-            Ok(Literal::FloatingPoint(19234.12534))
-        );
-        assert_eq!(
-            Literal::float_parser().parse("0."),
-            Ok(Literal::FloatingPoint(0.0))
-        );
-        assert_eq!(
-            Literal::float_parser().parse(".0"),
-            Ok(Literal::FloatingPoint(0.0))
+            float("19234.12534")
         );
+        assert_eq!(Literal::float_parser().parse("0."), float("0."));
+        assert_eq!(Literal::float_parser().parse(".0"), float(".0"));
+        assert_eq!(Literal::float_parser().parse("0.0"), float("0.0"));
+        assert!(Literal::float_parser().parse(".").is_err());
+
+        // Exponents
+        assert_eq!(Literal::float_parser().parse("1e10"), float("1e10"));
+        assert_eq!(Literal::float_parser().parse("2.5E-3"), float("2.5E-3"));
+        assert_eq!(Literal::float_parser().parse(".5e+2"), float(".5e+2"));
+        assert_eq!(Literal::float_parser().parse("1.e2"), float("1.e2"));
+        assert!(Literal::float_parser().parse("1").is_err());
+        assert!(Literal::float_parser().parse("1e").is_err());
+        assert!(Literal::float_parser().parse(".e1").is_err());
+    }
+
+    #[test]
+    fn float_precision() {
+        let parse = |s: &str| match Literal::float_parser().parse(s) {
+            Ok(Literal::FloatingPoint(f)) => f,
+            other => panic!("unexpected result {:?}", other),
+        };
+
+        assert_eq!(parse("2.5E-3").to_f64(), Some(2.5e-3));
+        assert_eq!(parse(".5e+2").to_f64(), Some(50.0));
+        assert_eq!(parse("2.5E-3").lexeme(), "2.5E-3");
+
+        assert_eq!(parse("0.0").precision(), FloatPrecision::Float);
+        assert_eq!(parse("3.4e38").precision(), FloatPrecision::Float);
+        assert_eq!(parse("3.5e38").precision(), FloatPrecision::Double);
+        assert_eq!(parse("1e-50").precision(), FloatPrecision::Double);
+        assert_eq!(parse("1e-40").precision(), FloatPrecision::Float);
+        assert_eq!(parse("1e-46").precision(), FloatPrecision::Double);
+        assert_eq!(parse("1e-310").precision(), FloatPrecision::Double);
+        assert_eq!(parse("1e-310").to_f64(), Some(1e-310));
+        assert_eq!(parse("1e-324").precision(), FloatPrecision::LongDouble);
+        assert_eq!(parse("1e308").precision(), FloatPrecision::Double);
+        assert_eq!(parse("1e400").precision(), FloatPrecision::LongDouble);
+        assert_eq!(parse("1e-400").precision(), FloatPrecision::LongDouble);
+        assert_eq!(parse("1.1e4932").precision(), FloatPrecision::LongDouble);
+
+        assert!(parse("1e400").fits(FloatPrecision::LongDouble));
+        assert!(!parse("1e400").fits(FloatPrecision::Double));
+        assert_eq!(parse("1e400").to_f64(), None);
+
+        let errors = Literal::float_parser().parse("1.2e4932").unwrap_err();
+        assert_eq!(errors[0].span(), 0..8);
         assert_eq!(
-            Literal::float_parser().parse("0.0"),
-            Ok(Literal::FloatingPoint(0.0))
+            errors[0].reason(),
+            &SimpleReason::Custom(
+                "floating point literal `1.2e4932` exceeds the range of long double".to_string()
+            )
         );
-        assert!(Literal::float_parser().parse(".").is_err());
+        assert!(Literal::float_parser().parse("1e-5000").is_err());
+        assert!(Literal::float_parser()
+            .parse("1e99999999999999999999")
+            .is_err());
+        assert!(Literal::float_parser()
+            .parse("1e9223372036854775807")
+            .is_err());
+        assert!(Literal::float_parser()
+            .parse("0.0000001e-9223372036854775808")
+            .is_err());
+        assert!(Specification::parser()
+            .parse("const double D = 1e9223372036854775807;")
+            .is_err());
     }
 
     #[test]
@@ -575,6 +1075,118 @@ mod literal_tests {
             Literal::char_parser().parse("'a'"),
             Ok(Literal::Character('a'))
         );
+        assert_eq!(
+            Literal::char_parser().parse("'é'"),
+            Ok(Literal::Character('é'))
+        );
+
+        let expect_error = |input: &str, span: std::ops::Range<usize>, msg: &str| {
+            let errors = Literal::char_parser().parse(input).unwrap_err();
+            assert_eq!(errors[0].span(), span, "for input {}", input);
+            assert_eq!(
+                errors[0].reason(),
+                &SimpleReason::Custom(msg.to_string()),
+                "for input {}",
+                input
+            );
+        };
+        expect_error("'€'", 1..2, "`€` is out of range for a char");
+        expect_error("''", 0..2, "character literal is empty");
+        expect_error(
+            "'ab'",
+            0..4,
+            "character literal contains more than one character",
+        );
+    }
+
+    #[test]
+    fn parse_char_escapes() {
+        let validate_escape = |escape: &str, c: char| {
+            assert_eq!(
+                Literal::char_parser().parse(format!("'{}'", escape).as_str()),
+                Ok(Literal::Character(c))
+            );
+        };
+        validate_escape("\\n", '\n');
+        validate_escape("\\t", '\t');
+        validate_escape("\\v", '\x0B');
+        validate_escape("\\b", '\x08');
+        validate_escape("\\r", '\r');
+        validate_escape("\\f", '\x0C');
+        validate_escape("\\a", '\x07');
+        validate_escape("\\\\", '\\');
+        validate_escape("\\?", '?');
+        validate_escape("\\'", '\'');
+        validate_escape("\\\"", '"');
+        validate_escape("\\0", '\0');
+        validate_escape("\\101", 'A');
+        validate_escape("\\377", '\u{FF}');
+        validate_escape("\\x41", 'A');
+        validate_escape("\\xa", '\n');
+    }
+
+    #[test]
+    fn parse_char_escape_errors() {
+        let expect_error = |input: &str, span: std::ops::Range<usize>, msg: &str| {
+            let errors = Literal::char_parser().parse(input).unwrap_err();
+            assert_eq!(errors[0].span(), span);
+            assert_eq!(
+                errors[0].reason(),
+                &SimpleReason::Custom(msg.to_string()),
+                "for input {}",
+                input
+            );
+        };
+        expect_error("'\\q'", 1..3, "unknown escape sequence `\\q`");
+        expect_error(
+            "'\\777'",
+            1..5,
+            "octal escape sequence `\\777` is out of range for a char",
+        );
+        expect_error(
+            "'\\x'",
+            1..3,
+            "`\\x` escape sequence has no hexadecimal digits",
+        );
+        expect_error(
+            "'\\u0041'",
+            1..7,
+            "`\\u` escape sequences are only valid in wide character and wide string literals",
+        );
+
+        // Octal and hexadecimal escapes stop after three and two digits respectively
+        expect_error(
+            "'\\1011'",
+            0..7,
+            "character literal contains more than one character",
+        );
+        expect_error(
+            "'\\x100'",
+            0..7,
+            "character literal contains more than one character",
+        );
+    }
+
+    #[test]
+    fn parse_wide_char() {
+        assert_eq!(
+            Literal::wide_char_parser().parse("L'A'"),
+            Ok(Literal::WideCharacter('A'))
+        );
+        assert_eq!(
+            Literal::wide_char_parser().parse("L'\\n'"),
+            Ok(Literal::WideCharacter('\n'))
+        );
+        assert_eq!(
+            Literal::wide_char_parser().parse("L'\\u20AC'"),
+            Ok(Literal::WideCharacter('€'))
+        );
+        assert_eq!(
+            Literal::wide_char_parser().parse("L'\\777'"),
+            Ok(Literal::WideCharacter('\u{1FF}'))
+        );
+        assert!(Literal::wide_char_parser().parse("'A'").is_err());
+        assert!(Literal::wide_char_parser().parse("L'\\uD800'").is_err());
     }
 
     #[test]
@@ -595,25 +1207,111 @@ mod literal_tests {
             Literal::string_parser().parse("\"Hello\" \"World\""),
             Ok(Literal::Str("HelloWorld".to_string()))
         );
+        assert_eq!(
+            Literal::string_parser().parse("\"Hello\" // greeting\n /* and */ \"World\""),
+            Ok(Literal::Str("HelloWorld".to_string()))
+        );
+
+        // Trailing whitespace is left for the next token
+        assert_eq!(
+            Literal::string_parser()
+                .then(just(" ;"))
+                .parse("\"Hello\" ;")
+                .map(|(s, _)| s),
+            Ok(Literal::Str("Hello".to_string()))
+        );
+
+        // Test escape sequences
+        assert_eq!(
+            Literal::string_parser().parse("\"\\\"Hello\\\"\\tWorld\\n\""),
+            Ok(Literal::Str("\"Hello\"\tWorld\n".to_string()))
+        );
+
+        // Characters in concatenated strings are kept distinct
+        assert_eq!(
+            Literal::string_parser().parse("\"\\xA\" \"B\""),
+            Ok(Literal::Str("\nB".to_string()))
+        );
+        assert_eq!(
+            Literal::string_parser().parse("\"\\xAB\""),
+            Ok(Literal::Str("\u{AB}".to_string()))
+        );
+
+        // Non-wide strings are limited to 8-bit characters
+        assert_eq!(
+            Literal::string_parser().parse("\"café\""),
+            Ok(Literal::Str("café".to_string()))
+        );
+        let errors = Literal::string_parser().parse("\"a€b\"").unwrap_err();
+        assert_eq!(errors[0].span(), 2..3);
+        assert_eq!(
+            errors[0].reason(),
+            &SimpleReason::Custom("`€` is out of range for a char".to_string())
+        );
+
+        // A string literal shall not contain the character '\0'
+        let errors = Literal::string_parser().parse("\"ab\\0c\"").unwrap_err();
+        assert_eq!(errors[0].span(), 3..5);
+        assert_eq!(
+            errors[0].reason(),
+            &SimpleReason::Custom(
+                "a string literal shall not contain the character '\\0'".to_string()
+            )
+        );
     }
 
     #[test]
-    fn parse_fixed() {
+    fn parse_wide_string() {
         assert_eq!(
-            Literal::fixed_parser().parse("3.6D"),
-            Ok(Literal::FixedPoint(3, 6))
+            Literal::wide_string_parser().parse("L\"Hello\""),
+            Ok(Literal::WideStr("Hello".to_string()))
         );
         assert_eq!(
-            Literal::fixed_parser().parse("1.2d"),
-            Ok(Literal::FixedPoint(1, 2))
+            Literal::wide_string_parser().parse("L\"Hello\" L\"\\u20AC\""),
+            Ok(Literal::WideStr("Hello€".to_string()))
         );
+        assert!(Literal::wide_string_parser().parse("\"Hello\"").is_err());
+
+        // A wide string literal shall not contain the wide character with value zero
+        let errors = Literal::wide_string_parser()
+            .parse("L\"a\\u0\"")
+            .unwrap_err();
+        assert_eq!(errors[0].span(), 3..6);
         assert_eq!(
-            Literal::fixed_parser().parse(".3d"),
-            Ok(Literal::FixedPoint(0, 3))
+            errors[0].reason(),
+            &SimpleReason::Custom(
+                "a wide string literal shall not contain the wide character with value zero"
+                    .to_string()
+            )
         );
+    }
+
+    #[test]
+    fn parse_fixed() {
+        let fixed = |value, scale| Ok(Literal::FixedPoint(Fixed::new(value, scale).unwrap()));
+
+        assert_eq!(Literal::fixed_parser().parse("3.6D"), fixed(36, 1));
+        assert_eq!(Literal::fixed_parser().parse("1.2d"), fixed(12, 1));
+        assert_eq!(Literal::fixed_parser().parse(".3d"), fixed(3, 1));
+        assert_eq!(Literal::fixed_parser().parse("3d"), fixed(3, 0));
+
+        // Leading zeros of the fraction are significant
+        assert_eq!(Literal::fixed_parser().parse("1.05d"), fixed(105, 2));
+        assert_eq!(Literal::fixed_parser().parse("1.5d"), fixed(15, 1));
+        assert_eq!(Literal::fixed_parser().parse("3.d"), fixed(3, 0));
+
+        // Up to 31 digits may be given
         assert_eq!(
-            Literal::fixed_parser().parse("3d"),
-            Ok(Literal::FixedPoint(3, 0))
+            Literal::fixed_parser().parse("123456789012345678901.2345678901d"),
+            fixed(1234567890123456789012345678901, 10)
+        );
+        let errors = Literal::fixed_parser()
+            .parse("1234567890123456789012345678901234567890d")
+            .unwrap_err();
+        assert_eq!(errors[0].span(), 0..41);
+        assert_eq!(
+            errors[0].reason(),
+            &SimpleReason::Custom("fixed point literal exceeds 31 digits".to_string())
         );
     }
 
@@ -626,8 +1324,23 @@ mod literal_tests {
             Ok(Literal::Str("String".to_string()))
         );
         assert_eq!(p.parse("'c'"), Ok(Literal::Character('c')));
-        assert_eq!(p.parse("2.1"), Ok(Literal::FloatingPoint(2.1)));
-        assert_eq!(p.parse("2.1d"), Ok(Literal::FixedPoint(2, 1)));
+        assert_eq!(p.parse("L'c'"), Ok(Literal::WideCharacter('c')));
+        assert_eq!(
+            p.parse("L\"String\""),
+            Ok(Literal::WideStr("String".to_string()))
+        );
+        assert_eq!(
+            p.parse("2.1"),
+            Ok(Literal::FloatingPoint(FloatLiteral::new("2.1").unwrap()))
+        );
+        assert_eq!(
+            p.parse("2e1"),
+            Ok(Literal::FloatingPoint(FloatLiteral::new("2e1").unwrap()))
+        );
+        assert_eq!(
+            p.parse("2.1d"),
+            Ok(Literal::FixedPoint(Fixed::new(21, 1).unwrap()))
+        );
         assert_eq!(p.parse("TRUE"), Ok(Literal::Bool(true)));
         assert_eq!(p.parse("3"), Ok(Literal::Integer(3)));
     }
@@ -0,0 +1,430 @@
+/**********************************************************************************
+ * Copyright © 2022 Michael Volling
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy of
+ * this software and associated documentation files (the “Software”), to deal in
+ * the Software without restriction, including without limitation the rights to
+ * use, copy, modify, merge, publish, distribute, sublicense, and/or sell copies of
+ * the Software, and to permit persons to whom the Software is furnished to do so,
+ * subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED “AS IS”, WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY, FITNESS
+ * FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE AUTHORS OR
+ * COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER
+ * IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN
+ * CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+ *********************************************************************************/
+
+use chumsky::prelude::*;
+
+use std::fmt::Display;
+use std::ops::Range;
+
+use crate::keyword::Keyword;
+use crate::lexer::{padding, symbol};
+
+/// The Identifier type represents the name given to an IDL entity where it is
+/// declared, with any escaping underscore removed
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Identifier(String);
+
+impl Identifier {
+    /// Creates an identifier from a word as it is written in IDL, applying the
+    /// keyword collision and escaping rules
+    ///
+    /// Returns a description of the problem if the word is not a legal identifier.
+    ///
+    /// Example
+    ///
+    /// ```
+    /// use ox_idl::name::Identifier;
+    ///
+    /// assert_eq!(Identifier::new("Point").unwrap().as_str(), "Point");
+    /// assert_eq!(Identifier::new("_module").unwrap().as_str(), "module");
+    /// assert_eq!(
+    ///     Identifier::new("Struct"),
+    ///     Err("`Struct` collides with keyword `struct`".to_string())
+    /// );
+    /// ```
+    pub fn new(word: &str) -> Result<Identifier, String> {
+        // 7.2.3
+        // An identifier is an arbitrarily long sequence of ASCII alphabetic, digit,
+        // and underscore (_) characters. The first character must be an ASCII
+        // alphabetic character.
+        //
+        // 7.2.3.1
+        // Users may lexically "escape" identifiers by prepending an underscore (_)
+        // to an identifier. This is a purely lexical convention that ONLY turns off
+        // keyword checking. The resulting identifier follows all the other rules for
+        // identifier processing.
+        let (escaped, name) = match word.strip_prefix('_') {
+            Some(name) => (true, name),
+            None => (false, word),
+        };
+
+        if !name.starts_with(|c: char| c.is_ascii_alphabetic())
+            || !name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+        {
+            return Err(format!(
+                "`{}` is not a valid identifier, identifiers must start with an alphabetic character",
+                word
+            ));
+        }
+
+        // 7.2.4
+        // Identifiers that collide with keywords (see 7.2.3, Identifiers) are illegal.
+        match Keyword::collision(name) {
+            Some(keyword) if !escaped && keyword.to_string() == name => Err(format!(
+                "`{}` is a keyword, it may be used as an identifier by escaping it as `_{}`",
+                name, name
+            )),
+            Some(keyword) if !escaped => Err(keyword.collision_message(name)),
+            _ => Ok(Identifier(name.to_string())),
+        }
+    }
+
+    /// Builds a parser is able to parse an identifier as specified in the IDL
+    /// Documentation
+    ///
+    /// Example
+    ///
+    /// ```
+    /// use ox_idl::name::Identifier;
+    /// use chumsky::prelude::*;
+    ///
+    /// let parser = Identifier::parser();
+    ///
+    /// assert_eq!(parser.parse("_interface").unwrap().as_str(), "interface");
+    /// assert!(parser.parse("Interface").is_err());
+    /// ```
+    pub fn parser() -> impl Parser<char, Identifier, Error = Simple<char>> + Clone {
+        text::ident().try_map(|word: String, span| {
+            Identifier::new(&word).map_err(|message| Simple::custom(span, message))
+        })
+    }
+
+    /// Returns the identifier as a string
+    pub fn as_str(&self) -> &str {
+        self.0.as_str()
+    }
+
+    /// Returns whether two identifiers collide, which is the case when they differ
+    /// only in case
+    pub fn collides_with(&self, other: &Identifier) -> bool {
+        // 7.2.3
+        // IDL identifiers are case insensitive. However, all references to a
+        // definition must use the same case as the defining occurrence.
+        self.0.eq_ignore_ascii_case(&other.0)
+    }
+
+    /// Describes this identifier colliding with one declared earlier in the same
+    /// scope, for use in diagnostics
+    pub fn collision_message(&self, earlier: &Identifier) -> String {
+        if self == earlier {
+            format!("`{}` is already declared in this scope", self)
+        } else {
+            format!(
+                "`{}` collides with `{}` declared in the same scope",
+                self, earlier
+            )
+        }
+    }
+
+    /// Checks the identifiers declared in a single scope, reporting every
+    /// identifier that collides with one declared before it
+    ///
+    /// Example
+    ///
+    /// ```
+    /// use ox_idl::name::Identifier;
+    ///
+    /// let a = Identifier::new("Value").unwrap();
+    /// let b = Identifier::new("VALUE").unwrap();
+    ///
+    /// let errors = Identifier::check_scope([(&a, 0..5), (&b, 10..15)]).unwrap_err();
+    /// assert_eq!(errors.len(), 1);
+    /// assert_eq!(errors[0].span(), 10..15);
+    /// ```
+    pub fn check_scope<'a>(
+        declarations: impl IntoIterator<Item = (&'a Identifier, Range<usize>)>,
+    ) -> Result<(), Vec<Simple<char>>> {
+        // 7.2.3
+        // When comparing two identifiers to see if they collide:
+        // - Upper- and lower-case letters are treated as the same letter.
+        // - All characters are significant.
+        let mut declared: Vec<&Identifier> = Vec::new();
+        let mut errors = Vec::new();
+        for (ident, span) in declarations {
+            match declared.iter().find(|d| d.collides_with(ident)) {
+                Some(d) => errors.push(Simple::custom(span, ident.collision_message(d))),
+                None => declared.push(ident),
+            }
+        }
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
+        }
+    }
+}
+
+impl Display for Identifier {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl From<Identifier> for String {
+    fn from(ident: Identifier) -> Self {
+        ident.0
+    }
+}
+
+/// The ScopedName type represents a possibly qualified reference to a named
+/// IDL entity, such as `Inner::Name` or `::Outer::Inner::Name`
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ScopedName {
+    /// Whether the name starts with `::`, which resolves it from the global scope
+    pub absolute: bool,
+    /// The identifiers making up the name, outermost first
+    pub parts: Vec<String>,
+}
+
+impl ScopedName {
+    /// Builds a parser is able to parse a scoped name as specified in the IDL
+    /// Documentation
+    ///
+    /// Example
+    ///
+    /// ```
+    /// use ox_idl::name::ScopedName;
+    /// use chumsky::prelude::*;
+    ///
+    /// let parser = ScopedName::parser();
+    ///
+    /// let n = parser.parse("::Outer::Name");
+    /// assert_eq!(n, Ok(ScopedName::from("::Outer::Name")));
+    /// assert_eq!(n.unwrap().parts, vec!["Outer".to_string(), "Name".to_string()]);
+    /// ```
+    pub fn parser() -> impl Parser<char, ScopedName, Error = Simple<char>> {
+        // 7.4.1.3
+        // <scoped_name> ::= <identifier>
+        //                 | "::" <identifier>
+        //                 | <scoped_name> "::" <identifier>
+        symbol("::")
+            .or_not()
+            .then(
+                text::ident()
+                    .map_with_span(|word: String, span| (word, span))
+                    .separated_by(Self::separator())
+                    .at_least(1),
+            )
+            // Each part is checked once the whole name is read, so that a bad part
+            // is reported rather than ending the name early
+            .try_map(|(root, parts), _| {
+                let parts = parts
+                    .into_iter()
+                    .map(|(word, span)| {
+                        Identifier::new(&word)
+                            .map(String::from)
+                            .map_err(|message| Simple::custom(span, message))
+                    })
+                    .collect::<Result<_, _>>()?;
+                Ok(ScopedName {
+                    absolute: root.is_some(),
+                    parts,
+                })
+            })
+    }
+
+    /// Builds a parser for the `::` between two identifiers of a scoped name
+    ///
+    /// The `::` is a token of its own, so whitespace and comments may surround it.
+    /// The whitespace and comments before it are only consumed if it follows them,
+    /// so that those following the name are left to the next token.
+    pub(crate) fn separator() -> impl Parser<char, (), Error = Simple<char>> + Clone {
+        padding().then(symbol("::")).ignored().boxed()
+    }
+
+    /// Returns the name of the global scope, which contains every top level
+    /// definition of a specification
+    pub fn root() -> ScopedName {
+        ScopedName {
+            absolute: true,
+            parts: Vec::new(),
+        }
+    }
+
+    /// Returns the last identifier of the name, which is the name of the entity
+    /// itself without its enclosing scopes
+    pub fn name(&self) -> &str {
+        self.parts.last().map(String::as_str).unwrap_or_default()
+    }
+
+    /// Returns the name of an entity declared directly within the scope this name
+    /// refers to
+    pub fn child(&self, name: &Identifier) -> ScopedName {
+        let mut parts = self.parts.clone();
+        parts.push(name.to_string());
+        ScopedName {
+            absolute: self.absolute,
+            parts,
+        }
+    }
+}
+
+impl From<&str> for ScopedName {
+    /// Splits a name written as it would be in IDL into a scoped name
+    fn from(s: &str) -> Self {
+        let (absolute, rest) = match s.strip_prefix("::") {
+            Some(rest) => (true, rest),
+            None => (false, s),
+        };
+        ScopedName {
+            absolute,
+            parts: rest.split("::").map(String::from).collect(),
+        }
+    }
+}
+
+impl Display for ScopedName {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.absolute {
+            f.write_str("::")?;
+        }
+        f.write_str(self.parts.join("::").as_str())
+    }
+}
+
+#[cfg(test)]
+mod name_tests {
+    use crate::name::{Identifier, ScopedName};
+    use chumsky::error::SimpleReason;
+    use chumsky::prelude::*;
+
+    fn ident(s: &str) -> Identifier {
+        Identifier::new(s).unwrap()
+    }
+
+    #[test]
+    fn parse_identifier() {
+        assert_eq!(Identifier::parser().parse("Point"), Ok(ident("Point")));
+        assert_eq!(Identifier::parser().parse("a_1"), Ok(ident("a_1")));
+        assert_eq!(Identifier::parser().parse("_Point"), Ok(ident("Point")));
+
+        // Escaped keywords and near misses
+        assert_eq!(Identifier::parser().parse("_module"), Ok(ident("_module")));
+        assert_eq!(Identifier::parser().parse("_Struct"), Ok(ident("_Struct")));
+
+        let reason = |s: &str| {
+            Identifier::parser().parse(s).unwrap_err()[0]
+                .reason()
+                .clone()
+        };
+        assert_eq!(
+            reason("Struct"),
+            SimpleReason::Custom("`Struct` collides with keyword `struct`".to_string())
+        );
+        assert_eq!(
+            reason("True"),
+            SimpleReason::Custom("`True` collides with keyword `TRUE`".to_string())
+        );
+        assert_eq!(
+            reason("module"),
+            SimpleReason::Custom(
+                "`module` is a keyword, it may be used as an identifier by escaping it as `_module`"
+                    .to_string()
+            )
+        );
+        assert_eq!(
+            reason("__x"),
+            SimpleReason::Custom(
+                "`__x` is not a valid identifier, identifiers must start with an alphabetic character"
+                    .to_string()
+            )
+        );
+        assert!(Identifier::parser().parse("1a").is_err());
+
+        let errors = ScopedName::parser().parse("Outer::Struct").unwrap_err();
+        assert_eq!(errors[0].span(), 7..13);
+    }
+
+    #[test]
+    fn identifier_collisions() {
+        assert!(ident("Point").collides_with(&ident("POINT")));
+        assert!(ident("Point").collides_with(&ident("Point")));
+        assert!(!ident("Point").collides_with(&ident("Point2")));
+
+        let (a, b, c, d) = (ident("a"), ident("b"), ident("A"), ident("b"));
+        assert_eq!(Identifier::check_scope([(&a, 0..1), (&b, 2..3)]), Ok(()));
+
+        let errors =
+            Identifier::check_scope([(&a, 0..1), (&b, 2..3), (&c, 4..5), (&d, 6..7)]).unwrap_err();
+        assert_eq!(errors.len(), 2);
+        assert_eq!(errors[0].span(), 4..5);
+        assert_eq!(
+            errors[0].reason(),
+            &SimpleReason::Custom("`A` collides with `a` declared in the same scope".to_string())
+        );
+        assert_eq!(errors[1].span(), 6..7);
+        assert_eq!(
+            errors[1].reason(),
+            &SimpleReason::Custom("`b` is already declared in this scope".to_string())
+        );
+    }
+
+    #[test]
+    fn parse_scoped_name() {
+        assert_eq!(
+            ScopedName::parser().parse("Name"),
+            Ok(ScopedName {
+                absolute: false,
+                parts: vec!["Name".to_string()]
+            })
+        );
+        assert_eq!(
+            ScopedName::parser().parse("a::b_2::C"),
+            Ok(ScopedName {
+                absolute: false,
+                parts: vec!["a".to_string(), "b_2".to_string(), "C".to_string()]
+            })
+        );
+        assert_eq!(
+            ScopedName::parser().parse("::a"),
+            Ok(ScopedName {
+                absolute: true,
+                parts: vec!["a".to_string()]
+            })
+        );
+        assert_eq!(
+            ScopedName::parser().parse(":: a /* outer */ :: b\n::c"),
+            Ok(ScopedName::from("::a::b::c"))
+        );
+        assert!(ScopedName::parser().parse("::").is_err());
+        assert!(ScopedName::parser().parse("1a").is_err());
+
+        // The padding after a name is not consumed unless another part follows
+        let parser = ScopedName::parser().map_with_span(|_, span| span);
+        assert_eq!(parser.then_ignore(just(" ;")).parse("a::b ;"), Ok(0..4));
+        assert!(ScopedName::parser()
+            .then_ignore(end())
+            .parse("a :: ")
+            .is_err());
+    }
+
+    #[test]
+    fn display() {
+        assert_eq!(ScopedName::from("a::b").to_string(), "a::b");
+        assert_eq!(ScopedName::from("::a::b").to_string(), "::a::b");
+        assert_eq!(ScopedName::from("::a::b").name(), "b");
+        assert_eq!(
+            ScopedName::from("::a").child(&Identifier::new("b").unwrap()),
+            ScopedName::from("::a::b")
+        );
+        assert_eq!(ScopedName::root().to_string(), "::");
+    }
+}
@@ -0,0 +1,361 @@
+/**********************************************************************************
+ * Copyright © 2022 Michael Volling
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy of
+ * this software and associated documentation files (the “Software”), to deal in
+ * the Software without restriction, including without limitation the rights to
+ * use, copy, modify, merge, publish, distribute, sublicense, and/or sell copies of
+ * the Software, and to permit persons to whom the Software is furnished to do so,
+ * subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED “AS IS”, WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY, FITNESS
+ * FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE AUTHORS OR
+ * COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER
+ * IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN
+ * CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+ *********************************************************************************/
+
+use chumsky::prelude::*;
+
+use std::fmt::Display;
+
+use crate::keyword::Keyword;
+use crate::lexer::padding;
+use crate::literal::FloatPrecision;
+
+/// The PrimitiveType enum lists the basic types of the IDL language
+/// that constants may be declared with
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum PrimitiveType {
+    Short,
+    UnsignedShort,
+    Long,
+    UnsignedLong,
+    LongLong,
+    UnsignedLongLong,
+    Int8,
+    UInt8,
+    Float,
+    Double,
+    LongDouble,
+    Char,
+    WChar,
+    Boolean,
+    Octet,
+}
+
+impl PrimitiveType {
+    /// Builds a parser is able to parse a primitive type as specified in the IDL
+    /// Documentation
+    ///
+    /// Types spelled with several keywords may have any whitespace or comments
+    /// between the keywords. The sized integer types of IDL4, such as `int32`, are
+    /// parsed as the classic type they are an alias of.
+    ///
+    /// Example
+    ///
+    /// ```
+    /// use ox_idl::primitive::PrimitiveType;
+    /// use chumsky::prelude::*;
+    ///
+    /// let parser = PrimitiveType::parser();
+    ///
+    /// assert_eq!(parser.parse("unsigned long long"), Ok(PrimitiveType::UnsignedLongLong));
+    /// assert_eq!(parser.parse("long double"), Ok(PrimitiveType::LongDouble));
+    /// assert_eq!(parser.parse("octet"), Ok(PrimitiveType::Octet));
+    /// assert_eq!(parser.parse("uint16"), Ok(PrimitiveType::UnsignedShort));
+    /// assert!(parser.parse("unsigned double").is_err());
+    /// ```
+    pub fn parser() -> impl Parser<char, PrimitiveType, Error = Simple<char>> + Clone {
+        // 7.4.1.4.4.2
+        // <signed_int> ::= <signed_short_int> | <signed_long_int>
+        //                | <signed_longlong_int>
+        // <signed_short_int> ::= "short"
+        // <signed_long_int> ::= "long"
+        // <signed_longlong_int> ::= "long" "long"
+        // <unsigned_int> ::= <unsigned_short_int> | <unsigned_long_int>
+        //                  | <unsigned_longlong_int>
+        // <unsigned_short_int> ::= "unsigned" "short"
+        // <unsigned_long_int> ::= "unsigned" "long"
+        // <unsigned_longlong_int> ::= "unsigned" "long" "long"
+        // <floating_pt_type> ::= "float" | "double" | "long" "double"
+        // <char_type> ::= "char"
+        // <wide_char_type> ::= "wchar"
+        // <boolean_type> ::= "boolean"
+        // <octet_type> ::= "octet"
+        //
+        // 7.4.13.4.4
+        // <signed_tiny_int> ::= "int8"
+        // <unsigned_tiny_int> ::= "uint8"
+        // <signed_short_int> ::= "int16"
+        // <signed_long_int> ::= "int32"
+        // <signed_longlong_int> ::= "int64"
+        // <unsigned_short_int> ::= "uint16"
+        // <unsigned_long_int> ::= "uint32"
+        // <unsigned_longlong_int> ::= "uint64"
+        let next = |keyword: Keyword| padding().ignore_then(keyword.make_parser());
+
+        let long = Keyword::Long.make_parser().ignore_then(
+            choice((
+                next(Keyword::Long).to(PrimitiveType::LongLong),
+                next(Keyword::Double).to(PrimitiveType::LongDouble),
+            ))
+            .or_not()
+            .map(|ty| ty.unwrap_or(PrimitiveType::Long)),
+        );
+
+        let classic = choice((
+            long,
+            Keyword::Short.make_parser().to(PrimitiveType::Short),
+            Keyword::Float.make_parser().to(PrimitiveType::Float),
+            Keyword::Double.make_parser().to(PrimitiveType::Double),
+            Keyword::Char.make_parser().to(PrimitiveType::Char),
+            Keyword::WChar.make_parser().to(PrimitiveType::WChar),
+            Keyword::Boolean.make_parser().to(PrimitiveType::Boolean),
+            Keyword::Octet.make_parser().to(PrimitiveType::Octet),
+        ));
+
+        let sized = choice((
+            Keyword::Int8.make_parser(),
+            Keyword::Int16.make_parser(),
+            Keyword::Int32.make_parser(),
+            Keyword::Int64.make_parser(),
+            Keyword::UInt8.make_parser(),
+            Keyword::UInt16.make_parser(),
+            Keyword::UInt32.make_parser(),
+            Keyword::UInt64.make_parser(),
+        ));
+
+        // Other than int8 and uint8, the sized integer types are aliases of the
+        // classic integer types
+        let aliases = sized.clone().map(|keyword| match keyword {
+            Keyword::Int8 => PrimitiveType::Int8,
+            Keyword::Int16 => PrimitiveType::Short,
+            Keyword::Int32 => PrimitiveType::Long,
+            Keyword::Int64 => PrimitiveType::LongLong,
+            Keyword::UInt8 => PrimitiveType::UInt8,
+            Keyword::UInt16 => PrimitiveType::UnsignedShort,
+            Keyword::UInt32 => PrimitiveType::UnsignedLong,
+            _ => PrimitiveType::UnsignedLongLong,
+        });
+
+        // Any type may follow `unsigned` so that a type without an unsigned form
+        // is reported as such, rather than as an unexpected keyword
+        let unsigned = Keyword::Unsigned
+            .make_parser()
+            .ignore_then(padding())
+            .ignore_then(
+                classic
+                    .clone()
+                    .map(|ty| (Some(ty), ty.to_string()))
+                    .or(sized.map(|keyword| (None, keyword.to_string()))),
+            )
+            .try_map(|(ty, spelling), span| match ty {
+                Some(PrimitiveType::Short) => Ok(PrimitiveType::UnsignedShort),
+                Some(PrimitiveType::Long) => Ok(PrimitiveType::UnsignedLong),
+                Some(PrimitiveType::LongLong) => Ok(PrimitiveType::UnsignedLongLong),
+                _ => Err(Simple::custom(
+                    span,
+                    format!(
+                        "`unsigned {}` is not a type, only short, long and long long may be unsigned",
+                        spelling
+                    ),
+                )),
+            });
+
+        choice((unsigned, classic, aliases))
+    }
+
+    /// Returns the inclusive range of values of an integer type, or None if
+    /// the type is not an integer type
+    ///
+    /// Octets are treated as 8 bit unsigned integers.
+    ///
+    /// Example
+    ///
+    /// ```
+    /// use ox_idl::primitive::PrimitiveType;
+    ///
+    /// assert_eq!(PrimitiveType::Short.integer_range(), Some((-32768, 32767)));
+    /// assert_eq!(PrimitiveType::Float.integer_range(), None);
+    /// ```
+    pub fn integer_range(&self) -> Option<(i128, i128)> {
+        let range = match self {
+            PrimitiveType::Short => (i16::MIN as i128, i16::MAX as i128),
+            PrimitiveType::UnsignedShort => (0, u16::MAX as i128),
+            PrimitiveType::Long => (i32::MIN as i128, i32::MAX as i128),
+            PrimitiveType::UnsignedLong => (0, u32::MAX as i128),
+            PrimitiveType::LongLong => (i64::MIN as i128, i64::MAX as i128),
+            PrimitiveType::UnsignedLongLong => (0, u64::MAX as i128),
+            PrimitiveType::Int8 => (i8::MIN as i128, i8::MAX as i128),
+            PrimitiveType::UInt8 | PrimitiveType::Octet => (0, u8::MAX as i128),
+            _ => return None,
+        };
+        Some(range)
+    }
+
+    /// Returns the number of bits of an integer type, or None if the type is
+    /// not an integer type
+    pub fn integer_bits(&self) -> Option<u32> {
+        self.integer_range()
+            .map(|(min, max)| (max - min).count_ones())
+    }
+
+    /// Returns true if the type is a signed integer type
+    pub fn is_signed_integer(&self) -> bool {
+        matches!(self.integer_range(), Some((min, _)) if min < 0)
+    }
+
+    /// Returns the precision of a floating point type, or None if the type is
+    /// not a floating point type
+    pub fn float_precision(&self) -> Option<FloatPrecision> {
+        match self {
+            PrimitiveType::Float => Some(FloatPrecision::Float),
+            PrimitiveType::Double => Some(FloatPrecision::Double),
+            PrimitiveType::LongDouble => Some(FloatPrecision::LongDouble),
+            _ => None,
+        }
+    }
+}
+
+impl Display for PrimitiveType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        // The type as it is spelled in IDL
+        f.write_str(match self {
+            PrimitiveType::Short => "short",
+            PrimitiveType::UnsignedShort => "unsigned short",
+            PrimitiveType::Long => "long",
+            PrimitiveType::UnsignedLong => "unsigned long",
+            PrimitiveType::LongLong => "long long",
+            PrimitiveType::UnsignedLongLong => "unsigned long long",
+            PrimitiveType::Int8 => "int8",
+            PrimitiveType::UInt8 => "uint8",
+            PrimitiveType::Float => "float",
+            PrimitiveType::Double => "double",
+            PrimitiveType::LongDouble => "long double",
+            PrimitiveType::Char => "char",
+            PrimitiveType::WChar => "wchar",
+            PrimitiveType::Boolean => "boolean",
+            PrimitiveType::Octet => "octet",
+        })
+    }
+}
+
+#[cfg(test)]
+mod primitive_tests {
+    use crate::literal::FloatPrecision;
+    use crate::primitive::PrimitiveType;
+    use chumsky::error::SimpleReason;
+    use chumsky::prelude::*;
+
+    #[test]
+    fn parse_primitive() {
+        let parse = |s: &str| PrimitiveType::parser().then_ignore(end()).parse(s);
+        assert_eq!(parse("short"), Ok(PrimitiveType::Short));
+        assert_eq!(parse("unsigned short"), Ok(PrimitiveType::UnsignedShort));
+        assert_eq!(parse("long"), Ok(PrimitiveType::Long));
+        assert_eq!(parse("unsigned long"), Ok(PrimitiveType::UnsignedLong));
+        assert_eq!(parse("long  long"), Ok(PrimitiveType::LongLong));
+        assert_eq!(
+            parse("unsigned /* comment */ long\nlong"),
+            Ok(PrimitiveType::UnsignedLongLong)
+        );
+        assert_eq!(parse("long double"), Ok(PrimitiveType::LongDouble));
+        assert_eq!(parse("wchar"), Ok(PrimitiveType::WChar));
+        assert_eq!(parse("boolean"), Ok(PrimitiveType::Boolean));
+        assert!(parse("longlong").is_err());
+        assert!(parse("unsigned").is_err());
+
+        assert_eq!(parse("int8"), Ok(PrimitiveType::Int8));
+        assert_eq!(parse("uint8"), Ok(PrimitiveType::UInt8));
+        assert_eq!(parse("int16"), Ok(PrimitiveType::Short));
+        assert_eq!(parse("int32"), Ok(PrimitiveType::Long));
+        assert_eq!(parse("int64"), Ok(PrimitiveType::LongLong));
+        assert_eq!(parse("uint32"), Ok(PrimitiveType::UnsignedLong));
+        assert_eq!(parse("uint64"), Ok(PrimitiveType::UnsignedLongLong));
+        assert!(parse("Int32").is_err());
+    }
+
+    #[test]
+    fn parse_invalid_unsigned() {
+        let error = |s: &str| {
+            let errors = PrimitiveType::parser()
+                .then_ignore(end())
+                .parse(s)
+                .unwrap_err();
+            assert_eq!(errors.len(), 1);
+            match errors[0].reason() {
+                SimpleReason::Custom(message) => (message.clone(), errors[0].span()),
+                reason => panic!("unexpected error {:?}", reason),
+            }
+        };
+        assert_eq!(
+            error("unsigned double"),
+            (
+                "`unsigned double` is not a type, only short, long and long long may be unsigned"
+                    .to_string(),
+                0..15
+            )
+        );
+        assert_eq!(
+            error("unsigned  long double").0,
+            "`unsigned long double` is not a type, only short, long and long long may be unsigned"
+        );
+        assert_eq!(
+            error("unsigned int32").0,
+            "`unsigned int32` is not a type, only short, long and long long may be unsigned"
+        );
+        assert_eq!(
+            error("unsigned octet").0,
+            "`unsigned octet` is not a type, only short, long and long long may be unsigned"
+        );
+
+        // Trailing whitespace is left for the next token
+        assert_eq!(
+            PrimitiveType::parser().then(just(" x")).parse("long x"),
+            Ok((PrimitiveType::Long, " x"))
+        );
+    }
+
+    #[test]
+    fn display() {
+        assert_eq!(
+            PrimitiveType::UnsignedLongLong.to_string(),
+            "unsigned long long"
+        );
+        assert_eq!(PrimitiveType::LongDouble.to_string(), "long double");
+        assert_eq!(PrimitiveType::WChar.to_string(), "wchar");
+    }
+
+    #[test]
+    fn integer_range() {
+        assert_eq!(PrimitiveType::Int8.integer_range(), Some((-128, 127)));
+        assert_eq!(PrimitiveType::Octet.integer_range(), Some((0, 255)));
+        assert_eq!(
+            PrimitiveType::UnsignedLongLong.integer_range(),
+            Some((0, u64::MAX as i128))
+        );
+        assert_eq!(PrimitiveType::Char.integer_range(), None);
+
+        assert_eq!(PrimitiveType::UnsignedLong.integer_bits(), Some(32));
+        assert_eq!(PrimitiveType::LongLong.integer_bits(), Some(64));
+        assert_eq!(PrimitiveType::Boolean.integer_bits(), None);
+
+        assert!(PrimitiveType::Short.is_signed_integer());
+        assert!(!PrimitiveType::UnsignedShort.is_signed_integer());
+        assert!(!PrimitiveType::Double.is_signed_integer());
+    }
+
+    #[test]
+    fn float_precision() {
+        assert_eq!(
+            PrimitiveType::Double.float_precision(),
+            Some(FloatPrecision::Double)
+        );
+        assert_eq!(PrimitiveType::Long.float_precision(), None);
+    }
+}
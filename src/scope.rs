@@ -0,0 +1,2175 @@
+/**********************************************************************************
+ * Copyright © 2022 Michael Volling
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy of
+ * this software and associated documentation files (the “Software”), to deal in
+ * the Software without restriction, including without limitation the rights to
+ * use, copy, modify, merge, publish, distribute, sublicense, and/or sell copies of
+ * the Software, and to permit persons to whom the Software is furnished to do so,
+ * subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED “AS IS”, WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY, FITNESS
+ * FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE AUTHORS OR
+ * COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER
+ * IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN
+ * CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+ *********************************************************************************/
+
+use chumsky::prelude::*;
+
+use std::collections::HashMap;
+use std::ops::Range;
+
+use crate::ast::{
+    Annotation, Bitmask, Bitset, Component, ComponentExport, Connector, Const, Definition, Enum,
+    Export, Home, HomeExport, Interface, Port, PortType, Reference, Specification, Struct,
+    TypeSpec, ValueElement, ValueType,
+};
+use crate::expr::{ConstExpr, ConstScope, ConstValue};
+use crate::keyword::Keyword;
+use crate::name::{Identifier, ScopedName};
+use crate::primitive::PrimitiveType;
+
+/// The Symbol enum represents the kind of entity a declared name refers to
+#[derive(Debug, Clone, PartialEq)]
+pub enum Symbol {
+    Module,
+    TemplateModule,
+    /// A constant, along with its value if it could be evaluated
+    Const(Option<ConstValue>),
+    /// A struct, along with the absolute name of the struct it inherits from, if
+    /// any, and the names of the members it declares itself
+    Struct(Option<ScopedName>, Vec<Identifier>),
+    Union,
+    /// An enum, along with the names of its enumerators
+    Enum(Vec<Identifier>),
+    /// An enumerator, which is declared in the scope enclosing its enum, along
+    /// with its value
+    Enumerator(ConstValue),
+    /// A bitmask, along with the position of each of its bits
+    Bitmask(BitLayout),
+    /// A bitset, along with the offset and width of each of its bitfields
+    Bitset(BitLayout),
+    Native,
+    Exception,
+    /// An alias of a type, along with the dimensions of the alias if it is an
+    /// array. The aliased type is resolved through any aliases it refers to, or
+    /// None if it does not name a type.
+    Typedef(Option<TypeSpec>, Vec<ConstExpr>),
+    /// An interface, along with its `local` or `abstract` modifier and the
+    /// absolute names of the interfaces it inherits from
+    Interface(Option<Keyword>, Vec<ScopedName>),
+    Operation,
+    /// An attribute, along with whether it is read only
+    Attribute(bool),
+    /// A value type, along with its `custom` or `abstract` modifier and the
+    /// absolute names of the value types it inherits from
+    ValueType(Option<Keyword>, Vec<ScopedName>),
+    ValueBox,
+    /// A state member of a value type, along with the `public` or `private`
+    /// keyword giving its visibility
+    StateMember(Keyword),
+    /// A factory of a value type or home
+    Factory,
+    /// An event type, along with its `custom` or `abstract` modifier and the
+    /// absolute names of the value types it inherits from
+    EventType(Option<Keyword>, Vec<ScopedName>),
+    /// A component, along with the absolute name of the component it inherits
+    /// from, if any
+    Component(Option<ScopedName>),
+    /// A port of a component, porttype or connector, along with the keyword
+    /// giving its kind
+    Port(Keyword),
+    /// A home, along with the absolute name of the home it inherits from, if any
+    Home(Option<ScopedName>),
+    Finder,
+    /// A porttype, along with the ports it declares
+    PortType(Vec<Port>),
+    /// A connector, along with the absolute name of the connector it inherits
+    /// from, if any
+    Connector(Option<ScopedName>),
+    /// A type that has been forward declared but not yet defined, with the
+    /// keyword giving the kind of the type
+    Forward(Keyword),
+}
+
+impl Symbol {
+    /// Returns a short description of the kind of the symbol, for use in diagnostics
+    pub fn kind(&self) -> String {
+        match self {
+            Symbol::Module => "module".to_string(),
+            Symbol::TemplateModule => "template module".to_string(),
+            Symbol::Const(_) => "constant".to_string(),
+            Symbol::Struct(_, _) => "struct".to_string(),
+            Symbol::Union => "union".to_string(),
+            Symbol::Enum(_) => "enum".to_string(),
+            Symbol::Enumerator(_) => "enumerator".to_string(),
+            Symbol::Bitmask(_) => "bitmask".to_string(),
+            Symbol::Bitset(_) => "bitset".to_string(),
+            Symbol::Native => "native type".to_string(),
+            Symbol::Exception => "exception".to_string(),
+            Symbol::Typedef(_, dimensions) if !dimensions.is_empty() => {
+                "typedef of an array".to_string()
+            }
+            Symbol::Typedef(_, _) => "typedef".to_string(),
+            Symbol::Interface(Some(modifier), _) => format!("{} interface", modifier),
+            Symbol::Interface(None, _) => "interface".to_string(),
+            Symbol::Operation => "operation".to_string(),
+            Symbol::Attribute(true) => "readonly attribute".to_string(),
+            Symbol::Attribute(false) => "attribute".to_string(),
+            Symbol::ValueType(Some(modifier), _) => format!("{} value type", modifier),
+            Symbol::ValueType(None, _) => "value type".to_string(),
+            Symbol::ValueBox => "boxed value type".to_string(),
+            Symbol::StateMember(visibility) => format!("{} state member", visibility),
+            Symbol::Factory => "factory".to_string(),
+            Symbol::EventType(Some(modifier), _) => format!("{} event type", modifier),
+            Symbol::EventType(None, _) => "event type".to_string(),
+            Symbol::Component(_) => "component".to_string(),
+            Symbol::Port(Keyword::Provides) => "facet".to_string(),
+            Symbol::Port(Keyword::Uses) => "receptacle".to_string(),
+            Symbol::Port(Keyword::Consumes) => "event sink".to_string(),
+            Symbol::Port(Keyword::Port) => "extended port".to_string(),
+            Symbol::Port(Keyword::MirrorPort) => "mirror port".to_string(),
+            Symbol::Port(_) => "event source".to_string(),
+            Symbol::Home(_) => "home".to_string(),
+            Symbol::Finder => "finder".to_string(),
+            Symbol::PortType(_) => "porttype".to_string(),
+            Symbol::Connector(_) => "connector".to_string(),
+            Symbol::Forward(keyword) => format!("forward declared {}", keyword),
+        }
+    }
+
+    /// Returns the kind of the symbol preceded by an article, for use in diagnostics
+    pub fn description(&self) -> String {
+        with_article(&self.kind())
+    }
+
+    /// Returns true if the symbol names a type
+    pub fn is_type(&self) -> bool {
+        !matches!(
+            self,
+            Symbol::Module
+                | Symbol::TemplateModule
+                | Symbol::Const(_)
+                | Symbol::Enumerator(_)
+                | Symbol::Exception
+                | Symbol::Operation
+                | Symbol::Attribute(_)
+                | Symbol::StateMember(_)
+                | Symbol::Factory
+                | Symbol::Port(_)
+                | Symbol::Finder
+                | Symbol::PortType(_)
+                | Symbol::Connector(_)
+        )
+    }
+
+    /// Returns the keyword that forward declares a symbol of this kind, or None if
+    /// it may not be forward declared
+    fn forward_keyword(&self) -> Option<Keyword> {
+        match self {
+            Symbol::Struct(_, _) => Some(Keyword::Struct),
+            Symbol::Union => Some(Keyword::Union),
+            Symbol::Interface(_, _) => Some(Keyword::Interface),
+            Symbol::ValueType(_, _) => Some(Keyword::ValueType),
+            Symbol::EventType(_, _) => Some(Keyword::EventType),
+            Symbol::Component(_) => Some(Keyword::Component),
+            Symbol::PortType(_) => Some(Keyword::PortType),
+            Symbol::Forward(keyword) => Some(keyword.clone()),
+            _ => None,
+        }
+    }
+}
+
+/// Returns the kind of an entity preceded by the article it takes, for use in
+/// diagnostics
+pub(crate) fn with_article(kind: &str) -> String {
+    match kind.chars().next() {
+        _ if kind.starts_with("union") => format!("a {}", kind),
+        Some('a' | 'e' | 'i' | 'o' | 'u') => format!("an {}", kind),
+        _ => format!("a {}", kind),
+    }
+}
+
+/// Lists each definition made within the scope `path`, and within the modules nested
+/// in it, along with its scope and the position in the source it is declared at
+///
+/// A definition is placed no earlier than `after`, the position of the module
+/// holding it. Only the definitions of an expanded template instance come before
+/// that, as they are written in the template module itself.
+fn source_order<'a>(
+    path: &ScopedName,
+    definitions: &'a [Definition],
+    after: usize,
+    ordered: &mut Vec<(usize, ScopedName, &'a Definition)>,
+) {
+    for definition in definitions {
+        let position = definition.span().start.max(after);
+        ordered.push((position, path.clone(), definition));
+        if let Definition::Module(module) = definition {
+            source_order(
+                &path.child(&module.name),
+                &module.definitions,
+                position,
+                ordered,
+            );
+        }
+    }
+}
+
+/// The BitLayout type describes how the members of a bitmask or bitset are laid
+/// out in bits
+#[derive(Debug, Clone, PartialEq)]
+pub struct BitLayout {
+    /// The members in order of their offset
+    pub fields: Vec<BitField>,
+    /// The total number of bits
+    pub width: u32,
+}
+
+/// The BitField type describes where a single member of a bitmask or bitset is
+/// placed
+#[derive(Debug, Clone, PartialEq)]
+pub struct BitField {
+    /// The name of the member, or None for bits that are left unused
+    pub name: Option<Identifier>,
+    /// The offset of the least significant bit of the member
+    pub offset: u32,
+    /// The number of bits of the member
+    pub width: u32,
+    /// The type the member is accessed as
+    pub ty: PrimitiveType,
+}
+
+/// The Declaration type represents a symbol along with where it was declared
+#[derive(Debug, Clone, PartialEq)]
+pub struct Declaration {
+    pub symbol: Symbol,
+    pub span: Range<usize>,
+}
+
+/// The SymbolTable type records every name declared in a specification, keyed
+/// by its absolute scoped name
+#[derive(Debug, Clone, Default)]
+pub struct SymbolTable {
+    symbols: HashMap<ScopedName, Declaration>,
+    /// The identifiers declared directly within each scope, in declaration order
+    scopes: HashMap<ScopedName, Vec<Identifier>>,
+}
+
+impl SymbolTable {
+    /// Builds the symbol table of a specification, evaluating its constants
+    ///
+    /// Definitions are declared in the order they appear in the source, so a module
+    /// that is reopened sees the same declarations whether its openings are kept
+    /// apart or merged by [`crate::ast::Module::merge`].
+    ///
+    /// Names that collide with another name in the same scope and constants that
+    /// cannot be evaluated are reported. The table is still returned in that case,
+    /// with the first declaration of a name kept and constants that could not be
+    /// evaluated left without a value.
+    ///
+    /// Example
+    ///
+    /// ```
+    /// use ox_idl::ast::Specification;
+    /// use ox_idl::expr::ConstValue;
+    /// use ox_idl::name::ScopedName;
+    /// use ox_idl::scope::{Symbol, SymbolTable};
+    /// use chumsky::prelude::*;
+    ///
+    /// let spec = Specification::parser()
+    ///     .parse("module M { const long A = 2; const long B = A * 3; };")
+    ///     .unwrap();
+    /// let (table, errors) = SymbolTable::new(&spec);
+    /// assert!(errors.is_empty());
+    /// assert_eq!(
+    ///     table.get(&ScopedName::from("::M::B")).unwrap().symbol,
+    ///     Symbol::Const(Some(ConstValue::Integer(6)))
+    /// );
+    /// ```
+    pub fn new(spec: &Specification) -> (SymbolTable, Vec<Simple<char>>) {
+        let mut table = SymbolTable::default();
+        let mut errors = Vec::new();
+        let mut ordered = Vec::new();
+        source_order(&ScopedName::root(), &spec.definitions, 0, &mut ordered);
+        // A stable sort keeps the definitions of an expanded template instance, which
+        // share the position of the instance, in the order they are written
+        ordered.sort_by_key(|(position, _, _)| *position);
+        for (_, path, definition) in ordered {
+            table.declare(&path, std::slice::from_ref(definition), &mut errors);
+        }
+        (table, errors)
+    }
+
+    /// Returns the declaration of an absolute scoped name
+    pub fn get(&self, name: &ScopedName) -> Option<&Declaration> {
+        self.symbols.get(name)
+    }
+
+    /// Resolves a name referenced from within the scope `scope`, returning the
+    /// absolute name of the declaration it refers to along with the declaration
+    ///
+    /// Relative names are looked up in the scope itself and then in each enclosing
+    /// scope in turn, ending with the global scope.
+    pub fn lookup(
+        &self,
+        scope: &ScopedName,
+        name: &ScopedName,
+    ) -> Option<(ScopedName, &Declaration)> {
+        // 7.5.2
+        // A name can be used in an unqualified form within a particular scope; it
+        // will be resolved by successively searching farther out in enclosing
+        // scopes, while taking into consideration inheritance relationships among
+        // interfaces.
+        let outermost = if name.absolute { 0 } else { scope.parts.len() };
+        (0..=outermost).rev().find_map(|depth| {
+            let scope = ScopedName {
+                absolute: true,
+                parts: scope.parts[..depth].to_vec(),
+            };
+            self.lookup_in(&scope, name)
+        })
+    }
+
+    /// Resolves a name within a single scope, and within each scope it inherits from
+    /// if the scope is an interface, value type, component, home or connector
+    fn lookup_in(
+        &self,
+        scope: &ScopedName,
+        name: &ScopedName,
+    ) -> Option<(ScopedName, &Declaration)> {
+        let candidate = ScopedName {
+            absolute: true,
+            parts: scope.parts.iter().chain(&name.parts).cloned().collect(),
+        };
+        if let Some(declaration) = self.symbols.get(&candidate) {
+            return Some((candidate, declaration));
+        }
+        match self
+            .symbols
+            .get(scope)
+            .map(|declaration| &declaration.symbol)
+        {
+            Some(
+                Symbol::Interface(_, bases)
+                | Symbol::ValueType(_, bases)
+                | Symbol::EventType(_, bases),
+            ) => bases.iter().find_map(|base| self.lookup_in(base, name)),
+            Some(Symbol::Component(base) | Symbol::Home(base) | Symbol::Connector(base)) => {
+                base.iter().find_map(|base| self.lookup_in(base, name))
+            }
+            _ => None,
+        }
+    }
+
+    /// Returns a view of the table from within the scope with the absolute name
+    /// `path`, which resolves names as they would be referenced from that scope
+    pub fn scope(&self, path: ScopedName) -> Scope<'_> {
+        Scope { table: self, path }
+    }
+
+    /// Declares each of the definitions within the scope `path`
+    fn declare(
+        &mut self,
+        path: &ScopedName,
+        definitions: &[Definition],
+        errors: &mut Vec<Simple<char>>,
+    ) {
+        for definition in definitions {
+            match definition {
+                // The definitions of a module are declared on their own, in source order
+                Definition::Module(module) => {
+                    self.insert(path, &module.name, Symbol::Module, &module.span, errors)
+                }
+                // The definitions of a template module are only declared once it is
+                // expanded, within the module of each instance
+                Definition::TemplateModule(t) => {
+                    self.insert(path, &t.name, Symbol::TemplateModule, &t.span, errors)
+                }
+                Definition::TemplateInstance(i) => {
+                    self.insert(path, &i.name, Symbol::Module, &i.span, errors)
+                }
+                Definition::Const(constant) => {
+                    let value = self
+                        .evaluate(path, constant)
+                        .map_err(|error| errors.push(error))
+                        .ok();
+                    self.insert(
+                        path,
+                        &constant.name,
+                        Symbol::Const(value),
+                        &constant.span,
+                        errors,
+                    );
+                }
+                Definition::Struct(s) => self.declare_struct(path, s, errors),
+                Definition::Union(u) => self.insert(path, &u.name, Symbol::Union, &u.span, errors),
+                Definition::Enum(e) => self.declare_enum(path, e, errors),
+                Definition::Bitmask(b) => self.declare_bitmask(path, b, errors),
+                Definition::Bitset(b) => self.declare_bitset(path, b, errors),
+                Definition::Native(n) => {
+                    self.insert(path, &n.name, Symbol::Native, &n.span, errors)
+                }
+                Definition::Forward(f) => self.insert(
+                    path,
+                    &f.name,
+                    Symbol::Forward(f.keyword.clone()),
+                    &f.span,
+                    errors,
+                ),
+                Definition::Typedef(t) => {
+                    // Problems with the aliased type are reported when the
+                    // specification is validated
+                    let ty = self.scope(path.clone()).resolve_type(&t.ty).ok();
+                    for declarator in &t.declarators {
+                        let symbol = Symbol::Typedef(ty.clone(), declarator.dimensions.clone());
+                        self.insert(path, &declarator.name, symbol, &declarator.span, errors);
+                    }
+                }
+                Definition::Interface(i) => self.declare_interface(path, i, errors),
+                Definition::Exception(e) => {
+                    self.insert(path, &e.name, Symbol::Exception, &e.span, errors)
+                }
+                Definition::ValueType(v) => self.declare_value_type(path, v, errors),
+                Definition::ValueBox(b) => {
+                    self.insert(path, &b.name, Symbol::ValueBox, &b.span, errors)
+                }
+                Definition::Component(c) => self.declare_component(path, c, errors),
+                Definition::Home(h) => self.declare_home(path, h, errors),
+                Definition::PortType(p) => self.declare_port_type(path, p, errors),
+                Definition::Connector(c) => self.declare_connector(path, c, errors),
+            }
+        }
+    }
+
+    /// Declares a struct within the scope `path`, reporting any member that collides
+    /// with a member inherited from its base
+    fn declare_struct(&mut self, path: &ScopedName, s: &Struct, errors: &mut Vec<Simple<char>>) {
+        // 7.4.13.4.1
+        // A structure may inherit from a single other structure, in which case the
+        // members of the base structure come first. The members of the derived
+        // structure shall not collide with those it inherits.
+        let base = self.base(
+            path,
+            s.base.as_ref(),
+            &Symbol::Struct(None, Vec::new()),
+            |symbol| matches!(symbol, Symbol::Struct(_, _)),
+            errors,
+        );
+
+        let names: Vec<Identifier> = s
+            .members
+            .iter()
+            .flat_map(|m| &m.declarators)
+            .map(|d| d.name.clone())
+            .collect();
+        for declarator in s.members.iter().flat_map(|m| &m.declarators) {
+            let mut ancestor = base.clone();
+            while let Some(name) = ancestor {
+                let Some(Declaration {
+                    symbol: Symbol::Struct(next, members),
+                    ..
+                }) = self.get(&name)
+                else {
+                    break;
+                };
+                if let Some(inherited) = members.iter().find(|m| m.collides_with(&declarator.name))
+                {
+                    errors.push(Simple::custom(
+                        declarator.span.clone(),
+                        format!(
+                            "`{}` collides with member `{}` inherited from `{}`",
+                            declarator.name,
+                            inherited,
+                            name.parts[name.parts.len() - 1]
+                        ),
+                    ));
+                    break;
+                }
+                ancestor = next.clone();
+            }
+        }
+
+        let symbol = Symbol::Struct(base, names);
+        self.insert(path, &s.name, symbol, &s.span, errors);
+    }
+
+    /// Declares an enum within the scope `path`, along with each of its enumerators
+    /// as constants of the enum
+    fn declare_enum(&mut self, path: &ScopedName, e: &Enum, errors: &mut Vec<Simple<char>>) {
+        let names = e.enumerators.iter().map(|e| e.name.clone()).collect();
+        self.insert(path, &e.name, Symbol::Enum(names), &e.span, errors);
+
+        // Enumerators are numbered from zero in the order they are declared, unless
+        // given a value with @value, which the following enumerators then count on from
+        let mut values: Vec<(i32, &Identifier)> = Vec::new();
+        let mut next = Some(0);
+        for enumerator in &e.enumerators {
+            let value = match self.annotation_param(
+                path,
+                &enumerator.annotations,
+                "value",
+                PrimitiveType::Long,
+            ) {
+                Ok(Some(value)) => Ok(value as i32),
+                Ok(None) => next.ok_or_else(|| {
+                    Simple::custom(
+                        enumerator.span.clone(),
+                        format!(
+                            "the value of `{}` exceeds the range of long",
+                            enumerator.name
+                        ),
+                    )
+                }),
+                Err(error) => Err(error),
+            };
+
+            let value = match value {
+                Ok(value) => value,
+                Err(error) => {
+                    errors.push(error);
+                    match next {
+                        Some(value) => value,
+                        None => continue,
+                    }
+                }
+            };
+            if let Some((_, earlier)) = values.iter().find(|(v, _)| *v == value) {
+                errors.push(Simple::custom(
+                    enumerator.span.clone(),
+                    format!(
+                        "`{}` has the value {}, which is already the value of `{}`",
+                        enumerator.name, value, earlier
+                    ),
+                ));
+            }
+            values.push((value, &enumerator.name));
+            next = value.checked_add(1);
+
+            let constant =
+                ConstValue::Enumerator(path.child(&e.name), enumerator.name.clone(), value);
+            self.insert(
+                path,
+                &enumerator.name,
+                Symbol::Enumerator(constant),
+                &enumerator.span,
+                errors,
+            );
+        }
+    }
+
+    /// Declares a bitmask within the scope `path`, computing the position of each of
+    /// its bits
+    fn declare_bitmask(&mut self, path: &ScopedName, b: &Bitmask, errors: &mut Vec<Simple<char>>) {
+        // XTypes 7.3.1.2.1.5
+        // The @bit_bound annotation sets the number of bits of the bitmask, which
+        // defaults to 32 and may be at most 64. Bits are given positions from zero in
+        // the order they are declared, unless given a position with @position, which
+        // the following bits then count on from.
+        let mut width = 32;
+        match self.annotation_param(
+            path,
+            &b.annotations,
+            "bit_bound",
+            PrimitiveType::UnsignedShort,
+        ) {
+            Ok(Some(bound)) if !(1..=64).contains(&bound) => errors.push(Simple::custom(
+                b.span.clone(),
+                format!(
+                    "the bit bound of bitmask `{}` must be between 1 and 64, found {}",
+                    b.name, bound
+                ),
+            )),
+            Ok(Some(bound)) => width = bound as u32,
+            Ok(None) => {}
+            Err(error) => errors.push(error),
+        }
+
+        let mut fields: Vec<BitField> = Vec::new();
+        let mut next = 0;
+        for value in &b.values {
+            let position = match self.annotation_param(
+                path,
+                &value.annotations,
+                "position",
+                PrimitiveType::UnsignedShort,
+            ) {
+                Ok(Some(position)) => position as u32,
+                Ok(None) => next,
+                Err(error) => {
+                    errors.push(error);
+                    next
+                }
+            };
+            next = position + 1;
+
+            if position >= width {
+                errors.push(Simple::custom(
+                    value.span.clone(),
+                    format!(
+                        "`{}` has position {}, which is outside of the {} bits of bitmask `{}`",
+                        value.name, position, width, b.name
+                    ),
+                ));
+            } else if let Some(earlier) = fields.iter().find(|f| f.offset == position) {
+                errors.push(Simple::custom(
+                    value.span.clone(),
+                    format!(
+                        "`{}` has position {}, which is already the position of `{}`",
+                        value.name,
+                        position,
+                        earlier.name.as_ref().expect("bitmask values are named")
+                    ),
+                ));
+            }
+            fields.push(BitField {
+                name: Some(value.name.clone()),
+                offset: position,
+                width: 1,
+                ty: PrimitiveType::Boolean,
+            });
+        }
+
+        let names = b.values.iter().map(|v| (&v.name, v.span.clone()));
+        if let Err(collisions) = Identifier::check_scope(names) {
+            errors.extend(collisions);
+        }
+        let layout = BitLayout { fields, width };
+        self.insert(path, &b.name, Symbol::Bitmask(layout), &b.span, errors);
+    }
+
+    /// Declares a bitset within the scope `path`, computing the offset and width of
+    /// each of its bitfields after those of the bitset it inherits from
+    fn declare_bitset(&mut self, path: &ScopedName, b: &Bitset, errors: &mut Vec<Simple<char>>) {
+        // 7.4.13.4.3.2
+        // A bitset can inherit from another bitset, in which case the bitfields of
+        // the base bitset come first. The bitfields are laid out in the order they
+        // are declared and the total size of a bitset is limited to 64 bits.
+        let mut fields = match &b.base {
+            Some(base) => match self.lookup(path, &base.name) {
+                Some((
+                    _,
+                    Declaration {
+                        symbol: Symbol::Bitset(layout),
+                        ..
+                    },
+                )) => layout.fields.clone(),
+                Some((_, declaration)) => {
+                    errors.push(Simple::custom(
+                        base.span.clone(),
+                        format!(
+                            "`{}` is {} and cannot be the base of a bitset",
+                            base.name,
+                            declaration.symbol.description()
+                        ),
+                    ));
+                    Vec::new()
+                }
+                None => {
+                    errors.push(Simple::custom(
+                        base.span.clone(),
+                        format!("`{}` does not name a type", base.name),
+                    ));
+                    Vec::new()
+                }
+            },
+            None => Vec::new(),
+        };
+        let mut offset = fields.iter().map(|f| f.offset + f.width).max().unwrap_or(0);
+
+        // Inherited bitfields are reported at the base they come from
+        let base_span = b
+            .base
+            .as_ref()
+            .map_or(b.span.clone(), |base| base.span.clone());
+        let mut names: Vec<(Identifier, Range<usize>)> = fields
+            .iter()
+            .filter_map(|f| f.name.clone())
+            .map(|name| (name, base_span.clone()))
+            .collect();
+
+        for bitfield in &b.bitfields {
+            let declared = bitfield.names.iter().cloned();
+            names.extend(declared.map(|name| (name, bitfield.span.clone())));
+
+            let scope = self.scope(path.clone());
+            let width = match bitfield
+                .width
+                .evaluate_as(PrimitiveType::UnsignedShort, &scope)
+            {
+                Ok(ConstValue::Integer(width)) if (1..=64).contains(&width) => width as u32,
+                Ok(value) => {
+                    errors.push(Simple::custom(
+                        bitfield.width.span(),
+                        format!(
+                            "the width of a bitfield must be between 1 and 64, found {}",
+                            value
+                        ),
+                    ));
+                    continue;
+                }
+                Err(error) => {
+                    errors.push(error);
+                    continue;
+                }
+            };
+
+            // When no type is given the smallest unsigned type the bitfield fits in
+            // is used
+            let ty = match &bitfield.ty {
+                None => match width {
+                    1 => PrimitiveType::Boolean,
+                    2..=8 => PrimitiveType::Octet,
+                    9..=16 => PrimitiveType::UnsignedShort,
+                    17..=32 => PrimitiveType::UnsignedLong,
+                    _ => PrimitiveType::UnsignedLongLong,
+                },
+                Some(TypeSpec::Primitive(ty, span)) => {
+                    let bits = match ty {
+                        PrimitiveType::Boolean => Some(1),
+                        ty => ty.integer_bits(),
+                    };
+                    match bits {
+                        Some(bits) if width <= bits => *ty,
+                        Some(_) => {
+                            errors.push(Simple::custom(
+                                span.clone(),
+                                format!("a bitfield of {} bits does not fit in {}", width, ty),
+                            ));
+                            *ty
+                        }
+                        None => {
+                            errors.push(Simple::custom(
+                                span.clone(),
+                                "the type of a bitfield must be boolean, octet or an integer type",
+                            ));
+                            continue;
+                        }
+                    }
+                }
+                Some(ty) => {
+                    errors.push(Simple::custom(
+                        ty.span(),
+                        "the type of a bitfield must be boolean, octet or an integer type",
+                    ));
+                    continue;
+                }
+            };
+
+            let names: Vec<Option<Identifier>> = if bitfield.names.is_empty() {
+                vec![None]
+            } else {
+                bitfield.names.iter().cloned().map(Some).collect()
+            };
+            for name in names {
+                fields.push(BitField {
+                    name,
+                    offset,
+                    width,
+                    ty,
+                });
+                offset += width;
+            }
+        }
+
+        if offset > 64 {
+            errors.push(Simple::custom(
+                b.span.clone(),
+                format!(
+                    "bitset `{}` is {} bits wide, which exceeds the limit of 64 bits",
+                    b.name, offset
+                ),
+            ));
+        }
+
+        let names = names.iter().map(|(name, span)| (name, span.clone()));
+        if let Err(collisions) = Identifier::check_scope(names) {
+            errors.extend(collisions);
+        }
+
+        let layout = BitLayout {
+            fields,
+            width: offset,
+        };
+        self.insert(path, &b.name, Symbol::Bitset(layout), &b.span, errors);
+    }
+
+    /// Declares an interface within the scope `path`, along with the operations,
+    /// attributes and definitions it exports
+    fn declare_interface(
+        &mut self,
+        path: &ScopedName,
+        interface: &Interface,
+        errors: &mut Vec<Simple<char>>,
+    ) {
+        // 7.4.3.4.3.2
+        // An interface can be derived from another interface, which is then called
+        // a base interface of the derived interface. [...] An interface may not be
+        // specified as a direct base interface of a derived interface more than once.
+        //
+        // 7.4.7.4 and 7.4.8.4
+        // An unconstrained interface may not inherit from a local interface, and an
+        // abstract interface may only inherit from other abstract interfaces.
+        let mut bases: Vec<ScopedName> = Vec::new();
+        for base in &interface.bases {
+            let message = match self.lookup(path, &base.name) {
+                Some((name, _)) if bases.contains(&name) => {
+                    format!("`{}` is inherited more than once", base.name)
+                }
+                Some((
+                    name,
+                    Declaration {
+                        symbol: Symbol::Interface(modifier, _),
+                        ..
+                    },
+                )) => match (&interface.modifier, modifier) {
+                    (None, Some(Keyword::Local))
+                    | (Some(Keyword::Abstract), None | Some(Keyword::Local)) => format!(
+                        "`{}` is {} and cannot be the base of {}",
+                        base.name,
+                        self.symbols[&name].symbol.description(),
+                        Symbol::Interface(interface.modifier.clone(), Vec::new()).description()
+                    ),
+                    _ => {
+                        bases.push(name);
+                        continue;
+                    }
+                },
+                Some((_, declaration)) => format!(
+                    "`{}` is {} and cannot be the base of an interface",
+                    base.name,
+                    declaration.symbol.description()
+                ),
+                None => format!("`{}` does not name a type", base.name),
+            };
+            errors.push(Simple::custom(base.span.clone(), message));
+        }
+
+        let symbol = Symbol::Interface(interface.modifier.clone(), bases.clone());
+        self.insert(path, &interface.name, symbol, &interface.span, errors);
+
+        let scope = path.child(&interface.name);
+        for export in &interface.exports {
+            self.declare_export(&scope, &bases, export, errors);
+        }
+    }
+
+    /// Declares a value type or event type within the scope `path`, along with the
+    /// state members, factories, operations, attributes and definitions it contains
+    fn declare_value_type(
+        &mut self,
+        path: &ScopedName,
+        value: &ValueType,
+        errors: &mut Vec<Simple<char>>,
+    ) {
+        let kind = |modifier: Option<Keyword>, bases: Vec<ScopedName>| match value.keyword {
+            Keyword::EventType => Symbol::EventType(modifier, bases),
+            _ => Symbol::ValueType(modifier, bases),
+        };
+        let plain = kind(None, Vec::new());
+
+        // 7.4.7.4
+        // A value type may inherit from any number of abstract value types, but from
+        // at most one value type that is not abstract, which must be listed first.
+        // An abstract value type may only inherit from other abstract value types.
+        //
+        // 7.4.10.4
+        // An event type may only inherit from a value type that is not abstract if it
+        // is an event type.
+        let mut bases: Vec<ScopedName> = Vec::new();
+        let mut stateful_base = false;
+        for (index, base) in value.bases.iter().enumerate() {
+            let message = match self.lookup(path, &base.name) {
+                Some((name, _)) if bases.contains(&name) => {
+                    format!("`{}` is inherited more than once", base.name)
+                }
+                Some((
+                    name,
+                    Declaration {
+                        symbol:
+                            symbol @ (Symbol::ValueType(modifier, _) | Symbol::EventType(modifier, _)),
+                        ..
+                    },
+                )) => match (&value.modifier, modifier) {
+                    (_, Some(Keyword::Abstract)) => {
+                        bases.push(name);
+                        continue;
+                    }
+                    (Some(Keyword::Abstract), _) => format!(
+                        "`{}` is {} and cannot be the base of {}",
+                        base.name,
+                        symbol.description(),
+                        kind(value.modifier.clone(), Vec::new()).description()
+                    ),
+                    _ if value.keyword == Keyword::EventType
+                        && !matches!(symbol, Symbol::EventType(_, _)) =>
+                    {
+                        format!(
+                            "`{}` is {} and cannot be the base of {}",
+                            base.name,
+                            symbol.description(),
+                            plain.description()
+                        )
+                    }
+                    _ if index > 0 => format!(
+                        "`{}` is {} and can only be inherited as the first base of {}",
+                        base.name,
+                        symbol.description(),
+                        plain.description()
+                    ),
+                    _ => {
+                        stateful_base = true;
+                        bases.push(name);
+                        continue;
+                    }
+                },
+                Some((_, declaration)) => format!(
+                    "`{}` is {} and cannot be the base of {}",
+                    base.name,
+                    declaration.symbol.description(),
+                    plain.description()
+                ),
+                None => format!("`{}` does not name a type", base.name),
+            };
+            errors.push(Simple::custom(base.span.clone(), message));
+        }
+
+        // 7.4.7.4
+        // A value type that is truncatable may be truncated to its first base, which
+        // must be a value type that is not abstract. Custom value types cannot be
+        // truncatable.
+        if value.truncatable {
+            let message = match &value.modifier {
+                Some(_) => Some(format!(
+                    "{} cannot be truncatable",
+                    kind(value.modifier.clone(), Vec::new()).description()
+                )),
+                None if !stateful_base => Some(format!(
+                    "a truncatable {} must first inherit from {} that is not abstract",
+                    plain.kind(),
+                    plain.description()
+                )),
+                None => None,
+            };
+            if let Some(message) = message {
+                errors.push(Simple::custom(value.bases[0].span.clone(), message));
+            }
+        }
+
+        // 7.4.7.4
+        // A value type may support any number of abstract interfaces, but at most one
+        // interface that is not abstract.
+        let supports = self.supported_interfaces(path, &value.supports, &plain, errors);
+        let mut concrete = supports
+            .iter()
+            .filter(|(_, modifier)| *modifier != Some(Keyword::Abstract));
+        if let Some((first, _)) = concrete.next() {
+            for (interface, _) in concrete {
+                errors.push(Simple::custom(
+                    interface.span.clone(),
+                    format!(
+                        "`{}` cannot be supported along with `{}`, {} may support at most one interface that is not abstract",
+                        interface.name,
+                        first.name,
+                        plain.description()
+                    ),
+                ));
+            }
+        }
+
+        let symbol = kind(value.modifier.clone(), bases.clone());
+        self.insert(path, &value.name, symbol, &value.span, errors);
+
+        let scope = path.child(&value.name);
+        for element in &value.elements {
+            match element {
+                ValueElement::Export(export) => self.declare_export(&scope, &bases, export, errors),
+                ValueElement::State(member) => {
+                    let symbol = Symbol::StateMember(member.visibility.clone());
+                    for declarator in &member.declarators {
+                        self.declare_member(
+                            &scope,
+                            &bases,
+                            &declarator.name,
+                            symbol.clone(),
+                            &declarator.span,
+                            errors,
+                        );
+                    }
+                }
+                ValueElement::Factory(factory) => self.insert(
+                    &scope,
+                    &factory.name,
+                    Symbol::Factory,
+                    &factory.span,
+                    errors,
+                ),
+            }
+        }
+    }
+
+    /// Declares a component within the scope `path`, along with its ports and
+    /// attributes
+    fn declare_component(
+        &mut self,
+        path: &ScopedName,
+        component: &Component,
+        errors: &mut Vec<Simple<char>>,
+    ) {
+        // 7.4.8.4
+        // A component may inherit from a single other component, and may support any
+        // number of interfaces.
+        let base = self.base(
+            path,
+            component.base.as_ref(),
+            &Symbol::Component(None),
+            |symbol| matches!(symbol, Symbol::Component(_)),
+            errors,
+        );
+        let supporter = Symbol::Component(None);
+        self.supported_interfaces(path, &component.supports, &supporter, errors);
+
+        let symbol = Symbol::Component(base.clone());
+        self.insert(path, &component.name, symbol, &component.span, errors);
+
+        let scope = path.child(&component.name);
+        self.declare_ports(&scope, base.as_slice(), &component.exports, errors);
+    }
+
+    /// Declares a home within the scope `path`, along with the factories, finders,
+    /// operations, attributes and definitions it contains
+    fn declare_home(&mut self, path: &ScopedName, home: &Home, errors: &mut Vec<Simple<char>>) {
+        // 7.4.9.4
+        // A home may inherit from a single other home, and may support any number of
+        // interfaces.
+        let base = self.base(
+            path,
+            home.base.as_ref(),
+            &Symbol::Home(None),
+            |symbol| matches!(symbol, Symbol::Home(_)),
+            errors,
+        );
+        self.supported_interfaces(path, &home.supports, &Symbol::Home(None), errors);
+
+        let symbol = Symbol::Home(base.clone());
+        self.insert(path, &home.name, symbol, &home.span, errors);
+
+        let scope = path.child(&home.name);
+        let bases = base.as_slice();
+        for export in &home.exports {
+            match export {
+                HomeExport::Export(export) => self.declare_export(&scope, bases, export, errors),
+                HomeExport::Factory(factory) => {
+                    let symbol = match factory.keyword {
+                        Keyword::Finder => Symbol::Finder,
+                        _ => Symbol::Factory,
+                    };
+                    self.insert(&scope, &factory.name, symbol, &factory.span, errors)
+                }
+            }
+        }
+    }
+
+    /// Declares a porttype within the scope `path`, along with the ports and
+    /// attributes it contains
+    fn declare_port_type(
+        &mut self,
+        path: &ScopedName,
+        port_type: &PortType,
+        errors: &mut Vec<Simple<char>>,
+    ) {
+        let ports = port_type
+            .exports
+            .iter()
+            .filter_map(|export| match export {
+                ComponentExport::Port(port) => Some(port.clone()),
+                ComponentExport::Attribute(_) => None,
+            })
+            .collect();
+        let symbol = Symbol::PortType(ports);
+        self.insert(path, &port_type.name, symbol, &port_type.span, errors);
+
+        let scope = path.child(&port_type.name);
+        self.declare_ports(&scope, &[], &port_type.exports, errors);
+    }
+
+    /// Declares a connector within the scope `path`, along with the ports and
+    /// attributes it contains
+    fn declare_connector(
+        &mut self,
+        path: &ScopedName,
+        connector: &Connector,
+        errors: &mut Vec<Simple<char>>,
+    ) {
+        // 7.4.11.4
+        // A connector may inherit from a single other connector.
+        let base = self.base(
+            path,
+            connector.base.as_ref(),
+            &Symbol::Connector(None),
+            |symbol| matches!(symbol, Symbol::Connector(_)),
+            errors,
+        );
+
+        let symbol = Symbol::Connector(base.clone());
+        self.insert(path, &connector.name, symbol, &connector.span, errors);
+
+        let scope = path.child(&connector.name);
+        self.declare_ports(&scope, base.as_slice(), &connector.exports, errors);
+    }
+
+    /// Declares the ports and attributes of a component, porttype or connector
+    /// within its scope `scope`, where `bases` are the absolute names of the scopes
+    /// it inherits from
+    fn declare_ports(
+        &mut self,
+        scope: &ScopedName,
+        bases: &[ScopedName],
+        exports: &[ComponentExport],
+        errors: &mut Vec<Simple<char>>,
+    ) {
+        for export in exports {
+            match export {
+                ComponentExport::Port(port) => {
+                    let symbol = Symbol::Port(port.keyword.clone());
+                    self.declare_member(scope, bases, &port.name, symbol, &port.span, errors)
+                }
+                ComponentExport::Attribute(a) => {
+                    for name in &a.names {
+                        let symbol = Symbol::Attribute(a.readonly);
+                        self.declare_member(scope, bases, name, symbol, &a.span, errors);
+                    }
+                }
+            }
+        }
+    }
+
+    /// Resolves the single base of a struct, component, home or connector declared within
+    /// the scope `path`, returning its absolute name if it is a symbol accepted by
+    /// `is_base` and reporting it otherwise. `derived` is the kind of symbol that
+    /// inherits from the base.
+    fn base(
+        &self,
+        path: &ScopedName,
+        base: Option<&Reference>,
+        derived: &Symbol,
+        is_base: impl Fn(&Symbol) -> bool,
+        errors: &mut Vec<Simple<char>>,
+    ) -> Option<ScopedName> {
+        let base = base?;
+        let message = match self.lookup(path, &base.name) {
+            Some((name, declaration)) if is_base(&declaration.symbol) => return Some(name),
+            Some((_, declaration)) => format!(
+                "`{}` is {} and cannot be the base of {}",
+                base.name,
+                declaration.symbol.description(),
+                derived.description()
+            ),
+            None => format!("`{}` does not name a type", base.name),
+        };
+        errors.push(Simple::custom(base.span.clone(), message));
+        None
+    }
+
+    /// Resolves the interfaces supported by a value type, component or home
+    /// declared within the scope `path`, reporting any name that is not an
+    /// interface or is supported more than once. `supporter` is the kind of symbol
+    /// that supports the interfaces. Each interface is returned along with its
+    /// `local` or `abstract` modifier.
+    fn supported_interfaces<'r>(
+        &self,
+        path: &ScopedName,
+        supports: &'r [Reference],
+        supporter: &Symbol,
+        errors: &mut Vec<Simple<char>>,
+    ) -> Vec<(&'r Reference, Option<Keyword>)> {
+        let mut names: Vec<ScopedName> = Vec::new();
+        let mut interfaces = Vec::new();
+        for interface in supports {
+            let message = match self.lookup(path, &interface.name) {
+                Some((name, _)) if names.contains(&name) => {
+                    format!("`{}` is supported more than once", interface.name)
+                }
+                Some((
+                    name,
+                    Declaration {
+                        symbol: Symbol::Interface(modifier, _),
+                        ..
+                    },
+                )) => {
+                    names.push(name);
+                    interfaces.push((interface, modifier.clone()));
+                    continue;
+                }
+                Some((_, declaration)) => format!(
+                    "`{}` is {} and cannot be supported by {}",
+                    interface.name,
+                    declaration.symbol.description(),
+                    supporter.description()
+                ),
+                None => format!("`{}` does not name a type", interface.name),
+            };
+            errors.push(Simple::custom(interface.span.clone(), message));
+        }
+        interfaces
+    }
+
+    /// Declares a single export of an interface, value type or home within its scope
+    /// `scope`, where `bases` are the absolute names of the scopes it inherits from
+    fn declare_export(
+        &mut self,
+        scope: &ScopedName,
+        bases: &[ScopedName],
+        export: &Export,
+        errors: &mut Vec<Simple<char>>,
+    ) {
+        match export {
+            Export::Definition(definition) => {
+                self.declare(scope, std::slice::from_ref(definition), errors)
+            }
+            Export::Operation(o) => {
+                self.declare_member(scope, bases, &o.name, Symbol::Operation, &o.span, errors)
+            }
+            Export::Attribute(a) => {
+                for name in &a.names {
+                    let symbol = Symbol::Attribute(a.readonly);
+                    self.declare_member(scope, bases, name, symbol, &a.span, errors);
+                }
+            }
+        }
+    }
+
+    /// Declares an operation, attribute, state member or port within the scope `scope`,
+    /// reporting it if it redefines a member inherited from one of `bases`
+    fn declare_member(
+        &mut self,
+        scope: &ScopedName,
+        bases: &[ScopedName],
+        name: &Identifier,
+        symbol: Symbol,
+        span: &Range<usize>,
+        errors: &mut Vec<Simple<char>>,
+    ) {
+        // 7.4.3.4.3.4
+        // It is illegal to redefine an attribute or an operation in a derived
+        // interface, or to inherit two operations or attributes with the same name.
+        let relative = ScopedName::from(name.as_str());
+        let inherited = bases
+            .iter()
+            .find_map(|base| self.lookup_in(base, &relative));
+        match inherited {
+            Some((inherited, declaration))
+                if matches!(
+                    declaration.symbol,
+                    Symbol::Operation
+                        | Symbol::Attribute(_)
+                        | Symbol::StateMember(_)
+                        | Symbol::Port(_)
+                ) =>
+            {
+                let base = &inherited.parts[inherited.parts.len() - 2];
+                errors.push(Simple::custom(
+                    span.clone(),
+                    format!(
+                        "`{}` is already declared as {} of `{}` and cannot be redefined",
+                        name,
+                        declaration.symbol.description(),
+                        base
+                    ),
+                ))
+            }
+            _ => self.insert(scope, name, symbol, span, errors),
+        }
+    }
+
+    /// Evaluates the single parameter of the built-in annotation called `name`, if
+    /// it is one of the given annotations
+    fn annotation_param(
+        &self,
+        path: &ScopedName,
+        annotations: &[Annotation],
+        name: &str,
+        ty: PrimitiveType,
+    ) -> Result<Option<i128>, Simple<char>> {
+        let (annotation, builtin) = match annotations
+            .iter()
+            .filter_map(|a| a.builtin().map(|builtin| (a, builtin)))
+            .find(|(_, builtin)| builtin.name == name)
+        {
+            Some(found) => found,
+            None => return Ok(None),
+        };
+        let value = match builtin.bind(annotation)?[0] {
+            Some(value) => value,
+            None => return Ok(None),
+        };
+        match value.evaluate_as(ty, &self.scope(path.clone()))? {
+            ConstValue::Integer(value) => Ok(Some(value)),
+            _ => unreachable!("integer constants are integers"),
+        }
+    }
+
+    /// Declares a single name within the scope `path`, reporting it if it collides
+    /// with a name already declared there
+    fn insert(
+        &mut self,
+        path: &ScopedName,
+        name: &Identifier,
+        symbol: Symbol,
+        span: &Range<usize>,
+        errors: &mut Vec<Simple<char>>,
+    ) {
+        let key = path.child(name);
+        let declared = self.scopes.entry(path.clone()).or_default();
+        let earlier = match declared.iter().find(|d| d.collides_with(name)) {
+            Some(earlier) => earlier,
+            None => {
+                declared.push(name.clone());
+                self.symbols.insert(
+                    key,
+                    Declaration {
+                        symbol,
+                        span: span.clone(),
+                    },
+                );
+                return;
+            }
+        };
+
+        // 7.4.1.4.1
+        // A module can be reopened.
+        //
+        // 7.4.1.4.4.4.4
+        // A structure or union type may be forward declared, the full definition
+        // of the type must follow.
+        if earlier == name {
+            let existing = &self.symbols[&key];
+            match (&existing.symbol, &symbol) {
+                (Symbol::Module, Symbol::Module) => return,
+                (Symbol::Forward(_), _)
+                    if existing.symbol.forward_keyword() == symbol.forward_keyword() =>
+                {
+                    self.symbols.insert(
+                        key,
+                        Declaration {
+                            symbol,
+                            span: span.clone(),
+                        },
+                    );
+                    return;
+                }
+                (_, Symbol::Forward(_))
+                    if existing.symbol.forward_keyword() == symbol.forward_keyword() =>
+                {
+                    return
+                }
+                _ => {}
+            }
+        }
+        errors.push(Simple::custom(
+            span.clone(),
+            name.collision_message(earlier),
+        ));
+    }
+
+    /// Evaluates the value of a constant declared within the scope `path`, checking
+    /// that it is of the declared type
+    pub fn evaluate(
+        &self,
+        path: &ScopedName,
+        constant: &Const,
+    ) -> Result<ConstValue, Simple<char>> {
+        // Primitive types are checked by the evaluator itself, the other types only
+        // need the kind of the value checked
+        let scope = self.scope(path.clone());
+        let ty = scope.resolve_type(&constant.ty)?;
+        let expected = match &ty {
+            TypeSpec::Primitive(ty, _) => return constant.value.evaluate_as(*ty, &scope),
+            TypeSpec::String(_, _) => "string",
+            TypeSpec::WString(_, _) => "wstring",
+            TypeSpec::Fixed(_, _) => "fixed",
+            TypeSpec::Named(name, _) => {
+                let declaration = self.get(name).expect("resolved names are declared");
+                if let Symbol::Enum(_) = declaration.symbol {
+                    let value = constant.value.evaluate(&scope)?;
+                    return match &value {
+                        ConstValue::Enumerator(e, _, _) if e == name => Ok(value),
+                        _ => Err(Simple::custom(
+                            constant.value.span(),
+                            format!(
+                                "expected a value of type {}, found {} value {}",
+                                name.name(),
+                                value.kind(),
+                                value
+                            ),
+                        )),
+                    };
+                }
+                return Err(Simple::custom(
+                    constant.ty.span(),
+                    format!(
+                        "`{}` is {} and cannot be the type of a constant",
+                        name.name(),
+                        declaration.symbol.description()
+                    ),
+                ));
+            }
+            _ => {
+                return Err(Simple::custom(
+                    constant.ty.span(),
+                    "the type of a constant must be an integer, floating point, fixed point, character, boolean, octet or string type",
+                ))
+            }
+        };
+
+        let value = constant.value.evaluate(&scope)?;
+        match (&ty, &value) {
+            (TypeSpec::Fixed(Some((digits, scale)), _), ConstValue::FixedPoint(fixed)) => {
+                // A malformed digits or scale is reported along with the type itself
+                let bound = |expr: &ConstExpr| match expr
+                    .evaluate_as(PrimitiveType::UnsignedShort, &scope)
+                {
+                    Ok(ConstValue::Integer(value)) => Some(value as u32),
+                    _ => None,
+                };
+                match (bound(digits), bound(scale)) {
+                    (Some(digits), Some(scale))
+                        if digits <= 31 && scale <= digits && !fixed.fits(digits, scale) =>
+                    {
+                        Err(Simple::custom(
+                            constant.value.span(),
+                            format!(
+                                "value {} is out of range for type fixed<{}, {}>",
+                                value, digits, scale
+                            ),
+                        ))
+                    }
+                    _ => Ok(value),
+                }
+            }
+            (TypeSpec::String(_, _), ConstValue::Str(_))
+            | (TypeSpec::WString(_, _), ConstValue::WideStr(_))
+            | (TypeSpec::Fixed(_, _), ConstValue::FixedPoint(_)) => Ok(value),
+            _ => Err(Simple::custom(
+                constant.value.span(),
+                format!(
+                    "expected a value of type {}, found {} value {}",
+                    expected,
+                    value.kind(),
+                    value
+                ),
+            )),
+        }
+    }
+}
+
+/// The Scope type is a view of a symbol table from within one of the scopes of
+/// a specification
+#[derive(Debug, Clone)]
+pub struct Scope<'a> {
+    table: &'a SymbolTable,
+    path: ScopedName,
+}
+
+impl<'a> Scope<'a> {
+    /// Returns the absolute name of the scope
+    pub fn path(&self) -> &ScopedName {
+        &self.path
+    }
+
+    /// Returns the view from the scope with the absolute name `path`
+    pub fn at(&self, path: ScopedName) -> Scope<'a> {
+        self.table.scope(path)
+    }
+
+    /// Returns the view from the scope named `name` declared within this scope
+    pub fn nested(&self, name: &Identifier) -> Scope<'a> {
+        self.table.scope(self.path.child(name))
+    }
+
+    /// Resolves a name referenced from within this scope, returning the absolute
+    /// name of the declaration it refers to along with the declaration
+    pub fn lookup(&self, name: &ScopedName) -> Option<(ScopedName, &'a Declaration)> {
+        self.table.lookup(&self.path, name)
+    }
+
+    /// Resolves a type referenced from within this scope through any typedefs
+    /// aliasing it. Other named types are given by their absolute name, and a
+    /// typedef of an array or of a type that could not be resolved is left as is.
+    pub fn resolve_type(&self, ty: &TypeSpec) -> Result<TypeSpec, Simple<char>> {
+        let (name, span) = match ty {
+            TypeSpec::Named(name, span) => (name, span),
+            ty => return Ok(ty.clone()),
+        };
+        match self.lookup(name) {
+            Some((
+                _,
+                Declaration {
+                    symbol: Symbol::Typedef(Some(ty), dimensions),
+                    ..
+                },
+            )) if dimensions.is_empty() => Ok(ty.clone()),
+            Some((name, _)) => Ok(TypeSpec::Named(name, span.clone())),
+            None => Err(Simple::custom(
+                span.clone(),
+                format!("`{}` does not name a type", name),
+            )),
+        }
+    }
+}
+
+impl ConstScope for Scope<'_> {
+    fn resolve(&self, name: &ScopedName) -> Option<ConstValue> {
+        match self
+            .lookup(name)
+            .map(|(_, declaration)| &declaration.symbol)
+        {
+            Some(Symbol::Const(value)) => value.clone(),
+            Some(Symbol::Enumerator(value)) => Some(value.clone()),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod scope_tests {
+    use crate::ast::TypeSpec;
+    use crate::expr::ConstValue;
+    use crate::keyword::Keyword;
+    use crate::name::{Identifier, ScopedName};
+    use crate::primitive::PrimitiveType;
+    use crate::scope::{Symbol, SymbolTable};
+    use crate::test_utils::{messages, parse};
+
+    fn table(source: &str) -> (SymbolTable, Vec<String>) {
+        let (table, errors) = SymbolTable::new(&parse(source));
+        (table, messages(&errors))
+    }
+
+    #[test]
+    fn lookup() {
+        let (table, errors) = table(
+            "
+            const long A = 1;
+            module M {
+                const long A = 2;
+                module N { const long B = A; const long C = ::A; };
+            };
+            ",
+        );
+        assert!(errors.is_empty());
+
+        let n = ScopedName::from("::M::N");
+        let (name, _) = table.lookup(&n, &ScopedName::from("A")).unwrap();
+        assert_eq!(name, ScopedName::from("::M::A"));
+        let (name, _) = table.lookup(&n, &ScopedName::from("::A")).unwrap();
+        assert_eq!(name, ScopedName::from("::A"));
+        let (name, _) = table
+            .lookup(&ScopedName::root(), &ScopedName::from("M::N::B"))
+            .unwrap();
+        assert_eq!(name, ScopedName::from("::M::N::B"));
+        assert!(table
+            .lookup(&ScopedName::root(), &ScopedName::from("B"))
+            .is_none());
+        assert!(table.lookup(&n, &ScopedName::from("N::M")).is_none());
+
+        let value = |name: &str| table.get(&ScopedName::from(name)).unwrap().symbol.clone();
+        assert_eq!(
+            value("::M::N::B"),
+            Symbol::Const(Some(ConstValue::Integer(2)))
+        );
+        assert_eq!(
+            value("::M::N::C"),
+            Symbol::Const(Some(ConstValue::Integer(1)))
+        );
+        assert_eq!(value("::M"), Symbol::Module);
+    }
+
+    #[test]
+    fn collisions() {
+        let (_, errors) = table(
+            "
+            struct S { long x; };
+            native s;
+            native S;
+            module S { native N; };
+            module M { native S; };
+            ",
+        );
+        assert_eq!(
+            errors,
+            vec![
+                "`s` collides with `S` declared in the same scope",
+                "`S` is already declared in this scope",
+                "`S` is already declared in this scope",
+            ]
+        );
+
+        // Forward declarations may come before or after the definition
+        let (table, errors) = table(
+            "
+            struct A; union B;
+            struct A { long x; };
+            union B switch (long) { case 1: long x; };
+            struct A; union B;
+            union C; union C;
+            struct B;
+            ",
+        );
+        assert_eq!(errors, vec!["`B` is already declared in this scope"]);
+        assert_eq!(
+            table.get(&ScopedName::from("::A")).unwrap().symbol,
+            Symbol::Struct(None, vec![Identifier::new("x").unwrap()])
+        );
+        assert_eq!(
+            table.get(&ScopedName::from("::B")).unwrap().symbol,
+            Symbol::Union
+        );
+    }
+
+    #[test]
+    fn enumerators() {
+        let (table, errors) = table(
+            "
+            module M {
+                enum Color { RED, @value(5) GREEN, BLUE };
+                const Color FAVOURITE = BLUE;
+            };
+            const M::Color C = M::RED;
+            ",
+        );
+        assert!(errors.is_empty());
+
+        let symbol = |name: &str| table.get(&ScopedName::from(name)).unwrap().symbol.clone();
+        let enumerator = |name: &str, value| {
+            ConstValue::Enumerator(
+                ScopedName::from("::M::Color"),
+                Identifier::new(name).unwrap(),
+                value,
+            )
+        };
+        assert_eq!(
+            symbol("::M::Color"),
+            Symbol::Enum(vec![
+                Identifier::new("RED").unwrap(),
+                Identifier::new("GREEN").unwrap(),
+                Identifier::new("BLUE").unwrap(),
+            ])
+        );
+        assert_eq!(symbol("::M::RED"), Symbol::Enumerator(enumerator("RED", 0)));
+        assert_eq!(
+            symbol("::M::GREEN"),
+            Symbol::Enumerator(enumerator("GREEN", 5))
+        );
+        assert_eq!(
+            symbol("::M::BLUE"),
+            Symbol::Enumerator(enumerator("BLUE", 6))
+        );
+        assert_eq!(
+            symbol("::M::FAVOURITE"),
+            Symbol::Const(Some(enumerator("BLUE", 6)))
+        );
+        assert_eq!(symbol("::C"), Symbol::Const(Some(enumerator("RED", 0))));
+    }
+
+    #[test]
+    fn enumerator_errors() {
+        let (_, errors) = table(
+            "
+            native RED;
+            enum Color { RED, GREEN, @value(1) BLUE, @value(1, 2) CYAN, Green };
+            enum Shape { @value(2147483647) SQUARE, CIRCLE };
+            const Color C = 1;
+            const Color D = SQUARE;
+            const long L = GREEN + 1;
+            ",
+        );
+        assert_eq!(
+            errors,
+            vec![
+                "`RED` is already declared in this scope",
+                "`BLUE` has the value 1, which is already the value of `GREEN`",
+                "@value takes exactly one parameter",
+                "`Green` collides with `GREEN` declared in the same scope",
+                "the value of `CIRCLE` exceeds the range of long",
+                "expected a value of type Color, found integer value 1",
+                "expected a value of type Color, found enumerator value SQUARE",
+                "operator `+` cannot be applied to mixed enumerator and integer values",
+            ]
+        );
+    }
+
+    #[test]
+    fn bitmasks() {
+        let (table, errors) = table(
+            "
+            const short P = 4;
+            @bit_bound(8) bitmask Flags { A, @position(P) B, C };
+            bitmask Wide { X, @position(31) Y };
+            ",
+        );
+        assert!(errors.is_empty(), "{:?}", errors);
+        let layout = match &table.get(&ScopedName::from("::Flags")).unwrap().symbol {
+            Symbol::Bitmask(layout) => layout,
+            symbol => panic!("unexpected symbol {:?}", symbol),
+        };
+        assert_eq!(layout.width, 8);
+        let positions = layout.fields.iter().map(|f| f.offset).collect::<Vec<_>>();
+        assert_eq!(positions, vec![0, 4, 5]);
+        match &table.get(&ScopedName::from("::Wide")).unwrap().symbol {
+            Symbol::Bitmask(layout) => assert_eq!(layout.width, 32),
+            symbol => panic!("unexpected symbol {:?}", symbol),
+        }
+    }
+
+    #[test]
+    fn bitmask_errors() {
+        let (_, errors) = table(
+            "
+            @bit_bound(65) bitmask A { X };
+            @bit_bound(4) bitmask B { X, @position(4) Y, @position(0) Z, x };
+            bitmask C { @position(1, 2) X };
+            ",
+        );
+        assert_eq!(
+            errors,
+            vec![
+                "the bit bound of bitmask `A` must be between 1 and 64, found 65",
+                "`Y` has position 4, which is outside of the 4 bits of bitmask `B`",
+                "`Z` has position 0, which is already the position of `X`",
+                "`x` collides with `X` declared in the same scope",
+                "@position takes exactly one parameter",
+            ]
+        );
+    }
+
+    #[test]
+    fn bitsets() {
+        let (table, errors) = table(
+            "
+            bitset Base { bitfield<3> version; };
+            bitset Header : Base {
+                bitfield<2>;
+                bitfield<1> a, b;
+                bitfield<12> id;
+                bitfield<4, long> small;
+            };
+            ",
+        );
+        assert!(errors.is_empty(), "{:?}", errors);
+        let layout = match &table.get(&ScopedName::from("::Header")).unwrap().symbol {
+            Symbol::Bitset(layout) => layout,
+            symbol => panic!("unexpected symbol {:?}", symbol),
+        };
+        assert_eq!(layout.width, 23);
+        let fields = layout
+            .fields
+            .iter()
+            .map(|f| {
+                let name = f.name.as_ref().map(|n| n.to_string());
+                (name, f.offset, f.width, f.ty)
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            fields,
+            vec![
+                (Some("version".to_string()), 0, 3, PrimitiveType::Octet),
+                (None, 3, 2, PrimitiveType::Octet),
+                (Some("a".to_string()), 5, 1, PrimitiveType::Boolean),
+                (Some("b".to_string()), 6, 1, PrimitiveType::Boolean),
+                (Some("id".to_string()), 7, 12, PrimitiveType::UnsignedShort),
+                (Some("small".to_string()), 19, 4, PrimitiveType::Long),
+            ]
+        );
+    }
+
+    #[test]
+    fn bitset_errors() {
+        let (_, errors) = table(
+            "
+            native N;
+            bitset A : N { bitfield<0> x; bitfield<9, octet> y; bitfield<1, float> z; };
+            bitset B : Missing { bitfield<40> x; bitfield<30> y; };
+            bitset C { bitfield<1> x; bitfield<1> X; };
+            ",
+        );
+        assert_eq!(
+            errors,
+            vec![
+                "`N` is a native type and cannot be the base of a bitset",
+                "the width of a bitfield must be between 1 and 64, found 0",
+                "a bitfield of 9 bits does not fit in octet",
+                "the type of a bitfield must be boolean, octet or an integer type",
+                "`Missing` does not name a type",
+                "bitset `B` is 70 bits wide, which exceeds the limit of 64 bits",
+                "`X` collides with `x` declared in the same scope",
+            ]
+        );
+    }
+
+    #[test]
+    fn typedefs() {
+        let (table, errors) = table(
+            "
+            enum Color { RED, GREEN };
+            typedef long Count;
+            module M { typedef Count Total, Grid[2][2]; };
+            typedef M::Total Sum;
+            typedef Color Shade;
+            const Sum S = 3;
+            const Shade C = GREEN;
+            const M::Grid G = 1;
+            const Sum T = RED;
+            ",
+        );
+        assert_eq!(
+            errors,
+            vec![
+                "`Grid` is a typedef of an array and cannot be the type of a constant",
+                "expected a value of type long, found enumerator value RED",
+            ]
+        );
+        assert_eq!(
+            table.get(&ScopedName::from("::Sum")).unwrap().symbol,
+            Symbol::Typedef(
+                Some(TypeSpec::Primitive(PrimitiveType::Long, 60..64)),
+                Vec::new()
+            )
+        );
+        assert_eq!(
+            table.get(&ScopedName::from("::S")).unwrap().symbol,
+            Symbol::Const(Some(ConstValue::Integer(3)))
+        );
+        match &table.get(&ScopedName::from("::C")).unwrap().symbol {
+            Symbol::Const(Some(ConstValue::Enumerator(ty, _, 1))) => {
+                assert_eq!(*ty, ScopedName::from("::Color"))
+            }
+            symbol => panic!("unexpected symbol {:?}", symbol),
+        }
+    }
+
+    #[test]
+    fn interfaces() {
+        let (table, errors) = table(
+            "
+            interface Base { const long SIZE = 4; void reset(); };
+            module M {
+                interface Derived : ::Base {
+                    const long TWICE = SIZE * 2;
+                    readonly attribute long count;
+                };
+            };
+            interface Later;
+            interface Later {};
+            ",
+        );
+        assert!(errors.is_empty(), "{:?}", errors);
+        assert_eq!(
+            table.get(&ScopedName::from("::M::Derived")).unwrap().symbol,
+            Symbol::Interface(None, vec![ScopedName::from("::Base")])
+        );
+        assert_eq!(
+            table
+                .get(&ScopedName::from("::M::Derived::TWICE"))
+                .unwrap()
+                .symbol,
+            Symbol::Const(Some(ConstValue::Integer(8)))
+        );
+        let scope = table.scope(ScopedName::from("::M::Derived"));
+        let (name, _) = scope.lookup(&ScopedName::from("reset")).unwrap();
+        assert_eq!(name, ScopedName::from("::Base::reset"));
+        assert_eq!(
+            table
+                .get(&ScopedName::from("::M::Derived::count"))
+                .unwrap()
+                .symbol,
+            Symbol::Attribute(true)
+        );
+        assert_eq!(
+            table.get(&ScopedName::from("::Later")).unwrap().symbol,
+            Symbol::Interface(None, Vec::new())
+        );
+    }
+
+    #[test]
+    fn interface_errors() {
+        let (_, errors) = table(
+            "
+            struct S { long x; };
+            interface Forward;
+            local interface L {};
+            abstract interface A { void op(); };
+            interface I { attribute long size; };
+            interface X : S, Forward, Missing, L, I, I {};
+            abstract interface Y : I {};
+            local interface Z : A, I { long op(); void size(); void other(); void OTHER(); };
+            ",
+        );
+        assert_eq!(
+            errors,
+            vec![
+                "`S` is a struct and cannot be the base of an interface",
+                "`Forward` is a forward declared interface and cannot be the base of an interface",
+                "`Missing` does not name a type",
+                "`L` is a local interface and cannot be the base of an interface",
+                "`I` is inherited more than once",
+                "`I` is an interface and cannot be the base of an abstract interface",
+                "`op` is already declared as an operation of `A` and cannot be redefined",
+                "`size` is already declared as an attribute of `I` and cannot be redefined",
+                "`OTHER` collides with `other` declared in the same scope",
+            ]
+        );
+    }
+
+    #[test]
+    fn value_types() {
+        let (table, errors) = table(
+            "
+            abstract interface Printable {};
+            interface Store {};
+            abstract valuetype Named { const long SIZE = 8; };
+            valuetype Base { public long id; };
+            valuetype Account : truncatable Base, Named supports Store, Printable {
+                private string<SIZE> owner;
+                factory open(in long id);
+            };
+            valuetype Later;
+            valuetype Later {};
+            ",
+        );
+        assert!(errors.is_empty(), "{:?}", errors);
+        assert_eq!(
+            table.get(&ScopedName::from("::Account")).unwrap().symbol,
+            Symbol::ValueType(
+                None,
+                vec![ScopedName::from("::Base"), ScopedName::from("::Named")]
+            )
+        );
+        let scope = table.scope(ScopedName::from("::Account"));
+        let (name, _) = scope.lookup(&ScopedName::from("SIZE")).unwrap();
+        assert_eq!(name, ScopedName::from("::Named::SIZE"));
+        let (name, declaration) = scope.lookup(&ScopedName::from("id")).unwrap();
+        assert_eq!(name, ScopedName::from("::Base::id"));
+        assert_eq!(declaration.symbol, Symbol::StateMember(Keyword::Public));
+        assert_eq!(
+            table
+                .get(&ScopedName::from("::Account::open"))
+                .unwrap()
+                .symbol,
+            Symbol::Factory
+        );
+        assert_eq!(
+            table.get(&ScopedName::from("::Later")).unwrap().symbol,
+            Symbol::ValueType(None, Vec::new())
+        );
+    }
+
+    #[test]
+    fn value_type_errors() {
+        let (_, errors) = table(
+            "
+            struct S { long x; };
+            interface I {};
+            interface J {};
+            abstract valuetype A {};
+            valuetype V { public long x; };
+            valuetype W {};
+            valuetype Forward;
+            valuetype B : A, V, S, Forward, Missing, A {};
+            abstract valuetype C : V {};
+            valuetype D : truncatable A {};
+            custom valuetype E : truncatable V {};
+            valuetype F supports I, J, I, S {};
+            valuetype G : V { public long x; private long y; void y(); };
+            ",
+        );
+        assert_eq!(
+            errors,
+            vec![
+                "`V` is a value type and can only be inherited as the first base of a value type",
+                "`S` is a struct and cannot be the base of a value type",
+                "`Forward` is a forward declared valuetype and cannot be the base of a value type",
+                "`Missing` does not name a type",
+                "`A` is inherited more than once",
+                "`V` is a value type and cannot be the base of an abstract value type",
+                "a truncatable value type must first inherit from a value type that is not abstract",
+                "a custom value type cannot be truncatable",
+                "`I` is supported more than once",
+                "`S` is a struct and cannot be supported by a value type",
+                "`J` cannot be supported along with `I`, a value type may support at most one interface that is not abstract",
+                "`x` is already declared as a public state member of `V` and cannot be redefined",
+                "`y` is already declared in this scope",
+            ]
+        );
+    }
+
+    #[test]
+    fn components() {
+        let (table, errors) = table(
+            "
+            interface Log {};
+            eventtype Alarm { public long level; };
+            abstract eventtype Tagged {};
+            eventtype Fire : Alarm, Tagged {};
+            valuetype Key { public long id; };
+            component Sensor;
+            component Base supports Log {
+                provides Log log;
+                emits Alarm alarm;
+            };
+            component Sensor : Base {
+                uses multiple Log sinks;
+                attribute long rate;
+            };
+            home BaseHome manages Base { void reset(); };
+            home SensorHome : BaseHome manages Sensor primarykey Key {
+                factory create(in long id);
+                finder find(in Key key);
+            };
+            ",
+        );
+        assert!(errors.is_empty(), "{:?}", errors);
+        let symbol = |name: &str| table.get(&ScopedName::from(name)).unwrap().symbol.clone();
+        assert_eq!(
+            symbol("::Fire"),
+            Symbol::EventType(
+                None,
+                vec![ScopedName::from("::Alarm"), ScopedName::from("::Tagged")]
+            )
+        );
+        assert_eq!(
+            symbol("::Sensor"),
+            Symbol::Component(Some(ScopedName::from("::Base")))
+        );
+        assert_eq!(symbol("::Sensor::sinks"), Symbol::Port(Keyword::Uses));
+        assert_eq!(
+            symbol("::SensorHome"),
+            Symbol::Home(Some(ScopedName::from("::BaseHome")))
+        );
+        assert_eq!(symbol("::SensorHome::create"), Symbol::Factory);
+        assert_eq!(symbol("::SensorHome::find"), Symbol::Finder);
+
+        let scope = table.scope(ScopedName::from("::Sensor"));
+        let (name, declaration) = scope.lookup(&ScopedName::from("alarm")).unwrap();
+        assert_eq!(name, ScopedName::from("::Base::alarm"));
+        assert_eq!(declaration.symbol.description(), "an event source");
+        let scope = table.scope(ScopedName::from("::SensorHome"));
+        let (name, _) = scope.lookup(&ScopedName::from("reset")).unwrap();
+        assert_eq!(name, ScopedName::from("::BaseHome::reset"));
+    }
+
+    #[test]
+    fn component_errors() {
+        let (_, errors) = table(
+            "
+            interface I {};
+            valuetype V {};
+            abstract valuetype A {};
+            eventtype E {};
+            eventtype F : V {};
+            eventtype G : A, E {};
+            component C supports I, V, I { provides I p; };
+            component D : I { uses I p; };
+            component H : C { consumes E p; };
+            home K : C manages C {};
+            ",
+        );
+        assert_eq!(
+            errors,
+            vec![
+                "`V` is a value type and cannot be the base of an event type",
+                "`E` is an event type and can only be inherited as the first base of an event type",
+                "`V` is a value type and cannot be supported by a component",
+                "`I` is supported more than once",
+                "`I` is an interface and cannot be the base of a component",
+                "`p` is already declared as a facet of `C` and cannot be redefined",
+                "`C` is a component and cannot be the base of a home",
+            ]
+        );
+    }
+
+    #[test]
+    fn connectors() {
+        let (table, errors) = table(
+            "
+            interface Reader {};
+            porttype Data;
+            porttype Data { provides Reader reader; attribute long depth; };
+            connector Base { port Data input; };
+            connector Pipe : Base { mirrorport Data output; };
+            connector Broken : Reader { port Data input; };
+            connector Other : Base { mirrorport Data input; };
+            ",
+        );
+        assert_eq!(
+            errors,
+            vec![
+                "`Reader` is an interface and cannot be the base of a connector",
+                "`input` is already declared as an extended port of `Base` and cannot be redefined",
+            ]
+        );
+        let symbol = |name: &str| table.get(&ScopedName::from(name)).unwrap().symbol.clone();
+        assert!(matches!(symbol("::Data"), Symbol::PortType(ports) if ports.len() == 1));
+        assert_eq!(symbol("::Data::depth"), Symbol::Attribute(false));
+        assert_eq!(
+            symbol("::Pipe"),
+            Symbol::Connector(Some(ScopedName::from("::Base")))
+        );
+        assert_eq!(symbol("::Pipe::output"), Symbol::Port(Keyword::MirrorPort));
+
+        let scope = table.scope(ScopedName::from("::Pipe"));
+        let (name, declaration) = scope.lookup(&ScopedName::from("input")).unwrap();
+        assert_eq!(name, ScopedName::from("::Base::input"));
+        assert_eq!(declaration.symbol.description(), "an extended port");
+    }
+
+    #[test]
+    fn constants() {
+        let (table, errors) = table(
+            "
+            const string S = \"a\" \"b\";
+            const fixed F = 1.50d;
+            typedef fixed<5, 2> Money;
+            const Money M = 123.45d;
+            const Money N = 123456.789d;
+            const Money P = 1.234d;
+            const octet O = 256;
+            const string T = 1;
+            const long L = UNKNOWN;
+            const S X = 1;
+            const Nothing Y = 1;
+            const long Z = O;
+            const long double D = 1e400;
+            const long double E = -D;
+            ",
+        );
+        assert_eq!(
+            errors,
+            vec![
+                "value 123456.789d is out of range for type fixed<5, 2>",
+                "value 1.234d is out of range for type fixed<5, 2>",
+                "value 256 is out of range for type octet",
+                "expected a value of type string, found integer value 1",
+                "`UNKNOWN` does not name a constant",
+                "`S` is a constant and cannot be the type of a constant",
+                "`Nothing` does not name a type",
+                "`O` does not name a constant",
+            ]
+        );
+        let value = |name: &str| table.get(&ScopedName::from(name)).unwrap().symbol.clone();
+        assert_eq!(
+            value("::S"),
+            Symbol::Const(Some(ConstValue::Str("ab".to_string())))
+        );
+        assert_eq!(value("::O"), Symbol::Const(None));
+        assert_eq!(
+            value("::E"),
+            Symbol::Const(Some(ConstValue::LongDouble("-1e400".to_string())))
+        );
+    }
+}
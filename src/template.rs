@@ -0,0 +1,1055 @@
+/**********************************************************************************
+ * Copyright © 2022 Michael Volling
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy of
+ * this software and associated documentation files (the “Software”), to deal in
+ * the Software without restriction, including without limitation the rights to
+ * use, copy, modify, merge, publish, distribute, sublicense, and/or sell copies of
+ * the Software, and to permit persons to whom the Software is furnished to do so,
+ * subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED “AS IS”, WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY, FITNESS
+ * FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE AUTHORS OR
+ * COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER
+ * IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN
+ * CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+ *********************************************************************************/
+
+use chumsky::prelude::*;
+
+use std::collections::{HashMap, HashSet};
+
+use crate::ast::{
+    ActualParameter, Annotation, AnnotationParam, Attribute, CaseLabel, ComponentExport, Const,
+    Declarator, Definition, Export, Factory, FormalKind, FormalParameter, HomeExport, Member,
+    Module, Param, Raises, Reference, Specification, TemplateDefinition, TemplateInstance,
+    TemplateModule, TypeSpec, ValueElement,
+};
+use crate::expr::ConstExpr;
+use crate::keyword::Keyword;
+use crate::name::{Identifier, ScopedName};
+use crate::scope::{Scope, Symbol, SymbolTable};
+
+impl Specification {
+    /// Expands every instance of a template module into a module holding the
+    /// definitions of the template module, with each formal parameter replaced by
+    /// the actual parameter given for it
+    ///
+    /// Each actual parameter is checked against the kind of its formal parameter.
+    /// Instances are expanded in the order they are declared, so that an instance
+    /// may use the definitions of the instances declared before it, and the
+    /// `alias` references within a template module are expanded along with it.
+    /// The template modules themselves are kept as they are.
+    ///
+    /// Example
+    ///
+    /// ```
+    /// use ox_idl::ast::{Definition, Specification, TypeSpec};
+    /// use ox_idl::name::ScopedName;
+    /// use chumsky::prelude::*;
+    ///
+    /// let spec = Specification::parser()
+    ///     .parse("struct Point { long x; }; module List<typename T> { typedef sequence<T> Items; }; module List<Point> Points;")
+    ///     .unwrap();
+    /// let spec = spec.expand().unwrap();
+    /// let Definition::Module(points) = &spec.definitions[2] else { panic!() };
+    /// let Definition::Typedef(items) = &points.definitions[0] else { panic!() };
+    /// let TypeSpec::Sequence(element, _, _) = &items.ty else { panic!() };
+    /// assert!(matches!(&**element, TypeSpec::Named(name, _) if *name == ScopedName::from("::Point")));
+    /// ```
+    pub fn expand(&self) -> Result<Specification, Vec<Simple<char>>> {
+        // 7.4.12.4
+        // A template module is instantiated by providing actual parameters in place
+        // of its formal parameters. The result is a concrete module, whose name is
+        // given by the instantiation.
+        let mut spec = self.clone();
+        let mut errors = Vec::new();
+        let mut expanded = Vec::new();
+        loop {
+            let (table, _) = SymbolTable::new(&spec);
+            let mut templates = HashMap::new();
+            collect_templates(&ScopedName::root(), &spec.definitions, &mut templates);
+            let mut expander = Expander {
+                table: &table,
+                templates,
+                expanded: &mut expanded,
+                errors: &mut errors,
+            };
+            if !expander.expand_next(&ScopedName::root(), &mut spec.definitions) {
+                break;
+            }
+        }
+
+        if errors.is_empty() {
+            Ok(spec)
+        } else {
+            Err(errors)
+        }
+    }
+}
+
+/// Records every template module declared within the scope `path` by its
+/// absolute name, including those declared within nested modules
+fn collect_templates(
+    path: &ScopedName,
+    definitions: &[Definition],
+    templates: &mut HashMap<ScopedName, TemplateModule>,
+) {
+    for definition in definitions {
+        match definition {
+            Definition::Module(module) => {
+                collect_templates(&path.child(&module.name), &module.definitions, templates)
+            }
+            Definition::TemplateModule(template) => {
+                templates.insert(path.child(&template.name), template.clone());
+            }
+            _ => {}
+        }
+    }
+}
+
+/// The Expander type expands the instances of template modules in a
+/// specification, one at a time
+struct Expander<'a> {
+    /// The symbol table of the specification as expanded so far
+    table: &'a SymbolTable,
+    templates: HashMap<ScopedName, TemplateModule>,
+    /// The absolute name of each module expanded so far, along with the absolute
+    /// name of the template module it is an instance of
+    expanded: &'a mut Vec<(ScopedName, ScopedName)>,
+    errors: &'a mut Vec<Simple<char>>,
+}
+
+impl Expander<'_> {
+    /// Expands the first instance of a template module declared within the scope
+    /// `path`, returning true if there was one to expand
+    ///
+    /// An instance that cannot be expanded is reported and removed.
+    fn expand_next(&mut self, path: &ScopedName, definitions: &mut Vec<Definition>) -> bool {
+        for index in 0..definitions.len() {
+            match &mut definitions[index] {
+                Definition::Module(module) => {
+                    let path = path.child(&module.name);
+                    if self.expand_next(&path, &mut module.definitions) {
+                        return true;
+                    }
+                }
+                Definition::TemplateInstance(instance) => {
+                    match self.instantiate(path, instance) {
+                        Ok(module) => definitions[index] = Definition::Module(module),
+                        Err(error) => {
+                            self.errors.push(error);
+                            definitions.remove(index);
+                        }
+                    }
+                    return true;
+                }
+                _ => {}
+            }
+        }
+        false
+    }
+
+    /// Expands an instance of a template module declared within the scope `path`
+    /// into a module
+    fn instantiate(
+        &mut self,
+        path: &ScopedName,
+        instance: &TemplateInstance,
+    ) -> Result<Module, Simple<char>> {
+        let reference = &instance.template;
+        let (name, template) = match self.table.lookup(path, &reference.name) {
+            Some((name, _)) if self.templates.contains_key(&name) => {
+                let template = &self.templates[&name];
+                (name, template)
+            }
+            Some((_, declaration)) => {
+                return Err(Simple::custom(
+                    reference.span.clone(),
+                    format!(
+                        "`{}` is {} and cannot be instantiated",
+                        reference.name,
+                        declaration.symbol.description()
+                    ),
+                ))
+            }
+            None => {
+                return Err(Simple::custom(
+                    reference.span.clone(),
+                    format!("`{}` does not name a template module", reference.name),
+                ))
+            }
+        };
+
+        // 7.4.12.4
+        // A template module cannot be instantiated within itself, directly or
+        // through the template modules it references.
+        let recursive = self
+            .expanded
+            .iter()
+            .any(|(module, expanded)| *expanded == name && path.parts.starts_with(&module.parts));
+        if recursive {
+            return Err(Simple::custom(
+                instance.span.clone(),
+                format!(
+                    "template module `{}` cannot be instantiated within itself",
+                    reference.name
+                ),
+            ));
+        }
+
+        if instance.params.len() != template.params.len() {
+            return Err(Simple::custom(
+                reference.span.clone(),
+                format!(
+                    "template module `{}` takes {} parameters, found {}",
+                    reference.name,
+                    template.params.len(),
+                    instance.params.len()
+                ),
+            ));
+        }
+
+        // The actual parameters are resolved where the template module is
+        // instantiated, and the names within the template module where it is
+        // declared. Both are made absolute before the definitions are placed in
+        // the instance, except for the names declared by the template module
+        // itself, which are declared again within the instance.
+        let scope = self.table.scope(path.clone());
+        let declared = ScopedName {
+            absolute: true,
+            parts: name.parts[..name.parts.len() - 1].to_vec(),
+        };
+        let mut params = Params {
+            actual: HashMap::new(),
+            local: HashSet::new(),
+            scope: self.table.scope(name.clone()),
+        };
+        for (formal, actual) in template.params.iter().zip(&instance.params) {
+            let actual = self.check_param(&scope, &declared, formal, actual)?;
+            params
+                .actual
+                .insert(formal.name.as_str().to_string(), actual);
+        }
+        for definition in &template.definitions {
+            match definition {
+                TemplateDefinition::Definition(definition) => {
+                    declared_names(std::slice::from_ref(definition), &mut params.local)
+                }
+                TemplateDefinition::Alias(alias) => {
+                    params.local.insert(alias.name.as_str().to_string());
+                }
+            }
+        }
+
+        let mut definitions = Vec::new();
+        for definition in &template.definitions {
+            match definition {
+                TemplateDefinition::Definition(definition) => {
+                    let mut definition = definition.clone();
+                    definition.substitute(&params);
+                    definitions.push(definition);
+                }
+                TemplateDefinition::Alias(alias) => {
+                    let template = match self.table.lookup(&declared, &alias.template.name) {
+                        Some((name, _)) => Reference {
+                            name,
+                            span: alias.template.span.clone(),
+                        },
+                        None => alias.template.clone(),
+                    };
+                    let params = alias
+                        .params
+                        .iter()
+                        .map(|param| {
+                            params.actual.get(param.as_str()).cloned().ok_or_else(|| {
+                                Simple::custom(
+                                    alias.span.clone(),
+                                    format!(
+                                        "`{}` is not a parameter of template module `{}`",
+                                        param, reference.name
+                                    ),
+                                )
+                            })
+                        })
+                        .collect::<Result<_, _>>()?;
+                    definitions.push(Definition::TemplateInstance(TemplateInstance {
+                        annotations: Vec::new(),
+                        template,
+                        params,
+                        name: alias.name.clone(),
+                        span: alias.span.clone(),
+                    }));
+                }
+            }
+        }
+
+        self.expanded.push((path.child(&instance.name), name));
+        Ok(Module {
+            annotations: instance.annotations.clone(),
+            name: instance.name.clone(),
+            definitions,
+            span: instance.span.clone(),
+        })
+    }
+
+    /// Checks that an actual parameter given within `scope` is of the kind of its
+    /// formal parameter, returning it with every name it references made absolute.
+    /// `declared` is the scope the template module is declared within.
+    fn check_param(
+        &self,
+        scope: &Scope,
+        declared: &ScopedName,
+        formal: &FormalParameter,
+        actual: &ActualParameter,
+    ) -> Result<ActualParameter, Simple<char>> {
+        // 7.4.12.4
+        // Each actual parameter must be consistent with the kind of the formal
+        // parameter it is given for.
+        let ty = match (&formal.kind, actual) {
+            (FormalKind::Const(ty), actual) => {
+                let value = match actual {
+                    ActualParameter::Const(value) => absolute_expr(scope, value),
+                    ActualParameter::Type(TypeSpec::Named(name, span)) => {
+                        absolute_expr(scope, &ConstExpr::Name(name.clone(), span.clone()))
+                    }
+                    ActualParameter::Type(ty) => {
+                        return Err(Simple::custom(
+                            ty.span(),
+                            format!("`{}` requires a constant, found a type", formal.name),
+                        ))
+                    }
+                };
+                let constant = Const {
+                    annotations: Vec::new(),
+                    name: formal.name.clone(),
+                    ty: ty.clone(),
+                    value,
+                    span: actual.span(),
+                };
+                self.table.evaluate(declared, &constant)?;
+                return Ok(ActualParameter::Const(constant.value));
+            }
+            (_, ActualParameter::Const(value)) => {
+                return Err(Simple::custom(
+                    value.span(),
+                    format!("`{}` requires a type, found a constant", formal.name),
+                ))
+            }
+            (_, ActualParameter::Type(ty)) => absolute_type(scope, ty)?,
+        };
+
+        let (keyword, expected) = match &formal.kind {
+            FormalKind::Type(Keyword::TypeName) => (None, "a type"),
+            FormalKind::Type(Keyword::Interface) => (Some(Keyword::Interface), "an interface"),
+            FormalKind::Type(Keyword::ValueType) => (Some(Keyword::ValueType), "a value type"),
+            FormalKind::Type(Keyword::EventType) => (Some(Keyword::EventType), "an event type"),
+            FormalKind::Type(Keyword::Struct) => (Some(Keyword::Struct), "a struct"),
+            FormalKind::Type(Keyword::Union) => (Some(Keyword::Union), "a union"),
+            FormalKind::Type(Keyword::Exception) => (Some(Keyword::Exception), "an exception"),
+            FormalKind::Type(Keyword::Enum) => (Some(Keyword::Enum), "an enum"),
+            _ => (Some(Keyword::Sequence), "a sequence"),
+        };
+        let resolved = scope.resolve_type(&ty)?;
+        let symbol = match &resolved {
+            TypeSpec::Named(name, _) => self.table.get(name).map(|d| &d.symbol),
+            _ => None,
+        };
+        let matches = match (&keyword, symbol) {
+            (None, Some(symbol)) => symbol.is_type(),
+            (None, None) => true,
+            (Some(Keyword::Sequence), _) => matches!(resolved, TypeSpec::Sequence(_, _, _)),
+            (Some(keyword), Some(Symbol::Forward(forward))) => keyword == forward,
+            (Some(Keyword::Interface), Some(Symbol::Interface(_, _)))
+            | (Some(Keyword::ValueType), Some(Symbol::ValueType(_, _) | Symbol::EventType(_, _)))
+            | (Some(Keyword::EventType), Some(Symbol::EventType(_, _)))
+            | (Some(Keyword::Struct), Some(Symbol::Struct(_, _)))
+            | (Some(Keyword::Union), Some(Symbol::Union))
+            | (Some(Keyword::Exception), Some(Symbol::Exception))
+            | (Some(Keyword::Enum), Some(Symbol::Enum(_))) => true,
+            _ => false,
+        };
+        if matches {
+            return Ok(ActualParameter::Type(ty));
+        }
+
+        let message = match (&ty, symbol) {
+            (TypeSpec::Named(name, _), Some(symbol)) => format!(
+                "`{}` requires {}, `{}` is {}",
+                formal.name,
+                expected,
+                name.name(),
+                symbol.description()
+            ),
+            _ => format!("`{}` requires {}", formal.name, expected),
+        };
+        Err(Simple::custom(ty.span(), message))
+    }
+}
+
+/// Returns a type referenced from within `scope` with every name it references
+/// made absolute, or reports the first name that does not name a type
+fn absolute_type(scope: &Scope, ty: &TypeSpec) -> Result<TypeSpec, Simple<char>> {
+    let bound = |bound: &Option<ConstExpr>| bound.as_ref().map(|b| absolute_expr(scope, b));
+    Ok(match ty {
+        TypeSpec::Named(name, span) => match scope.lookup(name) {
+            Some((name, declaration)) if declaration.symbol.is_type() => {
+                TypeSpec::Named(name, span.clone())
+            }
+            Some((_, declaration)) => {
+                return Err(Simple::custom(
+                    span.clone(),
+                    format!(
+                        "`{}` is {} and does not name a type",
+                        name,
+                        declaration.symbol.description()
+                    ),
+                ))
+            }
+            None => {
+                return Err(Simple::custom(
+                    span.clone(),
+                    format!("`{}` does not name a type", name),
+                ))
+            }
+        },
+        TypeSpec::String(b, span) => TypeSpec::String(bound(b), span.clone()),
+        TypeSpec::WString(b, span) => TypeSpec::WString(bound(b), span.clone()),
+        TypeSpec::Sequence(element, b, span) => TypeSpec::Sequence(
+            Box::new(absolute_type(scope, element)?),
+            bound(b),
+            span.clone(),
+        ),
+        TypeSpec::Map(key, value, b, span) => TypeSpec::Map(
+            Box::new(absolute_type(scope, key)?),
+            Box::new(absolute_type(scope, value)?),
+            bound(b),
+            span.clone(),
+        ),
+        ty => ty.clone(),
+    })
+}
+
+/// Returns a constant expression referenced from within `scope` with every name it
+/// references made absolute. Names that are not declared are left as they are.
+fn absolute_expr(scope: &Scope, expr: &ConstExpr) -> ConstExpr {
+    match expr {
+        ConstExpr::Name(name, span) => match scope.lookup(name) {
+            Some((name, _)) => ConstExpr::Name(name, span.clone()),
+            None => expr.clone(),
+        },
+        ConstExpr::Unary(op, operand, span) => {
+            ConstExpr::Unary(*op, Box::new(absolute_expr(scope, operand)), span.clone())
+        }
+        ConstExpr::Binary(op, left, right, span) => ConstExpr::Binary(
+            *op,
+            Box::new(absolute_expr(scope, left)),
+            Box::new(absolute_expr(scope, right)),
+            span.clone(),
+        ),
+        ConstExpr::Literal(_, _) => expr.clone(),
+    }
+}
+
+/// Records the name of each definition declared within `definitions`, including
+/// those declared within nested modules, interfaces and value types
+fn declared_names(definitions: &[Definition], names: &mut HashSet<String>) {
+    for definition in definitions {
+        let (declared, nested): (Vec<&Identifier>, Vec<Definition>) = match definition {
+            Definition::Module(m) => (vec![&m.name], m.definitions.clone()),
+            Definition::TemplateModule(t) => (vec![&t.name], Vec::new()),
+            Definition::TemplateInstance(i) => (vec![&i.name], Vec::new()),
+            Definition::Const(c) => (vec![&c.name], Vec::new()),
+            Definition::Typedef(t) => (t.declarators.iter().map(|d| &d.name).collect(), Vec::new()),
+            Definition::Struct(s) => (vec![&s.name], Vec::new()),
+            Definition::Union(u) => (vec![&u.name], Vec::new()),
+            // Enumerators are declared in the scope enclosing their enum
+            Definition::Enum(e) => {
+                let enumerators = e.enumerators.iter().map(|e| &e.name);
+                (
+                    std::iter::once(&e.name).chain(enumerators).collect(),
+                    Vec::new(),
+                )
+            }
+            Definition::Bitmask(b) => (vec![&b.name], Vec::new()),
+            Definition::Bitset(b) => (vec![&b.name], Vec::new()),
+            Definition::Native(n) => (vec![&n.name], Vec::new()),
+            Definition::Interface(i) => (vec![&i.name], exported_definitions(i.exports.iter())),
+            Definition::Exception(e) => (vec![&e.name], Vec::new()),
+            Definition::ValueType(v) => {
+                let exports = v.elements.iter().filter_map(|e| match e {
+                    ValueElement::Export(export) => Some(export),
+                    _ => None,
+                });
+                (vec![&v.name], exported_definitions(exports))
+            }
+            Definition::ValueBox(b) => (vec![&b.name], Vec::new()),
+            Definition::Component(c) => (vec![&c.name], Vec::new()),
+            Definition::Home(h) => (vec![&h.name], Vec::new()),
+            Definition::PortType(p) => (vec![&p.name], Vec::new()),
+            Definition::Connector(c) => (vec![&c.name], Vec::new()),
+            Definition::Forward(f) => (vec![&f.name], Vec::new()),
+        };
+        names.extend(declared.into_iter().map(|name| name.as_str().to_string()));
+        declared_names(&nested, names);
+    }
+}
+
+/// Returns the definitions among the exports of an interface or value type
+fn exported_definitions<'e>(exports: impl Iterator<Item = &'e Export>) -> Vec<Definition> {
+    exports
+        .filter_map(|e| match e {
+            Export::Definition(definition) => Some(definition.clone()),
+            _ => None,
+        })
+        .collect()
+}
+
+/// What the names within the definitions of a template module are replaced with
+/// when it is instantiated
+struct Params<'a> {
+    /// The actual parameters, keyed by the name of their formal parameter
+    actual: HashMap<String, ActualParameter>,
+    /// The names declared by the template module itself, which are left as they
+    /// are to refer to the definitions of the instance
+    local: HashSet<String>,
+    /// The scope of the template module, from which any other name is resolved
+    scope: Scope<'a>,
+}
+
+/// Returns the actual parameter a name refers to, if it is the name of a formal
+/// parameter
+fn formal<'p>(params: &'p Params, name: &ScopedName) -> Option<&'p ActualParameter> {
+    match name.parts.as_slice() {
+        [part] if !name.absolute => params.actual.get(part),
+        _ => None,
+    }
+}
+
+/// Returns the absolute name of a name referenced within a template module, if it
+/// names a declaration outside of the template module
+fn absolute(params: &Params, name: &ScopedName) -> Option<ScopedName> {
+    match name.parts.first() {
+        _ if name.absolute => None,
+        Some(first) if params.local.contains(first) => None,
+        _ => params.scope.lookup(name).map(|(name, _)| name),
+    }
+}
+
+/// The Substitute trait replaces the references to the formal parameters of a
+/// template module with their actual parameters
+trait Substitute {
+    fn substitute(&mut self, params: &Params);
+}
+
+impl<T: Substitute> Substitute for Vec<T> {
+    fn substitute(&mut self, params: &Params) {
+        self.iter_mut().for_each(|item| item.substitute(params));
+    }
+}
+
+impl<T: Substitute> Substitute for Option<T> {
+    fn substitute(&mut self, params: &Params) {
+        if let Some(item) = self {
+            item.substitute(params);
+        }
+    }
+}
+
+impl<T: Substitute> Substitute for Box<T> {
+    fn substitute(&mut self, params: &Params) {
+        self.as_mut().substitute(params);
+    }
+}
+
+impl Substitute for ConstExpr {
+    fn substitute(&mut self, params: &Params) {
+        match self {
+            ConstExpr::Name(name, _) => {
+                if let Some(ActualParameter::Const(value)) = formal(params, name) {
+                    *self = value.clone();
+                } else if let Some(absolute) = absolute(params, name) {
+                    *name = absolute;
+                }
+            }
+            ConstExpr::Unary(_, operand, _) => operand.substitute(params),
+            ConstExpr::Binary(_, left, right, _) => {
+                left.substitute(params);
+                right.substitute(params);
+            }
+            ConstExpr::Literal(_, _) => {}
+        }
+    }
+}
+
+impl Substitute for TypeSpec {
+    fn substitute(&mut self, params: &Params) {
+        match self {
+            TypeSpec::Named(name, _) => {
+                if let Some(ActualParameter::Type(ty)) = formal(params, name) {
+                    *self = ty.clone();
+                } else if let Some(absolute) = absolute(params, name) {
+                    *name = absolute;
+                }
+            }
+            TypeSpec::String(bound, _) | TypeSpec::WString(bound, _) => bound.substitute(params),
+            TypeSpec::Fixed(Some((digits, scale)), _) => {
+                digits.substitute(params);
+                scale.substitute(params);
+            }
+            TypeSpec::Sequence(element, bound, _) => {
+                element.substitute(params);
+                bound.substitute(params);
+            }
+            TypeSpec::Map(key, value, bound, _) => {
+                key.substitute(params);
+                value.substitute(params);
+                bound.substitute(params);
+            }
+            _ => {}
+        }
+    }
+}
+
+impl Substitute for Reference {
+    fn substitute(&mut self, params: &Params) {
+        if let Some(ActualParameter::Type(TypeSpec::Named(name, _))) = formal(params, &self.name) {
+            self.name = name.clone();
+        } else if let Some(absolute) = absolute(params, &self.name) {
+            self.name = absolute;
+        }
+    }
+}
+
+impl Substitute for ActualParameter {
+    fn substitute(&mut self, params: &Params) {
+        match self {
+            ActualParameter::Type(ty) => ty.substitute(params),
+            ActualParameter::Const(value) => value.substitute(params),
+        }
+    }
+}
+
+impl Substitute for Annotation {
+    fn substitute(&mut self, params: &Params) {
+        self.params.substitute(params);
+    }
+}
+
+impl Substitute for AnnotationParam {
+    fn substitute(&mut self, params: &Params) {
+        self.value.substitute(params);
+    }
+}
+
+impl Substitute for Declarator {
+    fn substitute(&mut self, params: &Params) {
+        self.dimensions.substitute(params);
+    }
+}
+
+impl Substitute for Member {
+    fn substitute(&mut self, params: &Params) {
+        self.annotations.substitute(params);
+        self.ty.substitute(params);
+        self.declarators.substitute(params);
+    }
+}
+
+impl Substitute for Param {
+    fn substitute(&mut self, params: &Params) {
+        self.annotations.substitute(params);
+        self.ty.substitute(params);
+    }
+}
+
+impl Substitute for Raises {
+    fn substitute(&mut self, params: &Params) {
+        self.exceptions.substitute(params);
+    }
+}
+
+impl Substitute for Attribute {
+    fn substitute(&mut self, params: &Params) {
+        self.annotations.substitute(params);
+        self.ty.substitute(params);
+        self.get_raises.substitute(params);
+        self.set_raises.substitute(params);
+    }
+}
+
+impl Substitute for Factory {
+    fn substitute(&mut self, params: &Params) {
+        self.annotations.substitute(params);
+        self.params.substitute(params);
+        self.raises.substitute(params);
+    }
+}
+
+impl Substitute for Export {
+    fn substitute(&mut self, params: &Params) {
+        match self {
+            Export::Operation(o) => {
+                o.annotations.substitute(params);
+                o.return_type.substitute(params);
+                o.params.substitute(params);
+                o.raises.substitute(params);
+            }
+            Export::Attribute(a) => a.substitute(params),
+            Export::Definition(d) => d.substitute(params),
+        }
+    }
+}
+
+impl Substitute for ComponentExport {
+    fn substitute(&mut self, params: &Params) {
+        match self {
+            ComponentExport::Port(p) => {
+                p.annotations.substitute(params);
+                p.ty.substitute(params);
+            }
+            ComponentExport::Attribute(a) => a.substitute(params),
+        }
+    }
+}
+
+impl Substitute for Definition {
+    fn substitute(&mut self, params: &Params) {
+        match self {
+            Definition::Module(m) => {
+                m.annotations.substitute(params);
+                m.definitions.substitute(params);
+            }
+            // The definitions of a nested template module are substituted when it is
+            // instantiated
+            Definition::TemplateModule(t) => t.annotations.substitute(params),
+            Definition::TemplateInstance(i) => {
+                i.annotations.substitute(params);
+                i.template.substitute(params);
+                i.params.substitute(params);
+            }
+            Definition::Const(c) => {
+                c.annotations.substitute(params);
+                c.ty.substitute(params);
+                c.value.substitute(params);
+            }
+            Definition::Typedef(t) => {
+                t.annotations.substitute(params);
+                t.ty.substitute(params);
+                t.declarators.substitute(params);
+            }
+            Definition::Struct(s) => {
+                s.annotations.substitute(params);
+                s.base.substitute(params);
+                s.members.substitute(params);
+            }
+            Definition::Union(u) => {
+                u.annotations.substitute(params);
+                u.discriminator.substitute(params);
+                for case in &mut u.cases {
+                    for label in &mut case.labels {
+                        if let CaseLabel::Value(value) = label {
+                            value.substitute(params);
+                        }
+                    }
+                    case.annotations.substitute(params);
+                    case.ty.substitute(params);
+                    case.declarator.substitute(params);
+                }
+            }
+            Definition::Enum(e) => {
+                e.annotations.substitute(params);
+                for enumerator in &mut e.enumerators {
+                    enumerator.annotations.substitute(params);
+                }
+            }
+            Definition::Bitmask(b) => {
+                b.annotations.substitute(params);
+                for value in &mut b.values {
+                    value.annotations.substitute(params);
+                }
+            }
+            Definition::Bitset(b) => {
+                b.annotations.substitute(params);
+                b.base.substitute(params);
+                for bitfield in &mut b.bitfields {
+                    bitfield.annotations.substitute(params);
+                    bitfield.width.substitute(params);
+                    bitfield.ty.substitute(params);
+                }
+            }
+            Definition::Native(n) => n.annotations.substitute(params),
+            Definition::Interface(i) => {
+                i.annotations.substitute(params);
+                i.bases.substitute(params);
+                i.exports.substitute(params);
+            }
+            Definition::Exception(e) => {
+                e.annotations.substitute(params);
+                e.members.substitute(params);
+            }
+            Definition::ValueType(v) => {
+                v.annotations.substitute(params);
+                v.bases.substitute(params);
+                v.supports.substitute(params);
+                for element in &mut v.elements {
+                    match element {
+                        ValueElement::Export(e) => e.substitute(params),
+                        ValueElement::State(s) => {
+                            s.annotations.substitute(params);
+                            s.ty.substitute(params);
+                            s.declarators.substitute(params);
+                        }
+                        ValueElement::Factory(f) => f.substitute(params),
+                    }
+                }
+            }
+            Definition::ValueBox(b) => {
+                b.annotations.substitute(params);
+                b.ty.substitute(params);
+            }
+            Definition::Component(c) => {
+                c.annotations.substitute(params);
+                c.base.substitute(params);
+                c.supports.substitute(params);
+                c.exports.substitute(params);
+            }
+            Definition::Home(h) => {
+                h.annotations.substitute(params);
+                h.base.substitute(params);
+                h.supports.substitute(params);
+                h.manages.substitute(params);
+                h.primary_key.substitute(params);
+                for export in &mut h.exports {
+                    match export {
+                        HomeExport::Export(e) => e.substitute(params),
+                        HomeExport::Factory(f) => f.substitute(params),
+                    }
+                }
+            }
+            Definition::PortType(p) => {
+                p.annotations.substitute(params);
+                p.exports.substitute(params);
+            }
+            Definition::Connector(c) => {
+                c.annotations.substitute(params);
+                c.base.substitute(params);
+                c.exports.substitute(params);
+            }
+            Definition::Forward(f) => f.annotations.substitute(params),
+        }
+    }
+}
+
+#[cfg(test)]
+mod template_tests {
+    use crate::ast::{Definition, Module, Specification, TypeSpec};
+    use crate::expr::{ConstExpr, ConstValue};
+    use crate::name::ScopedName;
+    use crate::scope::Symbol;
+    use crate::test_utils::{messages, parse};
+
+    fn expand(source: &str) -> Result<Specification, Vec<String>> {
+        parse(source).expand().map_err(|errors| messages(&errors))
+    }
+
+    fn module<'a>(definitions: &'a [Definition], name: &str) -> &'a Module {
+        definitions
+            .iter()
+            .find_map(|d| match d {
+                Definition::Module(m) if m.name.as_str() == name => Some(m),
+                _ => None,
+            })
+            .unwrap()
+    }
+
+    #[test]
+    fn substitute_params() {
+        let source = "
+            struct Point { long x; };
+            const long SIZE = 4;
+            module Tmpl<typename T, struct S, const long N, sequence Q> {
+                typedef T Array[N];
+                struct Wrapper : S { Q items; T extra[N + 1]; };
+                const long TWICE = N * 2;
+            };
+            module M {
+                typedef sequence<Point> Points;
+                module ::Tmpl<string, Point, SIZE, Points> Inst;
+            };
+            ";
+        let spec = expand(source).unwrap();
+        let inst = module(&module(&spec.definitions, "M").definitions, "Inst");
+        let Definition::Typedef(array) = &inst.definitions[0] else {
+            panic!("expected a typedef");
+        };
+        assert!(matches!(array.ty, TypeSpec::String(None, _)));
+        assert!(matches!(
+            &array.declarators[0].dimensions[0],
+            ConstExpr::Name(name, _) if *name == ScopedName::from("::SIZE")
+        ));
+        let Definition::Struct(wrapper) = &inst.definitions[1] else {
+            panic!("expected a struct");
+        };
+        assert_eq!(
+            wrapper.base.as_ref().unwrap().name,
+            ScopedName::from("::Point")
+        );
+        assert!(matches!(
+            &wrapper.members[0].ty,
+            TypeSpec::Named(name, _) if *name == ScopedName::from("::M::Points")
+        ));
+
+        let table = parse(source).validate().unwrap();
+        assert_eq!(
+            table
+                .get(&ScopedName::from("::M::Inst::TWICE"))
+                .unwrap()
+                .symbol,
+            Symbol::Const(Some(ConstValue::Integer(8)))
+        );
+        assert_eq!(
+            table.get(&ScopedName::from("::Tmpl")).unwrap().symbol,
+            Symbol::TemplateModule
+        );
+        assert!(table.get(&ScopedName::from("::Tmpl::Array")).is_none());
+    }
+
+    #[test]
+    fn expand_aliases() {
+        let spec = expand(
+            "
+            module Inner<typename T, const long N> { typedef sequence<T, N> Bounded; };
+            module Outer<typename U, const long M> {
+                alias Inner<U, M> Nested;
+                typedef U Single;
+            };
+            module Outer<long, 3> Longs;
+            module Outer<Longs::Single, 4> Again;
+            ",
+        )
+        .unwrap();
+        let nested = module(&module(&spec.definitions, "Again").definitions, "Nested");
+        let Definition::Typedef(bounded) = &nested.definitions[0] else {
+            panic!("expected a typedef");
+        };
+        let TypeSpec::Sequence(element, Some(bound), _) = &bounded.ty else {
+            panic!("expected a bounded sequence");
+        };
+        assert!(matches!(
+            &**element,
+            TypeSpec::Named(name, _) if *name == ScopedName::from("::Longs::Single")
+        ));
+        assert!(matches!(bound, ConstExpr::Literal(_, _)));
+
+        assert_eq!(
+            expand("module R<typename T> { alias R<T> Again; }; module R<long> X;").unwrap_err(),
+            vec!["template module `::R` cannot be instantiated within itself"]
+        );
+        assert_eq!(
+            expand("module A<typename T> { alias B<X> Y; }; module B<typename T> { native N; }; module A<long> Z;")
+                .unwrap_err(),
+            vec!["`X` is not a parameter of template module `A`"]
+        );
+    }
+
+    #[test]
+    fn check_params() {
+        let errors = expand(
+            "
+            struct S { long x; };
+            interface I {};
+            union U switch (long) { case 1: long a; };
+            typedef S Same;
+            const long C = 1;
+            module T<typename A, struct B, const short N, sequence Q, interface J> { native X; };
+            module T<long, Same, 1, sequence<long>, I> Good;
+            module T<C, S, 1, sequence<long>, I> Bad1;
+            module T<long, U, 1, sequence<long>, I> Bad2;
+            module T<long, S, 70000, sequence<long>, I> Bad3;
+            module T<long, S, long, sequence<long>, I> Bad4;
+            module T<long, S, 1, 5, I> Bad5;
+            module T<long, S, 1, string, I> Bad6;
+            module T<long, S, 1, sequence<long>, S> Bad7;
+            module T<long> Bad8;
+            module S<long> Bad9;
+            module Missing<long> Bad10;
+            module T<Nothing, S, 1, sequence<long>, I> Bad11;
+            ",
+        )
+        .unwrap_err();
+        assert_eq!(
+            errors,
+            vec![
+                "`C` is a constant and does not name a type",
+                "`B` requires a struct, `U` is a union",
+                "value 70000 is out of range for type short",
+                "`N` requires a constant, found a type",
+                "`Q` requires a type, found a constant",
+                "`Q` requires a sequence",
+                "`J` requires an interface, `S` is a struct",
+                "template module `T` takes 5 parameters, found 1",
+                "`S` is a struct and cannot be instantiated",
+                "`Missing` does not name a template module",
+                "`Nothing` does not name a type",
+            ]
+        );
+    }
+
+    #[test]
+    fn validate_instances() {
+        let errors = parse("module T<const long N> { typedef long Items[N]; }; module T<0> Empty;")
+            .validate()
+            .unwrap_err();
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].span(), 60..61);
+    }
+
+    #[test]
+    fn resolve_template_names() {
+        // The names within a template module are resolved where it is declared,
+        // unless they name one of its own definitions
+        let source = "
+            module X {
+                struct P { long a; };
+                const long SIZE = 2;
+                module T<typename A> {
+                    typedef P Q;
+                    struct R : P { A b[SIZE]; };
+                    enum Kind { FIRST, SECOND };
+                    typedef Q Pair[SIZE];
+                    const Kind K = FIRST;
+                };
+            };
+            struct P { string c; };
+            module X::T<long> Inst;
+        ";
+        let spec = expand(source).unwrap();
+        let inst = module(&spec.definitions, "Inst");
+        let Definition::Typedef(q) = &inst.definitions[0] else {
+            panic!("expected a typedef");
+        };
+        assert!(matches!(&q.ty, TypeSpec::Named(name, _) if *name == ScopedName::from("::X::P")));
+        let Definition::Typedef(pair) = &inst.definitions[3] else {
+            panic!("expected a typedef");
+        };
+        assert!(matches!(&pair.ty, TypeSpec::Named(name, _) if *name == ScopedName::from("Q")));
+        assert_eq!(
+            pair.declarators[0].dimensions[0],
+            ConstExpr::Name(ScopedName::from("::X::SIZE"), 304..308)
+        );
+
+        let table = parse(source).validate().unwrap();
+        assert_eq!(
+            table.get(&ScopedName::from("::Inst::Q")).unwrap().symbol,
+            Symbol::Typedef(
+                Some(TypeSpec::Named(ScopedName::from("::X::P"), 166..167)),
+                vec![]
+            )
+        );
+    }
+}
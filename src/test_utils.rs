@@ -0,0 +1,52 @@
+/**********************************************************************************
+ * Copyright © 2022 Michael Volling
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy of
+ * this software and associated documentation files (the “Software”), to deal in
+ * the Software without restriction, including without limitation the rights to
+ * use, copy, modify, merge, publish, distribute, sublicense, and/or sell copies of
+ * the Software, and to permit persons to whom the Software is furnished to do so,
+ * subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED “AS IS”, WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY, FITNESS
+ * FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE AUTHORS OR
+ * COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER
+ * IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN
+ * CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+ *********************************************************************************/
+
+//! Helpers shared by the unit tests of the crate
+
+use chumsky::error::SimpleReason;
+use chumsky::prelude::*;
+
+use std::ops::Range;
+
+use crate::ast::Specification;
+
+/// Returns the message of a semantic error, which is always a custom error
+pub fn message(error: &Simple<char>) -> String {
+    match error.reason() {
+        SimpleReason::Custom(message) => message.clone(),
+        reason => panic!("unexpected error {:?}", reason),
+    }
+}
+
+/// Parses a specification that is expected to be syntactically valid
+pub fn parse(source: &str) -> Specification {
+    Specification::parser().parse(source).unwrap()
+}
+
+/// Returns the message of each of the errors
+pub fn messages(errors: &[Simple<char>]) -> Vec<String> {
+    errors.iter().map(message).collect()
+}
+
+/// Returns the message and span of each of the errors
+pub fn diagnostics(errors: &[Simple<char>]) -> Vec<(String, Range<usize>)> {
+    errors.iter().map(|e| (message(e), e.span())).collect()
+}
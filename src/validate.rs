@@ -0,0 +1,1686 @@
+/**********************************************************************************
+ * Copyright © 2022 Michael Volling
+ *
+ * Permission is hereby granted, free of charge, to any person obtaining a copy of
+ * this software and associated documentation files (the “Software”), to deal in
+ * the Software without restriction, including without limitation the rights to
+ * use, copy, modify, merge, publish, distribute, sublicense, and/or sell copies of
+ * the Software, and to permit persons to whom the Software is furnished to do so,
+ * subject to the following conditions:
+ *
+ * The above copyright notice and this permission notice shall be included in all
+ * copies or substantial portions of the Software.
+ *
+ * THE SOFTWARE IS PROVIDED “AS IS”, WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+ * IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY, FITNESS
+ * FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE AUTHORS OR
+ * COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER
+ * IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN
+ * CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+ *********************************************************************************/
+
+use chumsky::prelude::*;
+
+use std::collections::HashMap;
+use std::fmt::Display;
+use std::ops::Range;
+
+use crate::annotation::{check_annotations, Target};
+use crate::ast::{
+    Annotation, Attribute, CaseLabel, ComponentExport, Declarator, Definition, Export, Factory,
+    Home, HomeExport, Interface, Member, Module, Operation, Port, PortType, Raises, Specification,
+    TypeSpec, Union, ValueBox, ValueElement, ValueType,
+};
+use crate::expr::{ConstExpr, ConstValue};
+use crate::keyword::Keyword;
+use crate::name::{Identifier, ScopedName};
+use crate::primitive::PrimitiveType;
+use crate::scope::{Declaration, Scope, Symbol, SymbolTable};
+
+impl Specification {
+    /// Checks that the specification is semantically valid, returning its symbol
+    /// table if it is or every problem found if it is not
+    ///
+    /// The instances of template modules are expanded first, and the definitions of
+    /// a template module are only checked within each of its instances. The built-in
+    /// annotations are checked against their definitions in [`crate::annotation`],
+    /// while any other annotation is accepted as it is.
+    ///
+    /// Example
+    ///
+    /// ```
+    /// use ox_idl::ast::Specification;
+    /// use chumsky::prelude::*;
+    ///
+    /// let spec = Specification::parser()
+    ///     .parse("union U switch (boolean) { case TRUE: long a; case TRUE: long b; };")
+    ///     .unwrap();
+    /// let errors = spec.validate().unwrap_err();
+    /// assert_eq!(errors.len(), 1);
+    /// ```
+    pub fn validate(&self) -> Result<SymbolTable, Vec<Simple<char>>> {
+        self.validate_expanded().map(|(_, table)| table)
+    }
+
+    /// Checks that the specification is semantically valid as [`Self::validate`]
+    /// does, returning the specification with its template instances expanded and
+    /// each reopened module merged by [`Module::merge`], so that every module is
+    /// defined once, along with its symbol table
+    ///
+    /// The merged specification is itself valid, with the same symbol table.
+    ///
+    /// Example
+    ///
+    /// ```
+    /// use ox_idl::ast::{Definition, Specification};
+    /// use chumsky::prelude::*;
+    ///
+    /// let spec = Specification::parser()
+    ///     .parse("module M { const long A = 1; }; const long B = M::A; module M { const long C = B; };")
+    ///     .unwrap();
+    /// let (merged, _) = spec.validate_merged().unwrap();
+    /// assert_eq!(merged.definitions.len(), 2);
+    /// let Definition::Module(m) = &merged.definitions[0] else { panic!() };
+    /// assert_eq!(m.definitions.len(), 2);
+    /// assert!(merged.validate().is_ok());
+    /// ```
+    pub fn validate_merged(&self) -> Result<(Specification, SymbolTable), Vec<Simple<char>>> {
+        let (spec, table) = self.validate_expanded()?;
+        let merged = Specification {
+            definitions: Module::merge(spec.definitions),
+        };
+        Ok((merged, table))
+    }
+
+    /// Validates the specification, returning it with its template instances
+    /// expanded along with its symbol table
+    fn validate_expanded(&self) -> Result<(Specification, SymbolTable), Vec<Simple<char>>> {
+        let spec = self.expand()?;
+        let (table, mut errors) = SymbolTable::new(&spec);
+        let root = table.scope(ScopedName::root());
+        check_definitions(&root, &spec.definitions, &mut errors);
+        check_applied_annotations(&root, &spec.definitions, &mut errors);
+        check_reopened_annotations(
+            &ScopedName::root(),
+            &spec.definitions,
+            &mut HashMap::new(),
+            &mut errors,
+        );
+        if errors.is_empty() {
+            Ok((spec, table))
+        } else {
+            Err(errors)
+        }
+    }
+}
+
+/// Checks that an annotation applied to a reopened module is applied alike to every
+/// occurrence of the module that applies it, so that the occurrences may be merged.
+/// `opened` holds the annotations of the earlier occurrences of each module.
+fn check_reopened_annotations<'a>(
+    path: &ScopedName,
+    definitions: &'a [Definition],
+    opened: &mut HashMap<ScopedName, Vec<&'a Annotation>>,
+    errors: &mut Vec<Simple<char>>,
+) {
+    for definition in definitions {
+        if let Definition::Module(module) = definition {
+            let name = path.child(&module.name);
+            let earlier = opened.entry(name.clone()).or_default();
+            for annotation in &module.annotations {
+                let conflicts = earlier
+                    .iter()
+                    .any(|a| a.name == annotation.name && !a.same_as(annotation));
+                if conflicts {
+                    errors.push(Simple::custom(
+                        annotation.span.clone(),
+                        format!(
+                            "@{} is applied to module `{}` differently where it was opened before",
+                            annotation.name, module.name
+                        ),
+                    ));
+                }
+            }
+            earlier.extend(&module.annotations);
+            check_reopened_annotations(&name, &module.definitions, opened, errors);
+        }
+    }
+}
+
+/// Checks each of the definitions declared within `scope`
+fn check_definitions(scope: &Scope, definitions: &[Definition], errors: &mut Vec<Simple<char>>) {
+    for definition in definitions {
+        match definition {
+            Definition::Module(module) => {
+                check_definitions(&scope.nested(&module.name), &module.definitions, errors)
+            }
+            Definition::Typedef(typedef) => {
+                check_type(scope, &typedef.ty, errors);
+                check_declarators(scope, &typedef.declarators, errors);
+            }
+            Definition::Struct(s) => {
+                check_members(scope, &s.members, errors);
+                check_member_names(&s.members, errors);
+            }
+            Definition::Exception(e) => {
+                check_members(scope, &e.members, errors);
+                check_member_names(&e.members, errors);
+            }
+            Definition::Union(union) => {
+                for case in &union.cases {
+                    check_type(scope, &case.ty, errors);
+                    check_declarators(scope, std::slice::from_ref(&case.declarator), errors);
+                }
+                check_union(scope, union, errors)
+            }
+            Definition::Interface(interface) => {
+                check_interface(&scope.nested(&interface.name), interface, errors)
+            }
+            Definition::ValueType(value) => {
+                check_value_type(&scope.nested(&value.name), value, errors)
+            }
+            Definition::ValueBox(value_box) => check_value_box(scope, value_box, errors),
+            Definition::Component(component) => {
+                check_ports(&scope.nested(&component.name), &component.exports, errors)
+            }
+            Definition::Home(home) => check_home(scope, home, errors),
+            Definition::PortType(port_type) => {
+                check_port_type(&scope.nested(&port_type.name), port_type, errors)
+            }
+            Definition::Connector(connector) => {
+                check_ports(&scope.nested(&connector.name), &connector.exports, errors)
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Checks the built-in annotations applied to each of the definitions declared
+/// within `scope`, and to each of their members
+fn check_applied_annotations(
+    scope: &Scope,
+    definitions: &[Definition],
+    errors: &mut Vec<Simple<char>>,
+) {
+    for definition in definitions {
+        match definition {
+            Definition::Module(module) => {
+                check_annotations(scope, Target::Module, &module.annotations, errors);
+                check_applied_annotations(&scope.nested(&module.name), &module.definitions, errors)
+            }
+            Definition::TemplateModule(template) => {
+                check_annotations(scope, Target::TemplateModule, &template.annotations, errors)
+            }
+            Definition::TemplateInstance(instance) => {
+                check_annotations(scope, Target::Module, &instance.annotations, errors)
+            }
+            Definition::Const(c) => check_annotations(scope, Target::Const, &c.annotations, errors),
+            Definition::Typedef(typedef) => {
+                check_annotations(scope, Target::Typedef, &typedef.annotations, errors)
+            }
+            Definition::Struct(s) => {
+                check_annotations(scope, Target::Struct, &s.annotations, errors);
+                for member in &s.members {
+                    check_annotations(scope, Target::Member, &member.annotations, errors);
+                }
+            }
+            Definition::Union(union) => {
+                check_annotations(scope, Target::Union, &union.annotations, errors);
+                for case in &union.cases {
+                    check_annotations(scope, Target::Case, &case.annotations, errors);
+                }
+            }
+            Definition::Enum(e) => {
+                check_annotations(scope, Target::Enum, &e.annotations, errors);
+                for enumerator in &e.enumerators {
+                    check_annotations(scope, Target::Enumerator, &enumerator.annotations, errors);
+                }
+            }
+            Definition::Bitmask(b) => {
+                check_annotations(scope, Target::Bitmask, &b.annotations, errors);
+                for value in &b.values {
+                    check_annotations(scope, Target::BitValue, &value.annotations, errors);
+                }
+            }
+            Definition::Bitset(b) => {
+                check_annotations(scope, Target::Bitset, &b.annotations, errors);
+                for bitfield in &b.bitfields {
+                    check_annotations(scope, Target::Bitfield, &bitfield.annotations, errors);
+                }
+            }
+            Definition::Native(n) => {
+                check_annotations(scope, Target::Native, &n.annotations, errors)
+            }
+            Definition::Interface(interface) => {
+                check_annotations(scope, Target::Interface, &interface.annotations, errors);
+                let scope = scope.nested(&interface.name);
+                for export in &interface.exports {
+                    check_export_annotations(&scope, export, errors);
+                }
+            }
+            Definition::Exception(e) => {
+                check_annotations(scope, Target::Exception, &e.annotations, errors);
+                for member in &e.members {
+                    check_annotations(scope, Target::Member, &member.annotations, errors);
+                }
+            }
+            Definition::ValueType(value) => {
+                let target = match value.keyword {
+                    Keyword::EventType => Target::EventType,
+                    _ => Target::ValueType,
+                };
+                check_annotations(scope, target, &value.annotations, errors);
+                let scope = scope.nested(&value.name);
+                for element in &value.elements {
+                    match element {
+                        ValueElement::Export(export) => {
+                            check_export_annotations(&scope, export, errors)
+                        }
+                        ValueElement::State(state) => check_annotations(
+                            &scope,
+                            Target::StateMember,
+                            &state.annotations,
+                            errors,
+                        ),
+                        ValueElement::Factory(factory) => {
+                            check_factory_annotations(&scope, factory, errors)
+                        }
+                    }
+                }
+            }
+            Definition::ValueBox(value_box) => {
+                check_annotations(scope, Target::ValueBox, &value_box.annotations, errors)
+            }
+            Definition::Component(component) => {
+                check_annotations(scope, Target::Component, &component.annotations, errors);
+                check_port_annotations(scope, &component.exports, errors);
+            }
+            Definition::Home(home) => {
+                check_annotations(scope, Target::Home, &home.annotations, errors);
+                let scope = scope.nested(&home.name);
+                for export in &home.exports {
+                    match export {
+                        HomeExport::Export(export) => {
+                            check_export_annotations(&scope, export, errors)
+                        }
+                        HomeExport::Factory(factory) => {
+                            check_factory_annotations(&scope, factory, errors)
+                        }
+                    }
+                }
+            }
+            Definition::PortType(port_type) => {
+                check_annotations(scope, Target::PortType, &port_type.annotations, errors);
+                check_port_annotations(scope, &port_type.exports, errors);
+            }
+            Definition::Connector(connector) => {
+                check_annotations(scope, Target::Connector, &connector.annotations, errors);
+                check_port_annotations(scope, &connector.exports, errors);
+            }
+            Definition::Forward(forward) => {
+                check_annotations(scope, Target::Forward, &forward.annotations, errors)
+            }
+        }
+    }
+}
+
+/// Checks the built-in annotations applied to an operation, attribute or
+/// definition of an interface or value type, and to the parameters of an operation
+fn check_export_annotations(scope: &Scope, export: &Export, errors: &mut Vec<Simple<char>>) {
+    match export {
+        Export::Operation(operation) => {
+            check_annotations(scope, Target::Operation, &operation.annotations, errors);
+            for param in &operation.params {
+                check_annotations(scope, Target::Param, &param.annotations, errors);
+            }
+        }
+        Export::Attribute(attribute) => {
+            check_annotations(scope, Target::Attribute, &attribute.annotations, errors)
+        }
+        Export::Definition(definition) => {
+            check_applied_annotations(scope, std::slice::from_ref(definition), errors)
+        }
+    }
+}
+
+/// Checks the built-in annotations applied to a factory or finder and to its
+/// parameters
+fn check_factory_annotations(scope: &Scope, factory: &Factory, errors: &mut Vec<Simple<char>>) {
+    check_annotations(scope, Target::Factory, &factory.annotations, errors);
+    for param in &factory.params {
+        check_annotations(scope, Target::Param, &param.annotations, errors);
+    }
+}
+
+/// Checks the built-in annotations applied to the ports and attributes of a
+/// component, porttype or connector
+fn check_port_annotations(
+    scope: &Scope,
+    exports: &[ComponentExport],
+    errors: &mut Vec<Simple<char>>,
+) {
+    for export in exports {
+        match export {
+            ComponentExport::Port(port) => {
+                check_annotations(scope, Target::Port, &port.annotations, errors)
+            }
+            ComponentExport::Attribute(attribute) => {
+                check_annotations(scope, Target::Attribute, &attribute.annotations, errors)
+            }
+        }
+    }
+}
+
+/// Checks the operations, attributes and definitions of an interface, where
+/// `scope` is the scope of the interface itself
+fn check_interface(scope: &Scope, interface: &Interface, errors: &mut Vec<Simple<char>>) {
+    for export in &interface.exports {
+        check_export(scope, export, errors);
+    }
+}
+
+/// Checks an operation, attribute or definition of an interface or value type,
+/// where `scope` is the scope of the interface or value type
+fn check_export(scope: &Scope, export: &Export, errors: &mut Vec<Simple<char>>) {
+    match export {
+        Export::Operation(operation) => check_operation(scope, operation, errors),
+        Export::Attribute(attribute) => check_attribute(scope, attribute, errors),
+        Export::Definition(definition) => {
+            check_definitions(scope, std::slice::from_ref(definition), errors)
+        }
+    }
+}
+
+/// Checks the type and the exceptions raised by an attribute
+fn check_attribute(scope: &Scope, attribute: &Attribute, errors: &mut Vec<Simple<char>>) {
+    check_type(scope, &attribute.ty, errors);
+    let clauses = [&attribute.get_raises, &attribute.set_raises];
+    for raises in clauses.into_iter().flatten() {
+        check_raises(scope, raises, errors);
+    }
+}
+
+/// Checks the elements of a value type, where `scope` is the scope of the value
+/// type itself
+fn check_value_type(scope: &Scope, value: &ValueType, errors: &mut Vec<Simple<char>>) {
+    // 7.4.7.4
+    // An abstract value type may not have state members or factories, as it cannot
+    // be instantiated.
+    let abstract_value = value.modifier == Some(Keyword::Abstract);
+    for element in &value.elements {
+        match element {
+            ValueElement::Export(export) => check_export(scope, export, errors),
+            ValueElement::State(member) => {
+                check_type(scope, &member.ty, errors);
+                check_declarators(scope, &member.declarators, errors);
+                if abstract_value {
+                    errors.push(Simple::custom(
+                        member.span.clone(),
+                        format!(
+                            "abstract value type `{}` cannot have state members",
+                            value.name
+                        ),
+                    ));
+                }
+            }
+            ValueElement::Factory(factory) => {
+                check_factory(scope, factory, errors);
+                if abstract_value {
+                    errors.push(Simple::custom(
+                        factory.span.clone(),
+                        format!("abstract value type `{}` cannot have factories", value.name),
+                    ));
+                }
+            }
+        }
+    }
+}
+
+/// Checks the types, names and directions of the parameters of a factory or finder
+fn check_factory(scope: &Scope, factory: &Factory, errors: &mut Vec<Simple<char>>) {
+    for param in &factory.params {
+        check_type(scope, &param.ty, errors);
+    }
+    let names = factory.params.iter().map(|p| (&p.name, p.span.clone()));
+    if let Err(collisions) = Identifier::check_scope(names) {
+        errors.extend(collisions);
+    }
+    if let Some(raises) = &factory.raises {
+        check_raises(scope, raises, errors);
+    }
+
+    // 7.4.5.3
+    // <init_param_dcl> ::= "in" <type_spec> <simple_declarator>
+    //
+    // 7.4.9.3
+    // <factory_param_dcl> ::= "in" <type_spec> <simple_declarator>
+    for param in factory.params.iter().filter(|p| p.direction != Keyword::In) {
+        errors.push(Simple::custom(
+            param.span.clone(),
+            format!(
+                "`{}` is an `{}` parameter, {} `{}` may only have `in` parameters",
+                param.name, param.direction, factory.keyword, factory.name
+            ),
+        ));
+    }
+}
+
+/// Checks the ports and attributes of a component, porttype or connector, where
+/// `scope` is the scope of the component, porttype or connector itself
+fn check_ports(scope: &Scope, exports: &[ComponentExport], errors: &mut Vec<Simple<char>>) {
+    for export in exports {
+        match export {
+            ComponentExport::Port(port) => check_port(scope, port, errors),
+            ComponentExport::Attribute(attribute) => check_attribute(scope, attribute, errors),
+        }
+    }
+}
+
+/// Checks that the type of a port is an interface for a `provides` or `uses` port,
+/// a porttype for a `port` or `mirrorport` and an event type for any other port
+fn check_port(scope: &Scope, port: &Port, errors: &mut Vec<Simple<char>>) {
+    // 7.4.8.4
+    // A facet or receptacle is given the interface it provides or uses.
+    //
+    // 7.4.10.4
+    // An event source or sink is given the event type it emits, publishes or
+    // consumes. A facet or receptacle may also be given `Object`.
+    //
+    // 7.4.11.4
+    // An extended port is given the porttype that defines it.
+    let interface = matches!(port.keyword, Keyword::Provides | Keyword::Uses);
+    let extended = matches!(port.keyword, Keyword::Port | Keyword::MirrorPort);
+    let expected = if interface {
+        "an interface"
+    } else if extended {
+        "a porttype"
+    } else {
+        "an event type"
+    };
+    let message = match &port.ty {
+        TypeSpec::Object(_) if interface => return,
+        TypeSpec::Named(name, _) => match scope.lookup(name) {
+            Some((_, declaration)) => match &declaration.symbol {
+                Symbol::Interface(_, _) | Symbol::Forward(Keyword::Interface) if interface => {
+                    return
+                }
+                Symbol::PortType(_) | Symbol::Forward(Keyword::PortType) if extended => {
+                    let mirrored = port.keyword == Keyword::MirrorPort;
+                    check_reversal(scope, port, name, mirrored, &mut Vec::new(), errors);
+                    return;
+                }
+                Symbol::EventType(_, _) | Symbol::Forward(Keyword::EventType)
+                    if !interface && !extended =>
+                {
+                    return
+                }
+                symbol => format!(
+                    "`{}` requires {}, `{}` is {}",
+                    port.keyword,
+                    expected,
+                    name,
+                    symbol.description()
+                ),
+            },
+            None => format!("`{}` does not name a type", name),
+        },
+        _ => format!(
+            "`{}` requires {}, found `{}`",
+            port.keyword,
+            expected,
+            Keyword::Object
+        ),
+    };
+    errors.push(Simple::custom(port.ty.span(), message));
+}
+
+/// Checks that every receptacle an extended port reverses can be reversed, where
+/// `port` is the extended port declared within `scope` and `name` is a porttype
+/// it contains, directly or through the ports of other porttypes. `mirrored` is
+/// true if the ports of the porttype are reversed, and `visited` lists the
+/// porttypes already checked.
+fn check_reversal(
+    scope: &Scope,
+    port: &Port,
+    name: &ScopedName,
+    mirrored: bool,
+    visited: &mut Vec<ScopedName>,
+    errors: &mut Vec<Simple<char>>,
+) {
+    // 7.4.11.4
+    // A mirrorport is the same as a port, except that the facets and receptacles
+    // of its porttype are reversed: provides become uses and uses become provides.
+    //
+    // A facet has no `multiple` form, so a `uses multiple` receptacle cannot be
+    // reversed.
+    let (path, ports) = match scope.lookup(name) {
+        Some((path, _)) if visited.contains(&path) => return,
+        Some((
+            path,
+            Declaration {
+                symbol: Symbol::PortType(ports),
+                ..
+            },
+        )) => (path, ports),
+        _ => return,
+    };
+    visited.push(path.clone());
+
+    let inner = scope.at(path.clone());
+    for declared in ports {
+        let reversed = if mirrored {
+            declared.mirrored()
+        } else {
+            declared.clone()
+        };
+        match (&reversed.ty, &reversed.keyword) {
+            (_, Keyword::Provides) if declared.multiple => errors.push(Simple::custom(
+                port.ty.span(),
+                format!(
+                    "`{}` is a `uses multiple` receptacle of `{}` and cannot be reversed by `{}`",
+                    declared.name, name, port.keyword
+                ),
+            )),
+            (TypeSpec::Named(nested, _), Keyword::Port | Keyword::MirrorPort) => {
+                let mirrored = reversed.keyword == Keyword::MirrorPort;
+                check_reversal(&inner, port, nested, mirrored, visited, errors)
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Checks the ports and attributes of a porttype, where `scope` is the scope of
+/// the porttype itself, and that the porttype does not contain a port of itself
+fn check_port_type(scope: &Scope, port_type: &PortType, errors: &mut Vec<Simple<char>>) {
+    check_ports(scope, &port_type.exports, errors);
+
+    // 7.4.11.4
+    // A porttype may contain extended ports of other porttypes, but not of itself.
+    for export in &port_type.exports {
+        if let ComponentExport::Port(Port {
+            keyword: Keyword::Port | Keyword::MirrorPort,
+            ty: TypeSpec::Named(name, span),
+            ..
+        }) = export
+        {
+            if contains_port_type(scope, name, scope.path(), &mut Vec::new()) {
+                errors.push(Simple::custom(
+                    span.clone(),
+                    format!(
+                        "porttype `{}` cannot contain a port of itself",
+                        port_type.name
+                    ),
+                ));
+            }
+        }
+    }
+}
+
+/// Returns true if the porttype `name` referenced from within `scope` is the
+/// porttype `target` or contains an extended port of it, directly or through
+/// the ports of other porttypes. `visited` lists the porttypes already searched.
+fn contains_port_type(
+    scope: &Scope,
+    name: &ScopedName,
+    target: &ScopedName,
+    visited: &mut Vec<ScopedName>,
+) -> bool {
+    let (path, ports) = match scope.lookup(name) {
+        Some((path, _)) if path == *target => return true,
+        Some((path, _)) if visited.contains(&path) => return false,
+        Some((
+            path,
+            Declaration {
+                symbol: Symbol::PortType(ports),
+                ..
+            },
+        )) => (path, ports),
+        _ => return false,
+    };
+    visited.push(path.clone());
+
+    let inner = scope.at(path);
+    ports.iter().any(|port| match (&port.keyword, &port.ty) {
+        (Keyword::Port | Keyword::MirrorPort, TypeSpec::Named(name, _)) => {
+            contains_port_type(&inner, name, target, visited)
+        }
+        _ => false,
+    })
+}
+
+/// Checks the managed component and primary key of a home declared within
+/// `scope`, along with the factories, finders, operations, attributes and
+/// definitions it contains
+fn check_home(scope: &Scope, home: &Home, errors: &mut Vec<Simple<char>>) {
+    // 7.4.9.4
+    // A home manages a component.
+    //
+    // 7.4.10.4
+    // The primary key of a home is a value type that is not abstract.
+    let message = match scope.lookup(&home.manages.name) {
+        Some((_, declaration))
+            if matches!(
+                declaration.symbol,
+                Symbol::Component(_) | Symbol::Forward(Keyword::Component)
+            ) =>
+        {
+            None
+        }
+        Some((_, declaration)) => Some(format!(
+            "`{}` is {} and cannot be managed by a home",
+            home.manages.name,
+            declaration.symbol.description()
+        )),
+        None => Some(format!("`{}` does not name a type", home.manages.name)),
+    };
+    if let Some(message) = message {
+        errors.push(Simple::custom(home.manages.span.clone(), message));
+    }
+
+    if let Some(primary_key) = &home.primary_key {
+        let message = match scope.lookup(&primary_key.name) {
+            Some((_, declaration)) => match &declaration.symbol {
+                Symbol::ValueType(modifier, _) if *modifier != Some(Keyword::Abstract) => None,
+                symbol => Some(format!(
+                    "`{}` is {} and cannot be a primary key",
+                    primary_key.name,
+                    symbol.description()
+                )),
+            },
+            None => Some(format!("`{}` does not name a type", primary_key.name)),
+        };
+        if let Some(message) = message {
+            errors.push(Simple::custom(primary_key.span.clone(), message));
+        }
+    }
+
+    let scope = scope.nested(&home.name);
+    for export in &home.exports {
+        match export {
+            HomeExport::Export(export) => check_export(&scope, export, errors),
+            HomeExport::Factory(factory) => check_factory(&scope, factory, errors),
+        }
+    }
+}
+
+/// Checks that the type of a boxed value type is declared and is not itself a
+/// value type
+fn check_value_box(scope: &Scope, value_box: &ValueBox, errors: &mut Vec<Simple<char>>) {
+    // 7.4.7.4
+    // Any IDL type may be used to declare a value box except for a valuetype.
+    check_type(scope, &value_box.ty, errors);
+    let message = match scope.resolve_type(&value_box.ty) {
+        Ok(TypeSpec::ValueBase(_)) => "`ValueBase` cannot be boxed".to_string(),
+        Ok(TypeSpec::Named(resolved, _)) => match (&value_box.ty, scope.lookup(&resolved)) {
+            (TypeSpec::Named(name, _), Some((_, declaration)))
+                if matches!(
+                    declaration.symbol,
+                    Symbol::ValueType(_, _)
+                        | Symbol::ValueBox
+                        | Symbol::Forward(Keyword::ValueType)
+                ) =>
+            {
+                format!(
+                    "`{}` is {} and cannot be boxed",
+                    name,
+                    declaration.symbol.description()
+                )
+            }
+            _ => return,
+        },
+        _ => return,
+    };
+    errors.push(Simple::custom(value_box.ty.span(), message));
+}
+
+/// Checks the types and parameter names of an operation, and that a oneway
+/// operation neither returns a value, has output parameters nor raises exceptions
+fn check_operation(scope: &Scope, operation: &Operation, errors: &mut Vec<Simple<char>>) {
+    if let Some(ty) = &operation.return_type {
+        check_type(scope, ty, errors);
+    }
+    for param in &operation.params {
+        check_type(scope, &param.ty, errors);
+    }
+    let names = operation.params.iter().map(|p| (&p.name, p.span.clone()));
+    if let Err(collisions) = Identifier::check_scope(names) {
+        errors.extend(collisions);
+    }
+    if let Some(raises) = &operation.raises {
+        check_raises(scope, raises, errors);
+    }
+
+    // 7.4.6.4.1
+    // An operation with the oneway attribute must specify a void return type, must
+    // not contain any output parameters (i.e. only in parameters are allowed) and
+    // must not contain a raises expression.
+    if !operation.oneway {
+        return;
+    }
+    if let Some(ty) = &operation.return_type {
+        errors.push(Simple::custom(
+            ty.span(),
+            format!("oneway operation `{}` must return void", operation.name),
+        ));
+    }
+    for param in operation
+        .params
+        .iter()
+        .filter(|p| p.direction != Keyword::In)
+    {
+        errors.push(Simple::custom(
+            param.span.clone(),
+            format!(
+                "`{}` is an `{}` parameter, oneway operation `{}` may only have `in` parameters",
+                param.name, param.direction, operation.name
+            ),
+        ));
+    }
+    if let Some(raises) = &operation.raises {
+        errors.push(Simple::custom(
+            raises.span.clone(),
+            format!(
+                "oneway operation `{}` cannot raise exceptions",
+                operation.name
+            ),
+        ));
+    }
+}
+
+/// Checks that each name listed by a `raises`, `getraises` or `setraises` clause
+/// refers to an exception
+fn check_raises(scope: &Scope, raises: &Raises, errors: &mut Vec<Simple<char>>) {
+    // 7.4.3.4.3.3
+    // A raises expression specifies which exceptions may be raised as a result of
+    // an invocation of the operation or an access to the attribute, so each name
+    // it lists must refer to an exception.
+    for exception in &raises.exceptions {
+        let message = match scope.lookup(&exception.name) {
+            Some((_, declaration)) if declaration.symbol == Symbol::Exception => continue,
+            Some((_, declaration)) => format!(
+                "`{}` is {} and cannot be listed by `{}`, only exceptions may be raised",
+                exception.name,
+                declaration.symbol.description(),
+                raises.keyword
+            ),
+            None => format!("`{}` does not name an exception", exception.name),
+        };
+        errors.push(Simple::custom(exception.span.clone(), message));
+    }
+}
+
+/// Checks the types and array dimensions of the members of a struct or exception
+fn check_members(scope: &Scope, members: &[Member], errors: &mut Vec<Simple<char>>) {
+    for member in members {
+        check_type(scope, &member.ty, errors);
+        check_declarators(scope, &member.declarators, errors);
+    }
+}
+
+/// Checks that the names of the members of a struct or exception do not collide with
+/// each other
+fn check_member_names(members: &[Member], errors: &mut Vec<Simple<char>>) {
+    let names = members
+        .iter()
+        .flat_map(|m| &m.declarators)
+        .map(|d| (&d.name, d.span.clone()));
+    if let Err(collisions) = Identifier::check_scope(names) {
+        errors.extend(collisions);
+    }
+}
+
+/// Checks that a type refers only to declared types and that each of its bounds
+/// is a positive integer
+fn check_type(scope: &Scope, ty: &TypeSpec, errors: &mut Vec<Simple<char>>) {
+    match ty {
+        TypeSpec::String(Some(bound), _) | TypeSpec::WString(Some(bound), _) => {
+            positive_int(scope, bound, errors);
+        }
+        TypeSpec::Sequence(element, bound, _) => {
+            check_type(scope, element, errors);
+            if let Some(bound) = bound {
+                positive_int(scope, bound, errors);
+            }
+        }
+        TypeSpec::Map(key, value, bound, _) => {
+            check_type(scope, key, errors);
+            check_type(scope, value, errors);
+            if let Some(bound) = bound {
+                positive_int(scope, bound, errors);
+            }
+        }
+        TypeSpec::Fixed(Some((digits, scale)), _) => {
+            // 7.4.1.4.4.3.4
+            // The first parameter is the number of digits, up to 31, and the second
+            // is the scale, which is at most the number of digits.
+            let digits = match positive_int(scope, digits, errors) {
+                Some(value) if value > 31 => {
+                    return errors.push(Simple::custom(
+                        digits.span(),
+                        format!(
+                            "a fixed point type may have at most 31 digits, found {}",
+                            value
+                        ),
+                    ))
+                }
+                Some(digits) => digits,
+                None => return,
+            };
+            match scale.evaluate_as(PrimitiveType::UnsignedShort, scope) {
+                Ok(ConstValue::Integer(value)) if value > digits as i128 => {
+                    errors.push(Simple::custom(
+                        scale.span(),
+                        format!(
+                            "the scale of a fixed point type cannot exceed its {} digits, found {}",
+                            digits, value
+                        ),
+                    ))
+                }
+                Ok(_) => {}
+                Err(error) => errors.push(error),
+            }
+        }
+        TypeSpec::Fixed(None, span) => errors.push(Simple::custom(
+            span.clone(),
+            "the digits and scale of a fixed point type must be given, except for the type of a constant",
+        )),
+        TypeSpec::Named(name, span) => {
+            let message = match scope.lookup(name) {
+                Some((_, declaration)) if declaration.symbol.is_type() => return,
+                Some((_, declaration)) => format!(
+                    "`{}` is {} and does not name a type",
+                    name,
+                    declaration.symbol.description()
+                ),
+                None => format!("`{}` does not name a type", name),
+            };
+            errors.push(Simple::custom(span.clone(), message));
+        }
+        _ => {}
+    }
+}
+
+/// Checks that each dimension of the declarators is a positive integer
+fn check_declarators(scope: &Scope, declarators: &[Declarator], errors: &mut Vec<Simple<char>>) {
+    for dimension in declarators.iter().flat_map(|d| &d.dimensions) {
+        positive_int(scope, dimension, errors);
+    }
+}
+
+/// Evaluates a `<positive_int_const>`, such as a bound or the size of an array
+/// dimension, reporting it unless it is a positive integer
+fn positive_int(scope: &Scope, expr: &ConstExpr, errors: &mut Vec<Simple<char>>) -> Option<u32> {
+    match expr.evaluate_as(PrimitiveType::UnsignedLong, scope) {
+        Ok(ConstValue::Integer(value)) if value > 0 => Some(value as u32),
+        Ok(value) => {
+            errors.push(Simple::custom(
+                expr.span(),
+                format!("expected a positive integer, found {}", value),
+            ));
+            None
+        }
+        Err(error) => {
+            errors.push(error);
+            None
+        }
+    }
+}
+
+/// Checks the discriminator, labels and element names of a union
+fn check_union(scope: &Scope, union: &Union, errors: &mut Vec<Simple<char>>) {
+    let elements = union
+        .cases
+        .iter()
+        .map(|case| (&case.declarator.name, case.declarator.span.clone()));
+    if let Err(collisions) = Identifier::check_scope(elements) {
+        errors.extend(collisions);
+    }
+
+    let discriminator = match Discriminator::resolve(scope, &union.discriminator) {
+        Ok(discriminator) => discriminator,
+        Err(error) => return errors.push(error),
+    };
+
+    // 7.4.1.4.4.4.2
+    // It is illegal for the same value to appear more than once in the set of case
+    // labels. [...] The default label may appear at most once in a union
+    // declaration.
+    let mut values: Vec<ConstValue> = Vec::new();
+    let mut default: Option<Range<usize>> = None;
+    for label in union.cases.iter().flat_map(|case| &case.labels) {
+        match label {
+            CaseLabel::Value(expr) => match discriminator.evaluate(scope, expr) {
+                Ok(value) if values.contains(&value) => errors.push(Simple::custom(
+                    expr.span(),
+                    format!("case label {} is used more than once", value),
+                )),
+                Ok(value) => values.push(value),
+                Err(error) => errors.push(error),
+            },
+            CaseLabel::Default(span) if default.is_some() => errors.push(Simple::custom(
+                span.clone(),
+                "a union may have at most one default label",
+            )),
+            CaseLabel::Default(span) => default = Some(span.clone()),
+        }
+    }
+
+    // A default label is only meaningful when some value of the discriminator is
+    // not covered by the case labels
+    if let Some(span) = default {
+        if values.len() as u128 >= discriminator.domain_size() {
+            errors.push(Simple::custom(
+                span,
+                format!(
+                    "the default label can never be selected, the case labels cover every value of {}",
+                    discriminator
+                ),
+            ));
+        }
+    }
+}
+
+/// The type of the discriminator of a union
+enum Discriminator {
+    Primitive(PrimitiveType),
+    /// An enum, given by its absolute name and the number of its enumerators
+    Enum(ScopedName, usize),
+}
+
+impl Discriminator {
+    /// Resolves the type of a union discriminator, checking that it is one of the
+    /// types a union may be discriminated by
+    fn resolve(scope: &Scope, ty: &TypeSpec) -> Result<Discriminator, Simple<char>> {
+        // 7.4.1.4.4.4.2
+        // The <switch_type_spec> can be an integer, char, boolean, octet or enum type
+        // or a reference to one of these types.
+        let message = match scope.resolve_type(ty)? {
+            TypeSpec::Primitive(primitive, _)
+                if primitive.integer_range().is_some()
+                    || matches!(
+                        primitive,
+                        PrimitiveType::Char | PrimitiveType::WChar | PrimitiveType::Boolean
+                    ) =>
+            {
+                return Ok(Discriminator::Primitive(primitive))
+            }
+            TypeSpec::Named(name, _) => match scope.lookup(&name) {
+                Some((
+                    name,
+                    Declaration {
+                        symbol: Symbol::Enum(enumerators),
+                        ..
+                    },
+                )) => return Ok(Discriminator::Enum(name, enumerators.len())),
+                Some((_, declaration)) => format!(
+                    "`{}` is {} and cannot be used as a union discriminator",
+                    name.name(),
+                    declaration.symbol.description()
+                ),
+                None => unreachable!("resolved names are declared"),
+            },
+            _ => {
+                "a union discriminator must be an integer, char, wchar, boolean, octet or enum type"
+                    .to_string()
+            }
+        };
+        Err(Simple::custom(ty.span(), message))
+    }
+
+    /// Evaluates a case label, checking that it is a value of the discriminator type
+    fn evaluate(&self, scope: &Scope, expr: &ConstExpr) -> Result<ConstValue, Simple<char>> {
+        match self {
+            Discriminator::Primitive(ty) => expr.evaluate_as(*ty, scope),
+            Discriminator::Enum(name, _) => match expr.evaluate(scope)? {
+                ConstValue::Enumerator(ty, enumerator, value) if ty == *name => {
+                    Ok(ConstValue::Enumerator(ty, enumerator, value))
+                }
+                value => Err(Simple::custom(
+                    expr.span(),
+                    format!(
+                        "expected a value of type {}, found {} value {}",
+                        self,
+                        value.kind(),
+                        value
+                    ),
+                )),
+            },
+        }
+    }
+
+    /// Returns the number of distinct values of the discriminator type
+    fn domain_size(&self) -> u128 {
+        match self {
+            Discriminator::Primitive(ty) => match ty.integer_range() {
+                Some((min, max)) => (max - min) as u128 + 1,
+                None => match ty {
+                    PrimitiveType::Boolean => 2,
+                    PrimitiveType::Char => 1 << 8,
+                    PrimitiveType::WChar => 1 << 16,
+                    _ => u128::MAX,
+                },
+            },
+            Discriminator::Enum(_, enumerators) => *enumerators as u128,
+        }
+    }
+}
+
+impl Display for Discriminator {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Discriminator::Primitive(ty) => write!(f, "{}", ty),
+            Discriminator::Enum(name, _) => write!(f, "{}", name.name()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod validate_tests {
+    use crate::ast::Definition;
+    use crate::test_utils::{diagnostics, parse};
+
+    fn validate(source: &str) -> Vec<(String, std::ops::Range<usize>)> {
+        match parse(source).validate() {
+            Ok(_) => Vec::new(),
+            Err(errors) => diagnostics(&errors),
+        }
+    }
+
+    fn messages(source: &str) -> Vec<String> {
+        validate(source)
+            .into_iter()
+            .map(|(message, _)| message)
+            .collect()
+    }
+
+    #[test]
+    fn type_bounds() {
+        let source = "const long N = 4; typedef sequence<string<N>, N * 2> A[N]; typedef string<0> B; typedef map<long, B, -1> C[2][N - 4];";
+        assert_eq!(
+            validate(source),
+            vec![
+                ("expected a positive integer, found 0".to_string(), 74..75),
+                (
+                    "value -1 is out of range for type unsigned long".to_string(),
+                    101..103
+                ),
+                ("expected a positive integer, found 0".to_string(), 110..115),
+            ]
+        );
+
+        assert_eq!(
+            messages(
+                "
+                typedef fixed<31, 31> F;
+                typedef fixed<32, 2> G;
+                typedef fixed<5, 6> H;
+                struct S { fixed x; };
+                "
+            ),
+            vec![
+                "a fixed point type may have at most 31 digits, found 32",
+                "the scale of a fixed point type cannot exceed its 5 digits, found 6",
+                "the digits and scale of a fixed point type must be given, except for the type of a constant",
+            ]
+        );
+    }
+
+    #[test]
+    fn reopened_modules() {
+        // A reopened module may use what is declared after the module was first opened
+        let source = "
+            module A { const long X = 1; struct S { long a; }; };
+            const long Y = A::X;
+            interface I {};
+            module A { const long Z = Y; interface J : ::I {}; typedef S T; };
+            const long W = A::Z;
+        ";
+        assert_eq!(messages(source), Vec::<String>::new());
+
+        // Merging gives one module per scoped name, which still validates
+        let (merged, _) = parse(source).validate_merged().unwrap();
+        let modules = merged
+            .definitions
+            .iter()
+            .filter_map(|d| match d {
+                Definition::Module(m) => Some(m),
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(modules.len(), 1);
+        assert_eq!(modules[0].definitions.len(), 5);
+        assert_eq!(
+            merged.validate().map(|_| ()).map_err(|e| diagnostics(&e)),
+            Ok(())
+        );
+
+        // Annotations applied to more than one occurrence must be alike
+        let source = "@x @y(1) module A { native N1; }; @x @y( 1 ) module A { native N2; };";
+        let (merged, _) = parse(source).validate_merged().unwrap();
+        let Definition::Module(a) = &merged.definitions[0] else {
+            panic!("expected a module");
+        };
+        assert_eq!(a.annotations.len(), 2);
+        let source = "@y(1) module A { native N1; }; @x @y(2) module A { native N2; };";
+        assert_eq!(
+            validate(source),
+            vec![(
+                "@y is applied to module `A` differently where it was opened before".to_string(),
+                34..39
+            )]
+        );
+
+        let source =
+            "module A { const long X = Y; }; const long Y = 1; module A { const long X = 2; };";
+        assert_eq!(
+            messages(source),
+            vec![
+                "`Y` does not name a constant",
+                "`X` is already declared in this scope",
+            ]
+        );
+    }
+
+    #[test]
+    fn type_names() {
+        assert_eq!(
+            messages(
+                "
+                const long N = 1;
+                typedef Missing A;
+                typedef sequence<N> B;
+                struct S { A a; M::X x; };
+                module M { typedef S X; };
+                typedef X C;
+                union U switch (A) { case 1: N n; };
+                "
+            ),
+            vec![
+                "`Missing` does not name a type",
+                "`N` is a constant and does not name a type",
+                "`X` does not name a type",
+                "`N` is a constant and does not name a type",
+                "`A` is a typedef and cannot be used as a union discriminator",
+            ]
+        );
+
+        assert!(messages(
+            "
+            typedef short Small;
+            typedef Small Tiny;
+            union U switch (Tiny) { case 1: Small s; };
+            enum E { A, B };
+            typedef E Alt;
+            union V switch (Alt) { case A: long a; case B: long b; };
+            "
+        )
+        .is_empty());
+    }
+
+    #[test]
+    fn struct_bases() {
+        assert_eq!(
+            messages(
+                "
+                struct A { long x; };
+                struct B : A { long y; };
+                struct C : B { long X; short z; };
+                struct D : Missing { long y; };
+                typedef long L;
+                struct E : L {};
+                struct F;
+                struct G : F { long g; };
+                struct F { long f; };
+                "
+            ),
+            vec![
+                "`X` collides with member `x` inherited from `A`",
+                "`Missing` does not name a type",
+                "`L` is a typedef and cannot be the base of a struct",
+                "`F` is a forward declared struct and cannot be the base of a struct",
+            ]
+        );
+        assert!(
+            messages("struct A { long x; }; struct B : ::A { long y; }; struct C : B {};")
+                .is_empty()
+        );
+    }
+
+    #[test]
+    fn struct_members() {
+        assert_eq!(
+            validate("struct S { long a, a; }; struct T { long b; short B; };"),
+            vec![
+                ("`a` is already declared in this scope".to_string(), 19..20),
+                (
+                    "`B` collides with `b` declared in the same scope".to_string(),
+                    50..51
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn exception_members() {
+        assert_eq!(
+            validate("exception E { long a; short a; }; exception F { long b; long B; };"),
+            vec![
+                ("`a` is already declared in this scope".to_string(), 28..29),
+                (
+                    "`B` collides with `b` declared in the same scope".to_string(),
+                    61..62
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn operations() {
+        let source = "interface I { oneway long f(in long a, out long b, inout long c) raises (E); void g(in long x, in Missing X); oneway void h(in long a); exception E {}; };";
+        assert_eq!(
+            validate(source),
+            vec![
+                ("oneway operation `f` must return void".to_string(), 21..25),
+                (
+                    "`b` is an `out` parameter, oneway operation `f` may only have `in` parameters"
+                        .to_string(),
+                    39..49
+                ),
+                (
+                    "`c` is an `inout` parameter, oneway operation `f` may only have `in` parameters"
+                        .to_string(),
+                    51..63
+                ),
+                (
+                    "oneway operation `f` cannot raise exceptions".to_string(),
+                    65..75
+                ),
+                ("`Missing` does not name a type".to_string(), 98..105),
+                (
+                    "`X` collides with `x` declared in the same scope".to_string(),
+                    95..107
+                ),
+            ]
+        );
+
+        assert!(messages(
+            "
+            interface J {
+                typedef sequence<long> Values;
+                Values fetch(in string<8> key);
+                attribute Values cached;
+            };
+            "
+        )
+        .is_empty());
+    }
+
+    #[test]
+    fn raises() {
+        let source = "exception E {}; struct S {}; interface I { exception F { Missing m; }; void g() raises (E, S); attribute long a getraises (F) setraises (X, I::F); readonly attribute long b raises (I); };";
+        assert_eq!(
+            validate(source),
+            vec![
+                ("`Missing` does not name a type".to_string(), 57..64),
+                (
+                    "`S` is a struct and cannot be listed by `raises`, only exceptions may be raised"
+                        .to_string(),
+                    91..92
+                ),
+                ("`X` does not name an exception".to_string(), 137..138),
+                (
+                    "`I` is an interface and cannot be listed by `raises`, only exceptions may be raised"
+                        .to_string(),
+                    181..182
+                ),
+            ]
+        );
+
+        // Exceptions are not types and cannot be used as one
+        assert_eq!(
+            messages("exception E {}; struct S { E e; };"),
+            vec!["`E` is an exception and does not name a type"]
+        );
+    }
+
+    #[test]
+    fn value_types() {
+        assert_eq!(
+            messages(
+                "
+                exception E {};
+                valuetype V {
+                    public Missing m;
+                    private long a[0];
+                    factory create(in long x, out long y, in long X) raises (V);
+                    void f(in Missing x);
+                };
+                abstract valuetype A { public long x; factory make(); };
+                valuetype Boxed V;
+                typedef V Same;
+                valuetype Boxed2 Same;
+                valuetype Boxed3 ValueBase;
+                valuetype Boxed4 sequence<V>;
+                "
+            ),
+            vec![
+                "`Missing` does not name a type",
+                "expected a positive integer, found 0",
+                "`X` collides with `x` declared in the same scope",
+                "`V` is a value type and cannot be listed by `raises`, only exceptions may be raised",
+                "`y` is an `out` parameter, factory `create` may only have `in` parameters",
+                "`Missing` does not name a type",
+                "abstract value type `A` cannot have state members",
+                "abstract value type `A` cannot have factories",
+                "`V` is a value type and cannot be boxed",
+                "`Same` is a value type and cannot be boxed",
+                "`ValueBase` cannot be boxed",
+            ]
+        );
+
+        assert!(messages(
+            "
+            struct Point { long x, y; };
+            valuetype Boxed Point;
+            valuetype Node {
+                public Node next;
+                public Boxed point;
+                factory create(in Boxed point) raises (Failed);
+                exception Failed {};
+            };
+            "
+        )
+        .is_empty());
+    }
+
+    #[test]
+    fn components() {
+        assert_eq!(
+            messages(
+                "
+                interface I {};
+                eventtype E {};
+                valuetype V {};
+                abstract valuetype A {};
+                struct S { long x; };
+                component C {
+                    provides E p;
+                    uses Missing q;
+                    emits I r;
+                    publishes Object s;
+                    consumes V t;
+                    attribute Missing u;
+                };
+                home H manages S primarykey A { finder find(out long x); };
+                home K manages C primarykey I { factory make(in Missing m); };
+                "
+            ),
+            vec![
+                "`provides` requires an interface, `E` is an event type",
+                "`Missing` does not name a type",
+                "`emits` requires an event type, `I` is an interface",
+                "`publishes` requires an event type, found `Object`",
+                "`consumes` requires an event type, `V` is a value type",
+                "`Missing` does not name a type",
+                "`S` is a struct and cannot be managed by a home",
+                "`A` is an abstract value type and cannot be a primary key",
+                "`x` is an `out` parameter, finder `find` may only have `in` parameters",
+                "`I` is an interface and cannot be a primary key",
+                "`Missing` does not name a type",
+            ]
+        );
+
+        assert!(messages(
+            "
+            interface I;
+            eventtype E;
+            component C;
+            valuetype Key { public long id; };
+            component C { provides I i; uses multiple Object o; consumes E e; };
+            home H manages C primarykey Key { finder find(in Key key); };
+            interface I {};
+            eventtype E {};
+            "
+        )
+        .is_empty());
+    }
+
+    #[test]
+    fn port_types() {
+        assert_eq!(
+            messages(
+                "
+                interface I {};
+                eventtype E {};
+                porttype P { uses multiple I readers; provides E wrong; };
+                porttype Q { port P p; };
+                porttype R { mirrorport R r; };
+                porttype S { port T t; };
+                porttype T { mirrorport S s; };
+                component C {
+                    port P p;
+                    mirrorport P q;
+                    mirrorport Q r;
+                    port I s;
+                    mirrorport Object t;
+                };
+                connector D { port Missing m; };
+                "
+            ),
+            vec![
+                "`provides` requires an interface, `E` is an event type",
+                "porttype `R` cannot contain a port of itself",
+                "porttype `S` cannot contain a port of itself",
+                "porttype `T` cannot contain a port of itself",
+                "`readers` is a `uses multiple` receptacle of `P` and cannot be reversed by `mirrorport`",
+                "`readers` is a `uses multiple` receptacle of `P` and cannot be reversed by `mirrorport`",
+                "`port` requires a porttype, `I` is an interface",
+                "`mirrorport` requires a porttype, found `Object`",
+                "`Missing` does not name a type",
+            ]
+        );
+
+        assert!(messages(
+            "
+            module M { interface Reader {}; interface Listener {}; };
+            porttype Data;
+            porttype Pair { port Data a; mirrorport Data b; };
+            porttype Data { provides M::Reader reader; uses M::Listener listener; };
+            component C { port Data d; port Pair p; };
+            connector Base { port Data d; };
+            connector Pipe : Base { mirrorport Data o; attribute long rate; };
+            "
+        )
+        .is_empty());
+    }
+
+    #[test]
+    fn annotations() {
+        let source = r#"
+            const long LIMIT = 10;
+            @extensibility(APPENDABLE) @topic(name = "points") struct Point {
+                @key @id(1) long x;
+                @range(min = 0, max = LIMIT) @default(3) @unit("m") long y;
+                @optional(FALSE) @verbatim(language = "c", text = "// z") long z;
+            };
+            @bit_bound(8) enum Color { @default_literal RED, @value(value = 4) GREEN };
+            @bit_bound(16) bitmask Flags { A, @position(value = 3) B };
+            interface I { @oneway void ping(); };
+            @service("CORBA") @ami interface J { };
+            @data_representation(XCDR1 | XCDR2) @custom(1, x = 2)
+            union U switch (long) { case 1: @id(4) long a; };
+        "#;
+        assert_eq!(messages(source), Vec::<String>::new());
+
+        let source = r#"@key struct S {
+                @id(-1) long a;
+                @optional(1) long b;
+                @range(0) long c;
+                @range(min = 0, maximum = 1) long d;
+                @unit(5) long e;
+                @id(value = 1, value = 2) long f;
+                @range(min = 0, 1) long g;
+            };
+            @extensibility(FLEXIBLE) @final(TRUE) enum E { @key A };
+            @data_representation(XCDR1 | CDR) @autoid(SEQUENTIAL)
+            union U switch (short) { case 1: @optional long a; };
+            @value(3) bitmask F { X };
+            @oneway interface I { @value(1) void op(@key in long p); };
+            @position(Z) bitmask G { @position(value = 1) Y, @position(value = Y) Z };
+        "#;
+        assert_eq!(
+            messages(source),
+            vec![
+                "`Y` does not name a constant",
+                "@key cannot be applied to a struct",
+                "value -1 is out of range for type unsigned long",
+                "expected a value of type boolean, found integer value 1",
+                "@range requires a value for `max`",
+                "@range has no parameter `maximum`",
+                "expected a string, found integer value 5",
+                "`value` of @id is given more than once",
+                "the parameters of @range cannot be given by position after one is given by name",
+                "`kind` of @extensibility must be one of FINAL, APPENDABLE, MUTABLE",
+                "@final takes no parameters",
+                "@key cannot be applied to an enumerator",
+                "`allowed_kinds` of @data_representation must be a combination of XCDR1, XML, XCDR2",
+                "@optional cannot be applied to a union element",
+                "@value cannot be applied to a bitmask",
+                "@oneway cannot be applied to an interface",
+                "@value cannot be applied to an operation",
+                "@key cannot be applied to a parameter",
+                "@position cannot be applied to a bitmask",
+            ]
+        );
+        assert_eq!(validate(source)[1].1, 0..4);
+    }
+
+    #[test]
+    fn valid_unions() {
+        assert!(messages(
+            "
+            const long TWO = 2;
+            union A switch (long) { case 1: case TWO: long x; default: string s; };
+            union B switch (char) { case 'a': long x; case 'b': short y; };
+            union C switch (boolean) { case TRUE: long x; case FALSE: short y; };
+            union D switch (octet) { case 0: long x; case 255: short y; default: char z; };
+            module M { const short N = 3; union E switch (short) { case N: long x; }; };
+            "
+        )
+        .is_empty());
+    }
+
+    #[test]
+    fn union_labels() {
+        let source = "union U switch (short) { case 1: long a; case 0x1: long b; case 2: default: long c; default: long d; };";
+        assert_eq!(
+            validate(source),
+            vec![
+                (
+                    "case label 1 is used more than once".to_string(),
+                    source.find("0x1").unwrap()..source.find("0x1").unwrap() + 3
+                ),
+                (
+                    "a union may have at most one default label".to_string(),
+                    source.rfind("default").unwrap()..source.rfind("default").unwrap() + 7
+                ),
+            ]
+        );
+
+        assert_eq!(
+            messages(
+                "
+                const string S = \"s\";
+                union U switch (unsigned short) {
+                    case -1: long a; case 'c': long b; case S: long c; case 70000: long d;
+                    case UNKNOWN: long e;
+                };
+                "
+            ),
+            vec![
+                "value -1 is out of range for type unsigned short",
+                "expected a value of type unsigned short, found character value 'c'",
+                "expected a value of type unsigned short, found string value \"s\"",
+                "value 70000 is out of range for type unsigned short",
+                "`UNKNOWN` does not name a constant",
+            ]
+        );
+    }
+
+    #[test]
+    fn union_default_coverage() {
+        assert_eq!(
+            messages("union U switch (boolean) { case TRUE: long a; case FALSE: long b; default: long c; };"),
+            vec!["the default label can never be selected, the case labels cover every value of boolean"]
+        );
+        assert!(
+            messages("union U switch (boolean) { case TRUE: long a; default: long c; };")
+                .is_empty()
+        );
+    }
+
+    #[test]
+    fn union_discriminators() {
+        assert_eq!(
+            messages(
+                "
+                struct S { long x; };
+                union A switch (double) { case 1: long a; };
+                union B switch (string) { case 1: long a; };
+                union C switch (S) { case 1: long a; };
+                union D switch (Nothing) { case 1: long a; };
+                "
+            ),
+            vec![
+                "a union discriminator must be an integer, char, wchar, boolean, octet or enum type",
+                "a union discriminator must be an integer, char, wchar, boolean, octet or enum type",
+                "`S` is a struct and cannot be used as a union discriminator",
+                "`Nothing` does not name a type",
+            ]
+        );
+    }
+
+    #[test]
+    fn enum_discriminators() {
+        assert!(messages(
+            "
+            module M { enum Color { RED, GREEN, BLUE }; };
+            union A switch (M::Color) { case M::RED: long a; case M::GREEN: short b; default: char c; };
+            union B switch (::M::Color) { case M::RED: case M::GREEN: case M::BLUE: long a; };
+            "
+        )
+        .is_empty());
+
+        assert_eq!(
+            messages(
+                "
+                enum Color { RED, GREEN };
+                enum Shape { SQUARE };
+                const Color FAVOURITE = GREEN;
+                union A switch (Color) {
+                    case RED: long a; case SQUARE: long b; case 1: long c; case FAVOURITE: long d;
+                    case GREEN: long e; default: long f;
+                };
+                "
+            ),
+            vec![
+                "expected a value of type Color, found enumerator value SQUARE",
+                "expected a value of type Color, found integer value 1",
+                "case label GREEN is used more than once",
+                "the default label can never be selected, the case labels cover every value of Color",
+            ]
+        );
+    }
+
+    #[test]
+    fn union_elements() {
+        assert_eq!(
+            messages("union U switch (long) { case 1: long a; case 2: short A; case 3: char a; };"),
+            vec![
+                "`A` collides with `a` declared in the same scope",
+                "`a` is already declared in this scope",
+            ]
+        );
+    }
+}